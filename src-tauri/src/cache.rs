@@ -0,0 +1,192 @@
+//! CPU 缓存层级的平台无关部分：去重排序，以及 x86 上基于 CPUID 的回退实现。
+//!
+//! CPUID 只描述当前核心看到的缓存：Intel 用 leaf 4，AMD/Hygon 用 leaf 0x8000001D，
+//! 两者每个子叶的寄存器布局相同。它只给出共享该缓存的逻辑 CPU 数量，
+//! 给不出具体编号，所以回退结果中每种缓存只有一条、`shared_cpus` 为空。
+
+use anyhow::anyhow;
+
+use crate::CacheInfo;
+
+/// 合并重复的缓存实例（同一级、同类型、同一组 CPU），并按级别、类型、首个 CPU 排序
+pub fn dedup(mut caches: Vec<CacheInfo>) -> Vec<CacheInfo> {
+    for c in &mut caches {
+        c.shared_cpus.sort_unstable();
+        c.shared_cpus.dedup();
+    }
+    caches.sort_by(|a, b| {
+        (a.level, kind_order(&a.kind), a.shared_cpus.first()).cmp(&(
+            b.level,
+            kind_order(&b.kind),
+            b.shared_cpus.first(),
+        ))
+    });
+    caches
+        .dedup_by(|a, b| a.level == b.level && a.kind == b.kind && a.shared_cpus == b.shared_cpus);
+    caches
+}
+
+/// 同一级里指令缓存排在数据缓存前面，与常见的 L1i / L1d 展示顺序一致
+fn kind_order(kind: &str) -> u8 {
+    match kind {
+        "instruction" => 0,
+        "data" => 1,
+        _ => 2,
+    }
+}
+
+/// 解析一个 CPUID 缓存子叶（leaf 4 / 0x8000001D）；类型为 0 表示没有更多缓存
+pub fn decode_cpuid_leaf(eax: u32, ebx: u32, ecx: u32) -> Option<CacheInfo> {
+    let kind = match eax & 0x1f {
+        1 => "data",
+        2 => "instruction",
+        3 => "unified",
+        _ => return None,
+    };
+    let line_size = (ebx & 0xfff) + 1;
+    let partitions = ((ebx >> 12) & 0x3ff) + 1;
+    let ways = ((ebx >> 22) & 0x3ff) + 1;
+    let sets = ecx.wrapping_add(1);
+    Some(CacheInfo {
+        level: (eax >> 5) & 0x7,
+        kind: kind.to_string(),
+        size_bytes: ways as u64 * partitions as u64 * line_size as u64 * sets as u64,
+        ways: Some(ways),
+        line_size: Some(line_size),
+        sets: Some(sets),
+        shared_by: ((eax >> 14) & 0xfff) + 1,
+        shared_cpus: Vec::new(),
+    })
+}
+
+/// 通过 CPUID 读取当前核心的缓存描述
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn read_cpuid() -> anyhow::Result<Vec<CacheInfo>> {
+    // leaf 4 / 0x8000001D 在查询前先用 leaf 0 / 0x80000000 确认支持
+    let vendor = cpuid(0, 0);
+    let max_basic = vendor.eax;
+    // 厂商字符串按 EBX、EDX、ECX 的顺序拼接
+    let mut name = Vec::with_capacity(12);
+    for reg in [vendor.ebx, vendor.edx, vendor.ecx] {
+        name.extend_from_slice(&reg.to_le_bytes());
+    }
+    let leaf = match name.as_slice() {
+        b"AuthenticAMD" | b"HygonGenuine" => {
+            let max_ext = cpuid(0x8000_0000, 0).eax;
+            (max_ext >= 0x8000_001d).then_some(0x8000_001d)
+        }
+        _ => (max_basic >= 4).then_some(4),
+    }
+    .ok_or_else(|| anyhow!("cpuid does not report deterministic cache parameters"))?;
+
+    let mut caches = Vec::new();
+    // 子叶数量没有上限字段，遇到类型为 0 的子叶即结束；这里加个保险上限
+    for sub in 0..16 {
+        let r = cpuid(leaf, sub);
+        match decode_cpuid_leaf(r.eax, r.ebx, r.ecx) {
+            Some(cache) => caches.push(cache),
+            None => break,
+        }
+    }
+    Ok(dedup(caches))
+}
+
+/// `__cpuid_count` 在较新的标准库中是安全函数，旧工具链上仍要求 unsafe，两者都要能编译
+#[cfg(target_arch = "x86")]
+#[allow(unused_unsafe)]
+pub fn cpuid(leaf: u32, sub_leaf: u32) -> std::arch::x86::CpuidResult {
+    // SAFETY: CPUID 不读写内存；本程序支持的 x86 处理器都实现了该指令
+    unsafe { std::arch::x86::__cpuid_count(leaf, sub_leaf) }
+}
+
+#[cfg(target_arch = "x86_64")]
+#[allow(unused_unsafe)]
+pub fn cpuid(leaf: u32, sub_leaf: u32) -> std::arch::x86_64::CpuidResult {
+    // SAFETY: CPUID 不读写内存，x86_64 上总是可用
+    unsafe { std::arch::x86_64::__cpuid_count(leaf, sub_leaf) }
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn read_cpuid() -> anyhow::Result<Vec<CacheInfo>> {
+    Err(anyhow!("cpu caches are not supported on this platform"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cpuid_leaf_4() {
+        // Skylake 的 L1d：8 路、64 字节行、64 组，两个超线程共享
+        let l1d = decode_cpuid_leaf(0x1c00_4121, 0x01c0_003f, 0x3f).unwrap();
+        assert_eq!(l1d.level, 1);
+        assert_eq!(l1d.kind, "data");
+        assert_eq!(l1d.size_bytes, 32 * 1024);
+        assert_eq!(
+            (l1d.ways, l1d.line_size, l1d.sets),
+            (Some(8), Some(64), Some(64))
+        );
+        assert_eq!(l1d.shared_by, 2);
+        assert!(l1d.shared_cpus.is_empty());
+
+        let l1i = decode_cpuid_leaf(0x1c00_4122, 0x01c0_003f, 0x3f).unwrap();
+        assert_eq!(l1i.kind, "instruction");
+
+        // 16 路、8192 组的 8 MB L3，16 个逻辑 CPU 共享
+        let l3 = decode_cpuid_leaf(0x1c03_c163, 0x03c0_003f, 0x1fff).unwrap();
+        assert_eq!((l3.level, l3.kind.as_str()), (3, "unified"));
+        assert_eq!(l3.size_bytes, 8 * 1024 * 1024);
+        assert_eq!(l3.sets, Some(8192));
+        assert_eq!(l3.shared_by, 16);
+
+        // 类型 0 表示没有更多缓存
+        assert!(decode_cpuid_leaf(0x1c00_0000, 0, 0).is_none());
+        assert!(decode_cpuid_leaf(0x0000_0004, 0x01c0_003f, 0x3f).is_none());
+    }
+
+    #[test]
+    fn dedup_sorts_and_merges() {
+        let cache = |level, kind: &str, cpus: &[u32]| CacheInfo {
+            level,
+            kind: kind.to_string(),
+            size_bytes: 1024,
+            ways: None,
+            line_size: None,
+            sets: None,
+            shared_by: cpus.len() as u32,
+            shared_cpus: cpus.to_vec(),
+        };
+        let caches = dedup(vec![
+            cache(3, "unified", &[1, 0]),
+            cache(1, "data", &[1]),
+            cache(1, "instruction", &[1]),
+            cache(1, "data", &[0]),
+            cache(3, "unified", &[0, 1, 1]),
+            cache(1, "instruction", &[0]),
+        ]);
+        let summary: Vec<(u32, &str, &[u32])> = caches
+            .iter()
+            .map(|c| (c.level, c.kind.as_str(), c.shared_cpus.as_slice()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (1, "instruction", &[0][..]),
+                (1, "instruction", &[1]),
+                (1, "data", &[0]),
+                (1, "data", &[1]),
+                (3, "unified", &[0, 1]),
+            ]
+        );
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn cpuid_on_this_machine() {
+        // 虚拟机可能隐藏缓存子叶，只要有结果就必须是排好序的
+        if let Ok(caches) = read_cpuid() {
+            assert!(caches.windows(2).all(|w| w[0].level <= w[1].level));
+            assert!(caches.iter().all(|c| c.size_bytes > 0));
+        }
+    }
+}
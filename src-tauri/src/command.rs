@@ -0,0 +1,215 @@
+//! 外部命令执行抽象。
+//!
+//! 所有解析命令输出的采集函数都通过 `CommandRunner` 调用命令，而不是直接使用
+//! `std::process::Command`，这样解析逻辑可以对着录制好的输出回放：
+//!
+//! - [`SystemRunner`]：真实执行命令
+//! - [`RecordingRunner`]：真实执行并把 stdout 写入目录，用于采集新的样本
+//! - [`ReplayRunner`]：从目录读取之前录制的 stdout，不执行任何命令
+//!
+//! 录制文件按 [`fixture_name`] 命名，例如 `route -n get default` 对应 `route_-n_get_default.txt`。
+//! 旁边同名的 `.stderr` 文件（如 `route_-n_get_default.stderr`）表示命令以非零状态退出，
+//! 内容为 stderr；没有它时视为执行成功。
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Arc;
+
+/// 回放目录：设置后所有命令都从该目录读取录制输出
+pub const REPLAY_DIR_ENV: &str = "CPU_LIGHT_COMMAND_FIXTURES";
+/// 录制目录：设置后真实执行命令并把输出写入该目录
+pub const RECORD_DIR_ENV: &str = "CPU_LIGHT_RECORD_COMMANDS";
+
+pub type SharedRunner = Arc<dyn CommandRunner>;
+
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+pub trait CommandRunner: Send + Sync {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput>;
+
+    /// 执行命令并返回去掉首尾空白的 stdout，失败或输出为空时返回 None
+    fn stdout(&self, program: &str, args: &[&str]) -> Option<String> {
+        let out = self.run(program, args).ok()?;
+        let s = out.stdout.trim().to_string();
+        (!s.is_empty()).then_some(s)
+    }
+}
+
+/// 按环境变量选择执行方式，默认真实执行
+pub fn from_env() -> SharedRunner {
+    if let Some(dir) = std::env::var_os(REPLAY_DIR_ENV) {
+        log::info!("Replaying command output from {:?}", dir);
+        return Arc::new(ReplayRunner::new(dir));
+    }
+    if let Some(dir) = std::env::var_os(RECORD_DIR_ENV) {
+        log::info!("Recording command output to {:?}", dir);
+        return Arc::new(RecordingRunner::new(dir));
+    }
+    Arc::new(SystemRunner)
+}
+
+/// 录制文件名：程序名（去掉路径）加参数，非 `[A-Za-z0-9._-]` 字符替换为 `_`
+pub fn fixture_name(program: &str, args: &[&str]) -> String {
+    let program = Path::new(program)
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| program.to_string());
+    let key = std::iter::once(program.as_str())
+        .chain(args.iter().copied())
+        .collect::<Vec<_>>()
+        .join("_");
+    let key: String = key
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}.txt", key)
+}
+
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        let out = Command::new(program).args(args).output()?;
+        Ok(CommandOutput {
+            success: out.status.success(),
+            stdout: String::from_utf8_lossy(&out.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&out.stderr).into_owned(),
+        })
+    }
+}
+
+pub struct RecordingRunner {
+    dir: PathBuf,
+}
+
+impl RecordingRunner {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl CommandRunner for RecordingRunner {
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        let out = SystemRunner.run(program, args)?;
+        // 只录制成功的输出，录制失败不影响正常采集
+        if out.success {
+            let path = self.dir.join(fixture_name(program, args));
+            if let Err(e) =
+                fs::create_dir_all(&self.dir).and_then(|_| fs::write(&path, &out.stdout))
+            {
+                log::warn!("failed to record {}: {}", path.display(), e);
+            }
+        }
+        Ok(out)
+    }
+}
+
+pub struct ReplayRunner {
+    dir: PathBuf,
+}
+
+impl ReplayRunner {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl CommandRunner for ReplayRunner {
+    /// 没有录制文件时返回 `NotFound`，与命令不存在时的行为一致
+    fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+        let path = self.dir.join(fixture_name(program, args));
+        let stdout = fs::read_to_string(&path)?;
+        let stderr = fs::read_to_string(path.with_extension("stderr")).ok();
+        Ok(CommandOutput {
+            success: stderr.is_none(),
+            stdout,
+            stderr: stderr.unwrap_or_default(),
+        })
+    }
+}
+
+/// 仓库里录制的 macOS 命令输出，解析函数的测试都对着它回放
+#[cfg(test)]
+pub fn macos_fixtures() -> ReplayRunner {
+    ReplayRunner::new(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fixtures/commands/macos"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fixture_names() {
+        assert_eq!(
+            fixture_name("route", &["-n", "get", "default"]),
+            "route_-n_get_default.txt"
+        );
+        assert_eq!(
+            fixture_name(
+                "/usr/sbin/system_profiler",
+                &["-json", "SPHardwareDataType"]
+            ),
+            "system_profiler_-json_SPHardwareDataType.txt"
+        );
+        assert_eq!(fixture_name("scutil", &["--dns"]), "scutil_--dns.txt");
+        assert_eq!(
+            fixture_name("sysctl", &["hw", "machdep.cpu"]),
+            "sysctl_hw_machdep.cpu.txt"
+        );
+    }
+
+    #[test]
+    fn replay_reads_recorded_stdout() {
+        let runner = macos_fixtures();
+        let out = runner.run("route", &["-n", "get", "default"]).unwrap();
+        assert!(out.success);
+        assert!(out.stdout.contains("gateway: 192.168.1.1"));
+        assert!(out.stderr.is_empty());
+        // stdout() 去掉首尾空白
+        assert_eq!(
+            runner.stdout("sysctl", &["-n", "kern.boottime"]).as_deref(),
+            Some("{ sec = 1759194764, usec = 287541 } Tue Sep 30 09:12:44 2025")
+        );
+    }
+
+    #[test]
+    fn replay_stderr_marks_failure() {
+        let tmp = tempfile::tempdir().unwrap();
+        fs::write(tmp.path().join("sysctl_hw_machdep.cpu.txt"), "partial\n").unwrap();
+        fs::write(
+            tmp.path().join("sysctl_hw_machdep.cpu.stderr"),
+            "sysctl: unknown oid\n",
+        )
+        .unwrap();
+        let out = ReplayRunner::new(tmp.path())
+            .run("sysctl", &["hw", "machdep.cpu"])
+            .unwrap();
+        assert!(!out.success);
+        assert_eq!(out.stdout, "partial\n");
+        assert_eq!(out.stderr, "sysctl: unknown oid\n");
+    }
+
+    #[test]
+    fn replay_missing_fixture_is_not_found() {
+        let runner = macos_fixtures();
+        let err = runner.run("no-such-command", &["--flag"]).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert_eq!(runner.stdout("no-such-command", &["--flag"]), None);
+    }
+}
@@ -0,0 +1,153 @@
+//! 块设备 I/O：平台实现给出累计计数，采样线程用相邻两次读数之差算出速率。
+//!
+//! 一块物理设备上的分区、LVM/LUKS 映射或 APFS 卷都归到同一个设备下，
+//! 挂载点由平台实现一并给出，用来把 `DiskInfo` 对应回设备。
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use nix::sys::statvfs::statvfs;
+use serde::{Deserialize, Serialize};
+
+use crate::DiskIo;
+
+/// 一个块设备的累计计数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiskCounters {
+    pub device: String,
+    pub mount_points: Vec<String>,
+    pub read_bytes: u64,
+    pub write_bytes: u64,
+    /// 完成的读、写请求数
+    pub reads: u64,
+    pub writes: u64,
+    /// 设备有请求在处理的累计毫秒数（Linux io_ticks）
+    pub busy_ms: Option<u64>,
+    /// 所有请求耗时之和（毫秒），对时间求导即平均队列深度
+    pub queue_ms: Option<u64>,
+}
+
+/// 由上一次与本次的累计计数算出各设备速率；上一次没有的设备跳过
+pub fn rates(
+    previous: &HashMap<String, DiskCounters>,
+    current: &[DiskCounters],
+    elapsed_secs: f64,
+) -> Vec<DiskIo> {
+    if elapsed_secs <= 0.0 {
+        return Vec::new();
+    }
+    current
+        .iter()
+        .filter_map(|cur| {
+            let prev = previous.get(&cur.device)?;
+            // 设备重新接入后计数会归零，这一次按 0 处理
+            let delta = |c: u64, p: u64| c.saturating_sub(p) as f64;
+            let per_sec = |c: u64, p: u64| delta(c, p) / elapsed_secs;
+            let elapsed_ms = elapsed_secs * 1000.0;
+            Some(DiskIo {
+                device: cur.device.clone(),
+                mount_points: cur.mount_points.clone(),
+                read_bytes_per_sec: per_sec(cur.read_bytes, prev.read_bytes) as u64,
+                write_bytes_per_sec: per_sec(cur.write_bytes, prev.write_bytes) as u64,
+                read_iops: per_sec(cur.reads, prev.reads) as f32,
+                write_iops: per_sec(cur.writes, prev.writes) as f32,
+                queue_depth: cur
+                    .queue_ms
+                    .zip(prev.queue_ms)
+                    .map(|(c, p)| (delta(c, p) / elapsed_ms) as f32),
+                busy_percent: cur
+                    .busy_ms
+                    .zip(prev.busy_ms)
+                    .map(|(c, p)| (delta(c, p) / elapsed_ms * 100.0).min(100.0) as f32),
+            })
+        })
+        .collect()
+}
+
+/// 挂载点所在文件系统的 inode 总数与剩余数；总数为 0 表示文件系统不使用 inode
+pub fn inodes(mount_point: &Path) -> Option<(u64, u64)> {
+    let stat = statvfs(mount_point).ok()?;
+    // fsfilcnt_t 在 Linux 上为 u64、在 macOS 上为 u32
+    #[allow(clippy::unnecessary_cast)]
+    let (total, free) = (stat.files() as u64, stat.files_free() as u64);
+    (total > 0).then_some((total, free))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters(device: &str, n: u64, busy_ms: Option<u64>, queue_ms: Option<u64>) -> DiskCounters {
+        DiskCounters {
+            device: device.to_string(),
+            mount_points: vec!["/".to_string()],
+            read_bytes: n * 4096,
+            write_bytes: n * 8192,
+            reads: n,
+            writes: n * 2,
+            busy_ms,
+            queue_ms,
+        }
+    }
+
+    fn previous(c: DiskCounters) -> HashMap<String, DiskCounters> {
+        HashMap::from([(c.device.clone(), c)])
+    }
+
+    #[test]
+    fn rates_from_deltas() {
+        let prev = previous(counters("nvme0n1", 100, Some(1000), Some(5000)));
+        let io = rates(
+            &prev,
+            &[
+                counters("nvme0n1", 300, Some(1500), Some(6000)),
+                // 上一次没有的设备只建立基准
+                counters("sda", 10, Some(0), Some(0)),
+            ],
+            2.0,
+        );
+        assert_eq!(io.len(), 1);
+        let d = &io[0];
+        assert_eq!(d.device, "nvme0n1");
+        assert_eq!(d.mount_points, ["/"]);
+        assert_eq!(d.read_bytes_per_sec, 100 * 4096);
+        assert_eq!(d.write_bytes_per_sec, 100 * 8192);
+        assert_eq!((d.read_iops, d.write_iops), (100.0, 200.0));
+        // 2 秒内请求耗时共 1000 ms -> 平均队列深度 0.5；忙碌 500 ms -> 25%
+        assert_eq!(d.queue_depth, Some(0.5));
+        assert_eq!(d.busy_percent, Some(25.0));
+    }
+
+    #[test]
+    fn busy_percent_clamped() {
+        // io_ticks 与采样时刻不完全同步，差值可能略大于经过的时间
+        let prev = previous(counters("sda", 0, Some(0), Some(0)));
+        let io = rates(&prev, &[counters("sda", 0, Some(1100), Some(4000))], 1.0);
+        assert_eq!(io[0].busy_percent, Some(100.0));
+        assert_eq!(io[0].queue_depth, Some(4.0));
+    }
+
+    #[test]
+    fn device_reset_and_bad_elapsed() {
+        let prev = previous(counters("sdb", 500, Some(9000), Some(9000)));
+        // 重新接入后计数归零，这一次按 0 处理
+        let io = rates(&prev, &[counters("sdb", 5, Some(10), Some(10))], 1.0);
+        assert_eq!(io[0].read_bytes_per_sec, 0);
+        assert_eq!(io[0].write_iops, 0.0);
+        assert_eq!(io[0].busy_percent, Some(0.0));
+        assert_eq!(io[0].queue_depth, Some(0.0));
+
+        let cur = [counters("sdb", 600, Some(9500), Some(9500))];
+        assert!(rates(&prev, &cur, 0.0).is_empty());
+        assert!(rates(&prev, &cur, -1.0).is_empty());
+    }
+
+    #[test]
+    fn missing_busy_counters() {
+        // macOS 没有忙碌时间；一边缺失也不计算
+        let prev = previous(counters("disk0", 0, None, Some(0)));
+        let io = rates(&prev, &[counters("disk0", 10, Some(10), Some(100))], 1.0);
+        assert_eq!(io[0].busy_percent, None);
+        assert_eq!(io[0].queue_depth, Some(0.1));
+    }
+}
@@ -0,0 +1,345 @@
+//! 指令集扩展检测。
+//!
+//! 每个扩展有两路信息来源：
+//! - 硬件：x86 上直接执行 CPUID；aarch64 用户态读不到 ID 寄存器，只能依赖系统报告
+//! - 系统：Linux 的 /proc/cpuinfo `flags` / `Features`，macOS 的 `sysctl hw.optional.*`，
+//!   内核或固件禁用的扩展不会出现在这里
+//!
+//! 硬件支持但系统没有报告（或 XCR0 没有打开对应的 XSAVE 状态）的扩展标记为已禁用，
+//! 例如 BIOS 关闭了 VT-x，或内核以 `noxsave`、`clearcpuid` 启动。
+
+use std::collections::HashSet;
+
+use crate::{CpuFeature, CpuFeatures, FeatureCategory};
+
+/// AVX 需要 XCR0 打开 SSE、AVX 状态
+const XCR0_AVX: u64 = 0b110;
+/// AVX-512 还需要 opmask、ZMM_Hi256、Hi16_ZMM 状态
+const XCR0_AVX512: u64 = 0b1110_0110;
+
+#[derive(Debug, Clone, Copy)]
+enum Reg {
+    Ebx,
+    Ecx,
+    Edx,
+}
+
+/// 某个扩展在 CPUID 中的位置：(leaf, reg, bit)，leaf 7 固定取子叶 0
+type CpuidBit = (u32, Reg, u32);
+
+pub struct FeatureDef {
+    pub category: &'static str,
+    pub name: &'static str,
+    /// /proc/cpuinfo 中的名字
+    pub linux: &'static str,
+    /// `hw.optional.` 之后的键名，空串表示 macOS 不报告
+    pub macos: &'static str,
+    cpuid: Option<CpuidBit>,
+    xcr0: u64,
+}
+
+const fn x86(
+    category: &'static str,
+    name: &'static str,
+    linux: &'static str,
+    macos: &'static str,
+    cpuid: CpuidBit,
+    xcr0: u64,
+) -> FeatureDef {
+    FeatureDef {
+        category,
+        name,
+        linux,
+        macos,
+        cpuid: Some(cpuid),
+        xcr0,
+    }
+}
+
+const fn arm(
+    category: &'static str,
+    name: &'static str,
+    linux: &'static str,
+    macos: &'static str,
+) -> FeatureDef {
+    FeatureDef {
+        category,
+        name,
+        linux,
+        macos,
+        cpuid: None,
+        xcr0: 0,
+    }
+}
+
+const EXT1: u32 = 0x8000_0001;
+
+#[rustfmt::skip]
+pub const X86_FEATURES: &[FeatureDef] = &[
+    x86("SSE", "SSE", "sse", "sse", (1, Reg::Edx, 25), 0),
+    x86("SSE", "SSE2", "sse2", "sse2", (1, Reg::Edx, 26), 0),
+    x86("SSE", "SSE3", "pni", "sse3", (1, Reg::Ecx, 0), 0),
+    x86("SSE", "SSSE3", "ssse3", "supplementalsse3", (1, Reg::Ecx, 9), 0),
+    x86("SSE", "SSE4.1", "sse4_1", "sse4_1", (1, Reg::Ecx, 19), 0),
+    x86("SSE", "SSE4.2", "sse4_2", "sse4_2", (1, Reg::Ecx, 20), 0),
+    x86("AVX", "AVX", "avx", "avx1_0", (1, Reg::Ecx, 28), XCR0_AVX),
+    x86("AVX", "AVX2", "avx2", "avx2_0", (7, Reg::Ebx, 5), XCR0_AVX),
+    x86("AVX", "FMA3", "fma", "fma", (1, Reg::Ecx, 12), XCR0_AVX),
+    x86("AVX", "F16C", "f16c", "f16c", (1, Reg::Ecx, 29), XCR0_AVX),
+    x86("AVX-512", "AVX-512F", "avx512f", "avx512f", (7, Reg::Ebx, 16), XCR0_AVX512),
+    x86("AVX-512", "AVX-512DQ", "avx512dq", "avx512dq", (7, Reg::Ebx, 17), XCR0_AVX512),
+    x86("AVX-512", "AVX-512CD", "avx512cd", "avx512cd", (7, Reg::Ebx, 28), XCR0_AVX512),
+    x86("AVX-512", "AVX-512BW", "avx512bw", "avx512bw", (7, Reg::Ebx, 30), XCR0_AVX512),
+    x86("AVX-512", "AVX-512VL", "avx512vl", "avx512vl", (7, Reg::Ebx, 31), XCR0_AVX512),
+    x86("AVX-512", "AVX-512IFMA", "avx512ifma", "avx512ifma", (7, Reg::Ebx, 21), XCR0_AVX512),
+    x86("AVX-512", "AVX-512VBMI", "avx512vbmi", "avx512vbmi", (7, Reg::Ecx, 1), XCR0_AVX512),
+    x86("AVX-512", "AVX-512VNNI", "avx512_vnni", "", (7, Reg::Ecx, 11), XCR0_AVX512),
+    x86("Crypto", "AES-NI", "aes", "aes", (1, Reg::Ecx, 25), 0),
+    x86("Crypto", "PCLMULQDQ", "pclmulqdq", "", (1, Reg::Ecx, 1), 0),
+    x86("Crypto", "SHA", "sha_ni", "", (7, Reg::Ebx, 29), 0),
+    x86("Crypto", "RDRAND", "rdrand", "rdrand", (1, Reg::Ecx, 30), 0),
+    x86("Crypto", "RDSEED", "rdseed", "rdseed", (7, Reg::Ebx, 18), 0),
+    x86("Bit manipulation", "BMI1", "bmi1", "bmi1", (7, Reg::Ebx, 3), 0),
+    x86("Bit manipulation", "BMI2", "bmi2", "bmi2", (7, Reg::Ebx, 8), 0),
+    x86("Bit manipulation", "POPCNT", "popcnt", "", (1, Reg::Ecx, 23), 0),
+    x86("Bit manipulation", "LZCNT", "abm", "", (EXT1, Reg::Ecx, 5), 0),
+    x86("Bit manipulation", "ADX", "adx", "adx", (7, Reg::Ebx, 19), 0),
+    x86("Virtualization", "VT-x", "vmx", "", (1, Reg::Ecx, 5), 0),
+    x86("Virtualization", "AMD-V", "svm", "", (EXT1, Reg::Ecx, 2), 0),
+];
+
+pub const ARM_FEATURES: &[FeatureDef] = &[
+    arm("SIMD", "NEON", "asimd", "neon"),
+    arm("SIMD", "FP16", "fphp", "arm.FEAT_FP16"),
+    arm("SIMD", "DotProd", "asimddp", "arm.FEAT_DotProd"),
+    arm("SIMD", "BF16", "bf16", "arm.FEAT_BF16"),
+    arm("SIMD", "I8MM", "i8mm", "arm.FEAT_I8MM"),
+    arm("SVE", "SVE", "sve", ""),
+    arm("SVE", "SVE2", "sve2", ""),
+    arm("SVE", "SME", "sme", "arm.FEAT_SME"),
+    arm("Crypto", "AES", "aes", "arm.FEAT_AES"),
+    arm("Crypto", "PMULL", "pmull", "arm.FEAT_PMULL"),
+    arm("Crypto", "SHA1", "sha1", "arm.FEAT_SHA1"),
+    arm("Crypto", "SHA256", "sha2", "arm.FEAT_SHA256"),
+    arm("Crypto", "SHA512", "sha512", "arm.FEAT_SHA512"),
+    arm("Crypto", "SHA3", "sha3", "arm.FEAT_SHA3"),
+    arm("Crypto", "CRC32", "crc32", "armv8_crc32"),
+    arm("Atomics", "LSE", "atomics", "arm.FEAT_LSE"),
+    arm("Atomics", "LSE2", "uscat", "arm.FEAT_LSE2"),
+    arm("Atomics", "LRCPC", "lrcpc", "arm.FEAT_LRCPC"),
+    arm("Security", "PAuth", "paca", "arm.FEAT_PAuth"),
+    arm("Security", "BTI", "bti", "arm.FEAT_BTI"),
+];
+
+/// CPUID 中与扩展相关的寄存器，以及系统实际打开的 XSAVE 状态
+#[derive(Debug, Clone, Default)]
+pub struct CpuidSnapshot {
+    pub leaf1_ecx: u32,
+    pub leaf1_edx: u32,
+    pub leaf7_ebx: u32,
+    pub leaf7_ecx: u32,
+    pub ext1_ecx: u32,
+    /// OSXSAVE 未置位时为 None，此时所有依赖 XSAVE 的扩展都不可用
+    pub xcr0: Option<u64>,
+}
+
+impl CpuidSnapshot {
+    fn has(&self, (leaf, reg, bit): CpuidBit) -> bool {
+        let value = match (leaf, reg) {
+            (1, Reg::Ecx) => self.leaf1_ecx,
+            (1, Reg::Edx) => self.leaf1_edx,
+            (7, Reg::Ebx) => self.leaf7_ebx,
+            (7, Reg::Ecx) => self.leaf7_ecx,
+            (EXT1, Reg::Ecx) => self.ext1_ecx,
+            _ => 0,
+        };
+        value & (1 << bit) != 0
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub fn read_cpuid() -> Option<CpuidSnapshot> {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::_xgetbv;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::_xgetbv;
+
+    use crate::cache::cpuid;
+
+    #[target_feature(enable = "xsave")]
+    unsafe fn xgetbv0() -> u64 {
+        _xgetbv(0)
+    }
+
+    let max_basic = cpuid(0, 0).eax;
+    let max_ext = cpuid(0x8000_0000, 0).eax;
+    let leaf1 = cpuid(1, 0);
+    let leaf7 = (max_basic >= 7).then(|| cpuid(7, 0));
+    let osxsave = leaf1.ecx & (1 << 27) != 0;
+
+    Some(CpuidSnapshot {
+        leaf1_ecx: leaf1.ecx,
+        leaf1_edx: leaf1.edx,
+        leaf7_ebx: leaf7.map_or(0, |r| r.ebx),
+        leaf7_ecx: leaf7.map_or(0, |r| r.ecx),
+        ext1_ecx: if max_ext >= EXT1 {
+            cpuid(EXT1, 0).ecx
+        } else {
+            0
+        },
+        // SAFETY: OSXSAVE 置位说明 CPU 支持 XGETBV 且系统已启用
+        xcr0: osxsave.then(|| unsafe { xgetbv0() }),
+    })
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+pub fn read_cpuid() -> Option<CpuidSnapshot> {
+    None
+}
+
+/// 按目标架构选择扩展表；`os` 返回系统是否报告了该扩展，None 表示该系统不报告这一项
+pub fn detect(
+    arch: &str,
+    os: &dyn Fn(&FeatureDef) -> Option<bool>,
+    cpuid: Option<&CpuidSnapshot>,
+) -> CpuFeatures {
+    let table = match arch {
+        "x86" | "x86_64" => X86_FEATURES,
+        "aarch64" => ARM_FEATURES,
+        _ => &[],
+    };
+
+    let mut categories: Vec<FeatureCategory> = Vec::new();
+    for def in table {
+        let feature = evaluate(def, os(def), cpuid);
+        match categories.iter_mut().find(|c| c.name == def.category) {
+            Some(c) => c.features.push(feature),
+            None => categories.push(FeatureCategory {
+                name: def.category.to_string(),
+                features: vec![feature],
+            }),
+        }
+    }
+
+    CpuFeatures {
+        arch: arch.to_string(),
+        categories,
+    }
+}
+
+fn evaluate(def: &FeatureDef, os: Option<bool>, cpuid: Option<&CpuidSnapshot>) -> CpuFeature {
+    let hw = cpuid.zip(def.cpuid).map(|(c, bit)| c.has(bit));
+    let supported = hw.or(os).unwrap_or(false);
+
+    // 只有拿到 CPUID 时才能判断 XSAVE 状态
+    let xsave_ok = match cpuid {
+        Some(c) if def.xcr0 != 0 => c.xcr0.is_some_and(|x| x & def.xcr0 == def.xcr0),
+        _ => true,
+    };
+    let os_ok = os.unwrap_or(true);
+
+    let disabled_reason = if !supported {
+        None
+    } else if !xsave_ok {
+        Some("the OS has not enabled the XSAVE state this extension needs".to_string())
+    } else if !os_ok {
+        Some("supported by the CPU but disabled by the OS or firmware".to_string())
+    } else {
+        None
+    };
+
+    CpuFeature {
+        name: def.name.to_string(),
+        supported,
+        enabled: supported && xsave_ok && os_ok,
+        disabled_reason,
+    }
+}
+
+/// /proc/cpuinfo 第一个处理器块的 `flags`（x86）或 `Features`（ARM）；
+/// 两者都没有时（部分虚拟机、非常规内核）返回 None，调用方应把各扩展视为未知而不是不支持
+pub fn parse_cpuinfo_flags(cpuinfo: &str) -> Option<HashSet<String>> {
+    cpuinfo
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(k, _)| matches!(k.trim(), "flags" | "Features"))
+        .map(|(_, v)| v.split_whitespace().map(str::to_string).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTEL_CPUINFO: &str = include_str!("../fixtures/sysfs/intel-laptop/cpuinfo");
+    const PI5_CPUINFO: &str = include_str!("../fixtures/sysfs/raspberry-pi-5/cpuinfo");
+
+    fn feature<'a>(features: &'a CpuFeatures, name: &str) -> &'a CpuFeature {
+        features
+            .categories
+            .iter()
+            .flat_map(|c| &c.features)
+            .find(|f| f.name == name)
+            .unwrap_or_else(|| panic!("no feature {name}"))
+    }
+
+    #[test]
+    fn aarch64_features_line() {
+        let flags = parse_cpuinfo_flags(PI5_CPUINFO).unwrap();
+        assert!(flags.contains("asimd"));
+        assert!(flags.contains("atomics"));
+
+        let features = detect("aarch64", &|f| Some(flags.contains(f.linux)), None);
+        assert_eq!(features.arch, "aarch64");
+        for name in [
+            "NEON", "FP16", "DotProd", "AES", "PMULL", "SHA1", "SHA256", "CRC32", "LSE", "LRCPC",
+        ] {
+            let f = feature(&features, name);
+            assert!(f.supported && f.enabled, "{name} should be supported");
+        }
+        for name in ["SVE", "SVE2", "SME", "BF16", "SHA512", "BTI"] {
+            assert!(
+                !feature(&features, name).supported,
+                "{name} should be unsupported"
+            );
+        }
+    }
+
+    #[test]
+    fn x86_flags_line() {
+        let flags = parse_cpuinfo_flags(INTEL_CPUINFO).unwrap();
+        assert!(flags.contains("avx2"));
+        assert!(flags.contains("sse4_2"));
+        assert!(!flags.contains("avx512f"));
+    }
+
+    #[test]
+    fn missing_flags_line_is_unknown() {
+        assert!(parse_cpuinfo_flags("processor\t: 0\nBogoMIPS\t: 108.00\n").is_none());
+        assert!(parse_cpuinfo_flags("").is_none());
+    }
+
+    #[test]
+    fn cpuid_without_os_report() {
+        // 系统没有报告时以 CPUID 为准，而不是把所有扩展都标成被系统禁用
+        let cpuid = CpuidSnapshot {
+            leaf1_ecx: (1 << 28) | (1 << 25),
+            leaf7_ebx: 1 << 16,
+            xcr0: Some(XCR0_AVX),
+            ..Default::default()
+        };
+        let features = detect("x86_64", &|_| None, Some(&cpuid));
+        let avx = feature(&features, "AVX");
+        assert!(avx.supported && avx.enabled);
+        assert!(feature(&features, "AES-NI").enabled);
+
+        // AVX-512F 硬件支持，但 XCR0 没有打开 ZMM 状态
+        let avx512 = feature(&features, "AVX-512F");
+        assert!(avx512.supported && !avx512.enabled);
+        assert!(avx512.disabled_reason.is_some());
+
+        // 系统报告里缺少某项时，视为被系统禁用
+        let features = detect("x86_64", &|f| Some(f.linux != "aes"), Some(&cpuid));
+        let aes = feature(&features, "AES-NI");
+        assert!(aes.supported && !aes.enabled);
+    }
+}
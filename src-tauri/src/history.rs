@@ -0,0 +1,431 @@
+//! 进程内的指标历史：每个指标一个有界环形缓冲区，由后台采样写入。
+//!
+//! 指标名约定：
+//! `cpu`、`cpu.core.<n>`、`memory`、`swap`（已用 KB）、`disk.read`、`disk.write`、
+//! `net.rx`、`net.tx`（字节/秒）、`disk.<设备>.read`、`disk.<设备>.write`（字节/秒）、
+//! `disk.<设备>.read_iops`、`disk.<设备>.write_iops`、`disk.<设备>.busy`（%）、
+//! `net.<接口>.rx`、`net.<接口>.tx`（字节/秒）、`net.<接口>.rx_packets`、`net.<接口>.tx_packets`（包/秒）、
+//! `temp.cpu`、`temp.gpu`、`temp.<传感器名>`、
+//! `power.package`、`power.cpu`、`power.gpu`、`power.ane`、`power.dram`、`power.system`、
+//! `power.<域名>`（W）、`battery`（%）
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+
+use crate::SystemMetrics;
+
+/// 默认每个指标保留的点数（1 秒间隔下约 1 小时）
+pub const DEFAULT_CAPACITY: usize = 3600;
+
+/// 超过这么久没有新数据的指标（拔掉的磁盘、消失的接口等）整个移除
+pub const RETENTION_MS: u64 = 60 * 60 * 1000;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Point {
+    pub timestamp_ms: u64,
+    pub value: f64,
+}
+
+/// 降采样后的一个时间桶
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Bucket {
+    /// 桶起始时间（unix 毫秒）
+    pub timestamp_ms: u64,
+    pub min: f64,
+    pub avg: f64,
+    pub max: f64,
+    pub count: usize,
+}
+
+pub struct MetricsHistory {
+    capacity: usize,
+    series: Mutex<HashMap<String, VecDeque<Point>>>,
+}
+
+impl MetricsHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            series: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn push(&self, metric: &str, timestamp_ms: u64, value: f64) {
+        let mut series = self.series.lock().unwrap();
+        let buf = series
+            .entry(metric.to_string())
+            .or_insert_with(|| VecDeque::with_capacity(self.capacity));
+        if buf.len() == self.capacity {
+            buf.pop_front();
+        }
+        buf.push_back(Point {
+            timestamp_ms,
+            value,
+        });
+    }
+
+    /// 把一次采样拆成各个指标写入
+    pub fn record(&self, m: &SystemMetrics) {
+        for (metric, value) in flatten(m) {
+            self.push(&metric, m.timestamp_ms, value);
+        }
+        self.prune(m.timestamp_ms);
+    }
+
+    /// 移除在保留期内没有任何数据的指标
+    pub fn prune(&self, now_ms: u64) {
+        let cutoff = now_ms.saturating_sub(RETENTION_MS);
+        self.series
+            .lock()
+            .unwrap()
+            .retain(|_, buf| buf.back().is_some_and(|p| p.timestamp_ms >= cutoff));
+    }
+
+    /// 当前有数据的指标名（已排序）
+    pub fn metrics(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.series.lock().unwrap().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// 查询 `since_ms` 之后的数据，按 `resolution_ms` 宽度分桶；
+    /// resolution 为 0 时每个原始点单独成桶
+    pub fn query(&self, metric: &str, since_ms: u64, resolution_ms: u64) -> Vec<Bucket> {
+        let series = self.series.lock().unwrap();
+        let Some(buf) = series.get(metric) else {
+            return Vec::new();
+        };
+        let points = buf.iter().filter(|p| p.timestamp_ms >= since_ms);
+        downsample(points, resolution_ms)
+    }
+}
+
+/// 把一次采样拆成 (指标名, 数值) 列表，内存历史与磁盘存储共用同一套指标名
+pub fn flatten(m: &SystemMetrics) -> Vec<(String, f64)> {
+    let mut out = vec![
+        ("cpu".to_string(), m.cpu_usage_percent as f64),
+        ("memory".to_string(), m.used_memory_kb as f64),
+        ("swap".to_string(), m.used_swap_kb as f64),
+        ("disk.read".to_string(), m.disk_read_bytes_per_sec as f64),
+        ("disk.write".to_string(), m.disk_write_bytes_per_sec as f64),
+        ("net.rx".to_string(), m.network_rx_bytes_per_sec as f64),
+        ("net.tx".to_string(), m.network_tx_bytes_per_sec as f64),
+    ];
+    for (i, usage) in m.per_core_usage.iter().enumerate() {
+        out.push((format!("cpu.core.{}", i), *usage as f64));
+    }
+    for io in &m.disk_io {
+        let dev = &io.device;
+        out.push((format!("disk.{}.read", dev), io.read_bytes_per_sec as f64));
+        out.push((format!("disk.{}.write", dev), io.write_bytes_per_sec as f64));
+        out.push((format!("disk.{}.read_iops", dev), io.read_iops as f64));
+        out.push((format!("disk.{}.write_iops", dev), io.write_iops as f64));
+        if let Some(busy) = io.busy_percent {
+            out.push((format!("disk.{}.busy", dev), busy as f64));
+        }
+    }
+    for io in &m.net_io {
+        let iface = &io.interface;
+        out.push((format!("net.{}.rx", iface), io.rx_bytes_per_sec as f64));
+        out.push((format!("net.{}.tx", iface), io.tx_bytes_per_sec as f64));
+        out.push((
+            format!("net.{}.rx_packets", iface),
+            io.rx_packets_per_sec as f64,
+        ));
+        out.push((
+            format!("net.{}.tx_packets", iface),
+            io.tx_packets_per_sec as f64,
+        ));
+    }
+    if let Some(temps) = &m.temps {
+        if let Some(cpu) = temps.cpu {
+            out.push(("temp.cpu".to_string(), cpu as f64));
+        }
+        if let Some(gpu) = temps.gpu {
+            out.push(("temp.gpu".to_string(), gpu as f64));
+        }
+        for (name, value) in &temps.others {
+            out.push((format!("temp.{}", name), *value as f64));
+        }
+    }
+    if let Some(power) = &m.power {
+        let domains = [
+            ("package", power.package),
+            ("cpu", power.cpu),
+            ("gpu", power.gpu),
+            ("ane", power.ane),
+            ("dram", power.dram),
+            ("system", power.system),
+        ];
+        for (name, value) in domains {
+            if let Some(value) = value {
+                out.push((format!("power.{}", name), value as f64));
+            }
+        }
+        for (name, value) in &power.others {
+            out.push((format!("power.{}", name), *value as f64));
+        }
+    }
+    if let Some(battery) = m.battery_percent {
+        out.push(("battery".to_string(), battery as f64));
+    }
+    out
+}
+
+/// 将按时间排序的点聚合成 min/avg/max 桶
+pub fn downsample<'a>(points: impl Iterator<Item = &'a Point>, resolution_ms: u64) -> Vec<Bucket> {
+    let raw = points.map(|p| Bucket {
+        timestamp_ms: p.timestamp_ms,
+        min: p.value,
+        avg: p.value,
+        max: p.value,
+        count: 1,
+    });
+    merge_buckets(raw, resolution_ms)
+}
+
+/// 把已经聚合过的桶（可能来自不同精度）合并到 `resolution_ms` 宽的桶中，
+/// 平均值按各桶的样本数加权
+pub fn merge_buckets(input: impl IntoIterator<Item = Bucket>, resolution_ms: u64) -> Vec<Bucket> {
+    let mut buckets: Vec<Bucket> = Vec::new();
+    for b in input {
+        let start = if resolution_ms == 0 {
+            b.timestamp_ms
+        } else {
+            b.timestamp_ms - b.timestamp_ms % resolution_ms
+        };
+        match buckets.last_mut() {
+            Some(last) if resolution_ms > 0 && last.timestamp_ms == start => {
+                last.min = last.min.min(b.min);
+                last.max = last.max.max(b.max);
+                last.avg += b.avg * b.count as f64;
+                last.count += b.count;
+            }
+            _ => buckets.push(Bucket {
+                timestamp_ms: start,
+                avg: b.avg * b.count as f64,
+                ..b
+            }),
+        }
+    }
+    for b in &mut buckets {
+        if b.count > 0 {
+            b.avg /= b.count as f64;
+        }
+    }
+    buckets
+}
+
+/// 只有汇总字段的一次采样，历史与存储的测试共用
+#[cfg(test)]
+pub fn sample_metrics(timestamp_ms: u64, cpu_usage_percent: f32) -> SystemMetrics {
+    SystemMetrics {
+        timestamp_ms,
+        cpu_usage_percent,
+        per_core_usage: vec![10.0, 15.0],
+        total_memory_kb: 16_000_000,
+        used_memory_kb: 8_000_000,
+        total_swap_kb: 0,
+        used_swap_kb: 0,
+        disk_read_bytes_per_sec: 4096,
+        disk_write_bytes_per_sec: 8192,
+        network_rx_bytes_per_sec: 100,
+        network_tx_bytes_per_sec: 200,
+        disks: Vec::new(),
+        disk_io: Vec::new(),
+        net_io: Vec::new(),
+        temps: None,
+        power: None,
+        battery_percent: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DiskIo, NetIo, Power, Temps};
+
+    fn values(buckets: &[Bucket]) -> Vec<(u64, f64, f64, f64, usize)> {
+        buckets
+            .iter()
+            .map(|b| (b.timestamp_ms, b.min, b.avg, b.max, b.count))
+            .collect()
+    }
+
+    #[test]
+    fn ring_evicts_oldest() {
+        let history = MetricsHistory::new(3);
+        for i in 0..5 {
+            history.push("cpu", i * 1000, i as f64);
+        }
+        let points: Vec<f64> = history.query("cpu", 0, 0).iter().map(|b| b.avg).collect();
+        assert_eq!(points, [2.0, 3.0, 4.0]);
+        assert!(history.query("gpu", 0, 0).is_empty());
+
+        // 容量至少为 1
+        let tiny = MetricsHistory::new(0);
+        tiny.push("cpu", 0, 1.0);
+        tiny.push("cpu", 1000, 2.0);
+        assert_eq!(tiny.query("cpu", 0, 0).len(), 1);
+    }
+
+    #[test]
+    fn metric_names_sorted() {
+        let history = MetricsHistory::new(10);
+        for name in ["net.rx", "cpu.core.1", "cpu", "battery", "cpu.core.0"] {
+            history.push(name, 0, 0.0);
+        }
+        assert_eq!(
+            history.metrics(),
+            ["battery", "cpu", "cpu.core.0", "cpu.core.1", "net.rx"]
+        );
+    }
+
+    #[test]
+    fn query_downsamples() {
+        let history = MetricsHistory::new(100);
+        for (ts, value) in [
+            (1000, 1.0),
+            (1500, 3.0),
+            (2100, 10.0),
+            (2900, 20.0),
+            (4000, 5.0),
+        ] {
+            history.push("cpu", ts, value);
+        }
+        assert_eq!(
+            values(&history.query("cpu", 0, 1000)),
+            [
+                (1000, 1.0, 2.0, 3.0, 2),
+                (2000, 10.0, 15.0, 20.0, 2),
+                (4000, 5.0, 5.0, 5.0, 1),
+            ]
+        );
+        // since 之前的点不参与
+        assert_eq!(
+            values(&history.query("cpu", 2100, 1000)),
+            [(2000, 10.0, 15.0, 20.0, 2), (4000, 5.0, 5.0, 5.0, 1)]
+        );
+        assert_eq!(history.query("cpu", 0, 0).len(), 5);
+    }
+
+    #[test]
+    fn merge_weights_by_count() {
+        let bucket = |timestamp_ms, avg, count| Bucket {
+            timestamp_ms,
+            min: avg,
+            avg,
+            max: avg,
+            count,
+        };
+        let merged = merge_buckets([bucket(0, 1.0, 3), bucket(60_000, 5.0, 1)], 3_600_000);
+        assert_eq!(values(&merged), [(0, 1.0, 2.0, 5.0, 4)]);
+    }
+
+    #[test]
+    fn flatten_key_names() {
+        let mut m = sample_metrics(0, 12.5);
+        m.disk_io = vec![DiskIo {
+            device: "nvme0n1".to_string(),
+            mount_points: vec!["/".to_string()],
+            read_bytes_per_sec: 1,
+            write_bytes_per_sec: 2,
+            read_iops: 3.0,
+            write_iops: 4.0,
+            queue_depth: Some(0.5),
+            busy_percent: Some(25.0),
+        }];
+        m.net_io = vec![NetIo {
+            interface: "wlp0s20f3".to_string(),
+            rx_bytes_per_sec: 5,
+            tx_bytes_per_sec: 6,
+            rx_packets_per_sec: 7.0,
+            tx_packets_per_sec: 8.0,
+            rx_errors: 0,
+            tx_errors: 0,
+            rx_dropped: None,
+            tx_dropped: None,
+            multicast: None,
+            rx_bytes_total: 0,
+            tx_bytes_total: 0,
+            rx_bytes_session: 0,
+            tx_bytes_session: 0,
+        }];
+        m.temps = Some(Temps {
+            cpu: Some(48.5),
+            gpu: None,
+            others: vec![("Battery".to_string(), 30.5)],
+            sensors: Vec::new(),
+        });
+        m.power = Some(Power {
+            cpu: Some(0.2),
+            others: vec![("psys".to_string(), 4.0)],
+            ..Default::default()
+        });
+        m.battery_percent = Some(80.0);
+
+        let names: Vec<String> = flatten(&m).into_iter().map(|(name, _)| name).collect();
+        assert_eq!(
+            names,
+            [
+                "cpu",
+                "memory",
+                "swap",
+                "disk.read",
+                "disk.write",
+                "net.rx",
+                "net.tx",
+                "cpu.core.0",
+                "cpu.core.1",
+                "disk.nvme0n1.read",
+                "disk.nvme0n1.write",
+                "disk.nvme0n1.read_iops",
+                "disk.nvme0n1.write_iops",
+                "disk.nvme0n1.busy",
+                "net.wlp0s20f3.rx",
+                "net.wlp0s20f3.tx",
+                "net.wlp0s20f3.rx_packets",
+                "net.wlp0s20f3.tx_packets",
+                "temp.cpu",
+                "temp.Battery",
+                "power.cpu",
+                "power.psys",
+                "battery",
+            ]
+        );
+    }
+
+    #[test]
+    fn powermetrics_temps_keep_one_series() {
+        let history = MetricsHistory::new(10);
+        for (i, reading) in ["30.51", "30.62", "30.70"].iter().enumerate() {
+            let mut m = sample_metrics(i as u64 * 1000, 12.5);
+            m.temps = Some(crate::macos::parse_powermetrics_temps(&format!(
+                "Battery temperature: {} C\n",
+                reading
+            )));
+            history.record(&m);
+        }
+        let temps: Vec<String> = history
+            .metrics()
+            .into_iter()
+            .filter(|name| name.starts_with("temp."))
+            .collect();
+        assert_eq!(temps, ["temp.Battery"]);
+        assert_eq!(history.query("temp.Battery", 0, 0).len(), 3);
+    }
+
+    #[test]
+    fn stale_series_pruned() {
+        let history = MetricsHistory::new(10);
+        history.push("disk.sdb.read", 0, 1.0);
+        history.record(&sample_metrics(RETENTION_MS, 12.5));
+        assert!(history.metrics().contains(&"disk.sdb.read".to_string()));
+
+        history.record(&sample_metrics(RETENTION_MS + 1, 12.5));
+        assert!(!history.metrics().contains(&"disk.sdb.read".to_string()));
+        assert!(history.metrics().contains(&"cpu".to_string()));
+    }
+}
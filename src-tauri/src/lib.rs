@@ -1,17 +1,42 @@
-use std::{collections::HashMap, process::Command, time::Duration};
+use std::{collections::HashMap, time::Duration};
 
-use regex::Regex;
 use tauri::{path::BaseDirectory, Manager};
 
 use anyhow::Context;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::net::IpAddr;
-use sysinfo::{Disks, System};
+use sysinfo::System;
 
 // 导入 tray 模块
 mod tray;
 
+mod cache;
+mod command;
+mod disk;
+mod features;
+mod history;
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+mod linux;
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+mod macos;
+mod memory;
+mod network;
+mod platform;
+mod process;
+mod sampler;
+mod smart;
+mod smbios;
+mod store;
+
+use history::Bucket;
+use platform::SharedPlatform;
+use process::control::{ActionGuard, ActionTicket, ProcessSignal};
+use process::sockets::{SocketFilter, SocketInfo};
+use process::{ProcessDetails, ProcessInfo, ProcessNode, SortBy};
+use sampler::{Sampler, SharedSampler};
+use store::{MetricsStore, SharedStore};
+
 // Learn more about Tauri commands at https://tauri.app/develop/calling-rust/
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -24,14 +49,6 @@ struct MacOSMapEntry {
     marketing_name: String,
 }
 
-#[tauri::command]
-fn get_product_version() -> Option<String> {
-    let out = Command::new("sw_vers")
-        .arg("-productVersion")
-        .output()
-        .ok()?;
-    Some(String::from_utf8_lossy(&out.stdout).trim().to_string())
-}
 #[tauri::command]
 fn load_map(handle: tauri::AppHandle) -> Vec<MacOSMapEntry> {
     // 尝试解析资源路径
@@ -52,13 +69,6 @@ fn load_map(handle: tauri::AppHandle) -> Vec<MacOSMapEntry> {
         .collect()
 }
 
-#[tauri::command]
-fn find_marketing_name(ver: &str, map: &[MacOSMapEntry]) -> Option<String> {
-    map.iter()
-        .find(|e| e.product_version == ver)
-        .map(|e| e.marketing_name.clone())
-}
-
 #[derive(Debug, Deserialize, Serialize)]
 struct ErrorReport {
     message: String,
@@ -135,67 +145,25 @@ pub struct DeviceInfo {
     pub model_identifier: Option<String>,
     pub boot_time_utc: Option<String>, // ISO8601
     pub uptime_seconds: u64,
-}
-
-fn run_cmd_out(cmd: &str, args: &[&str]) -> Option<String> {
-    let output = Command::new(cmd).args(args).output().ok()?;
-    let s = String::from_utf8_lossy(&output.stdout).trim().to_string();
-    s.is_empty().then(|| None).unwrap_or(Some(s))
-}
-
-fn ioreg_get_property(key: &str) -> Option<String> {
-    let out = run_cmd_out("ioreg", &["-rd1", "-c", "IOPlatformExpertDevice"])?;
-    // 支持 "key" = "value" 或 "key" = <"value">
-    let pattern = format!(
-        r#""{}"\s*=\s*(?:"([^"]+)"|<"([^"]+)">|<([0-9a-fA-F ]+)>)"#,
-        regex::escape(key)
-    );
-    let re = Regex::new(&pattern).ok()?;
-    re.captures(&out).and_then(|cap| {
-        // 优先匹配字符串
-        cap.get(1)
-            .or_else(|| cap.get(2))
-            .map(|m| m.as_str().to_string())
-            // 如果是16进制字节，尝试转成字符串
-            .or_else(|| {
-                cap.get(3).and_then(|m| {
-                    let bytes: Vec<u8> = m
-                        .as_str()
-                        .split_whitespace()
-                        .filter_map(|b| u8::from_str_radix(b, 16).ok())
-                        .collect();
-                    String::from_utf8(bytes).ok()
-                })
-            })
-    })
+    pub manufacturer: Option<String>,
+    pub product_name: Option<String>,
+    pub board_vendor: Option<String>,
+    pub board_name: Option<String>,
+    /// 主板序列号，Linux 上与 product_serial 一样只有 root 可读
+    #[serde(default)]
+    pub board_serial: Option<String>,
+    /// Linux 的 /etc/machine-id
+    pub machine_id: Option<String>,
+    /// 存在但需要 root 才能读取的字段名（如 "serial_number"），前端据此提示而不是显示为空
+    #[serde(default)]
+    pub restricted: Vec<String>,
 }
 
 #[tauri::command]
-fn get_device_info() -> DeviceInfo {
-    let serial_number = ioreg_get_property("IOPlatformSerialNumber");
-    let hardware_uuid = ioreg_get_property("IOPlatformUUID");
-    let model_identifier = ioreg_get_property("model");
-    let boot_time_str = run_cmd_out("sysctl", &["-n", "kern.boottime"]);
-    let boot_time_utc = boot_time_str.and_then(|s| {
-        let re = Regex::new(r#"\{ sec = (\d+),"#).ok()?;
-        re.captures(&s)
-            .and_then(|cap| cap.get(1).map(|m| m.as_str().to_string()))
-            .and_then(|sec_str| sec_str.parse::<i64>().ok())
-            .and_then(|secs| {
-                let dt = chrono::NaiveDateTime::from_timestamp_opt(secs, 0)?;
-                Some(dt.format("%Y-%m-%dT%H:%M:%SZ").to_string())
-            })
-    });
-
-    let uptime_seconds = sysinfo::System::uptime();
-
-    DeviceInfo {
-        serial_number,
-        hardware_uuid,
-        model_identifier,
-        boot_time_utc,
-        uptime_seconds,
-    }
+fn get_device_info(platform: tauri::State<'_, SharedPlatform>) -> Result<DeviceInfo, String> {
+    platform
+        .device_info()
+        .map_err(|e| format!("fetch device info error: {:?}", e))
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -215,199 +183,301 @@ pub struct HardwareData {
     activation_lock_status: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[tauri::command]
+fn get_hardware_data(platform: tauri::State<'_, SharedPlatform>) -> Result<HardwareData, String> {
+    platform
+        .hardware_data()
+        .map_err(|e| format!("fetch hardware data error: {:?}", e))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct DiskInfo {
     name: String,
     mount_point: String,
     total: u64,
     available: u64,
+    /// 所在的块设备，与 `DiskIo::device` 对应；无法映射时为 None
+    device: Option<String>,
+    file_system: String,
+    /// "SSD" / "HDD"，未知时为 None
+    kind: Option<String>,
+    is_removable: bool,
+    is_read_only: bool,
+    /// inode 总数与剩余数，文件系统不提供（如 exFAT）时为 None
+    inodes_total: Option<u64>,
+    inodes_free: Option<u64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// 单个块设备的 I/O，由两次采样之间的累计计数差得出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiskIo {
+    /// 块设备名，如 "nvme0n1"、"sda"、"disk0"
+    pub device: String,
+    /// 该设备（含分区、APFS 卷）上的挂载点
+    pub mount_points: Vec<String>,
+    pub read_bytes_per_sec: u64,
+    pub write_bytes_per_sec: u64,
+    pub read_iops: f32,
+    pub write_iops: f32,
+    /// 平均队列深度（处理中与排队的请求数）
+    pub queue_depth: Option<f32>,
+    /// 设备忙碌时间占比（macOS 没有对应计数，为 None）
+    pub busy_percent: Option<f32>,
+}
+
+/// 单个网络接口的流量，速率由两次采样之间的累计计数差得出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NetIo {
+    pub interface: String,
+    pub rx_bytes_per_sec: u64,
+    pub tx_bytes_per_sec: u64,
+    pub rx_packets_per_sec: f32,
+    pub tx_packets_per_sec: f32,
+    /// 以下错误、丢包与多播为累计值
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    pub rx_dropped: Option<u64>,
+    pub tx_dropped: Option<u64>,
+    pub multicast: Option<u64>,
+    /// 开机（或接口创建）以来的收发字节数
+    pub rx_bytes_total: u64,
+    pub tx_bytes_total: u64,
+    /// 应用启动以来的收发字节数
+    pub rx_bytes_session: u64,
+    pub tx_bytes_session: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Temps {
     cpu: Option<f32>,
     gpu: Option<f32>,
     others: Vec<(String, f32)>,
+    /// 全部传感器及其阈值（目前只有 Linux 提供）
+    #[serde(default)]
+    sensors: Vec<TempSensor>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TempSensor {
+    name: String,
+    celsius: f32,
+    /// 驱动给出的上限温度
+    max: Option<f32>,
+    /// 临界温度，超过后硬件可能降频或关机
+    critical: Option<f32>,
+}
+
+/// 功耗（W），各平台能拿到的域不同，拿不到的为 None
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Power {
+    /// CPU 封装（Linux RAPL 各 package 之和）
+    package: Option<f32>,
+    /// CPU 核心（RAPL core / macOS CPU Power）
+    cpu: Option<f32>,
+    /// 显卡（RAPL uncore 即核显 / macOS GPU Power）
+    gpu: Option<f32>,
+    /// 神经网络引擎（仅 Apple Silicon）
+    ane: Option<f32>,
+    dram: Option<f32>,
+    /// 整机 / 平台（RAPL psys / macOS Combined Power）
+    system: Option<f32>,
+    others: Vec<(String, f32)>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SystemMetrics {
+    timestamp_ms: u64,
     cpu_usage_percent: f32, // 0.0..100.0
+    per_core_usage: Vec<f32>,
     total_memory_kb: u64,
     used_memory_kb: u64,
+    total_swap_kb: u64,
+    used_swap_kb: u64,
+    disk_read_bytes_per_sec: u64,
+    disk_write_bytes_per_sec: u64,
+    network_rx_bytes_per_sec: u64,
+    network_tx_bytes_per_sec: u64,
     disks: Vec<DiskInfo>,
+    /// 各块设备的吞吐与 IOPS，第一次采样只建立基准，为空
+    disk_io: Vec<DiskIo>,
+    /// 各网络接口的流量，同样从第二次采样开始才有数据
+    net_io: Vec<NetIo>,
     temps: Option<Temps>,
+    power: Option<Power>,
+    battery_percent: Option<f32>,
 }
 
+/// 读取后台采样的最新快照，不再在命令里临时刷新
 #[tauri::command]
-fn get_system_metrics() -> Result<SystemMetrics, String> {
-    // --- 1. 基本信息：cpu/memory/disk（使用 sysinfo） ---
-    let mut sys = System::new_all();
-
-    sys.refresh_all();
+fn get_system_metrics(sampler: tauri::State<'_, SharedSampler>) -> Result<SystemMetrics, String> {
+    sampler
+        .latest()
+        .ok_or_else(|| "metrics sampler has not produced a sample yet".to_string())
+}
 
-    let cpu_usage =
-        sys.cpus().iter().map(|c| c.cpu_usage()).sum::<f32>() / (sys.cpus().len() as f32);
+/// 调整后台采样间隔（毫秒），返回实际生效的间隔
+#[tauri::command]
+fn set_sampling_interval(sampler: tauri::State<'_, SharedSampler>, interval_ms: u64) -> u64 {
+    sampler
+        .set_interval(Duration::from_millis(interval_ms))
+        .as_millis() as u64
+}
 
-    sys.refresh_all();
+/// 查询某个指标的历史，`since` 为 unix 毫秒（默认全部），
+/// `resolution` 为分桶宽度毫秒（默认 0，即返回原始点）
+#[tauri::command]
+fn get_metrics_history(
+    sampler: tauri::State<'_, SharedSampler>,
+    metric: String,
+    since: Option<u64>,
+    resolution: Option<u64>,
+) -> Vec<Bucket> {
+    sampler
+        .history()
+        .query(&metric, since.unwrap_or(0), resolution.unwrap_or(0))
+}
 
-    let total_memory = sys.total_memory(); // KB
-    let used_memory = sys.used_memory(); // KB
+/// 列出当前有历史数据的指标名
+#[tauri::command]
+fn list_history_metrics(sampler: tauri::State<'_, SharedSampler>) -> Vec<String> {
+    sampler.history().metrics()
+}
 
-    let disks = Disks::new_with_refreshed_list()
-        .iter()
-        .map(|d| DiskInfo {
-            name: d.name().to_string_lossy().into_owned(),
-            mount_point: d.mount_point().to_string_lossy().into_owned(),
-            total: d.total_space(),
-            available: d.available_space(),
-        })
-        .collect::<Vec<_>>();
-
-    // let disks = sys
-    //     .disks()
-    //     .iter()
-    //     .map(|d| DiskInfo {
-    //         name: d.name().to_string_lossy().into_owned(),
-    //         mount_point: d.mount_point().to_string_lossy().into_owned(),
-    //         total: d.total_space(),
-    //         available: d.available_space(),
-    //     })
-    //     .collect::<Vec<_>>();
-
-    // --- 2. 温度信息：平台差异处理（best-effort） ---
-    #[cfg(target_os = "macos")]
-    let temps = macos_try_get_temps();
-
-    Ok(SystemMetrics {
-        cpu_usage_percent: cpu_usage,
-        total_memory_kb: total_memory,
-        used_memory_kb: used_memory,
-        disks,
-        temps,
+/// 进程列表，`sort_by` 为 cpu / memory / disk / pid / name（默认 cpu），
+/// `filter` 匹配进程名、命令行或 pid，`limit` 限制返回条数
+#[tauri::command]
+async fn list_processes(
+    sampler: tauri::State<'_, SharedSampler>,
+    sort_by: Option<SortBy>,
+    filter: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<ProcessInfo>, String> {
+    let sampler = sampler.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        process::list(
+            &mut sampler.collectors(),
+            sort_by.unwrap_or_default(),
+            filter.as_deref(),
+            limit,
+        )
     })
+    .await
+    .map_err(|e| format!("list_processes error: {:?}", e))
 }
 
+/// TCP / UDP 套接字表，`protocol` 为 tcp / udp，`state` 如 LISTEN / ESTABLISHED
+/// （LISTEN 同时匹配未连接的 UDP），`port` 匹配本地或远端端口
 #[tauri::command]
-fn get_hardware_data() -> Result<HardwareData, String> {
-    let output = Command::new("system_profiler")
-        .args(&["-json", "SPHardwareDataType"])
-        .output()
-        .map_err(|e| format!("failed to run system_profiler: {}", e))?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("system_profiler failed: {}", stderr));
+async fn list_sockets(
+    platform: tauri::State<'_, SharedPlatform>,
+    protocol: Option<String>,
+    state: Option<String>,
+    port: Option<u16>,
+) -> Result<Vec<SocketInfo>, String> {
+    let platform = platform.inner().clone();
+    let filter = SocketFilter {
+        protocol,
+        state,
+        port,
+    };
+    let res = tauri::async_runtime::spawn_blocking(move || platform.sockets()).await;
+    match res {
+        Ok(Ok(sockets)) => Ok(process::sockets::filter(sockets, &filter)),
+        Ok(Err(e)) => Err(format!("list_sockets error: {:?}", e)),
+        Err(e) => Err(format!("task join error: {:?}", e)),
     }
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-
-    // system_profiler -json SPHardwareDataType 的输出是 { "SPHardwareDataType": [ { ... } ] }
-    let v: serde_json::Value = serde_json::from_str(&stdout)
-        .map_err(|e| format!("failed to parse JSON from system_profiler: {}", e))?;
-
-    // 保险地从 JSON 中提取字段
-    let hw = v
-        .get("SPHardwareDataType")
-        .and_then(|arr| arr.get(0))
-        .ok_or_else(|| "unexpected JSON structure".to_string())?;
-
-    let get_str = |key: &str| hw.get(key).and_then(|s| s.as_str()).map(|s| s.to_string());
-
-    Ok(HardwareData {
-        model_name: get_str("machine_name").or_else(|| get_str("Model Name")), // 兼容性尝试
-        model_identifier: get_str("machine_model").or_else(|| get_str("Model Identifier")),
-        model_number: get_str("Model Number"),
-        chip: get_str("chip_type").or_else(|| get_str("Chip")),
-        total_number_of_cores: get_str("number_processors")
-            .or_else(|| get_str("Total Number of Cores"))
-            .or_else(|| hw.get("Total Number of Cores").map(|x| x.to_string())),
-        memory: get_str("physical_memory").or_else(|| get_str("Memory")),
-        system_firmware_version: get_str("system_firmware_version")
-            .or_else(|| get_str("System Firmware Version")),
-        os_loader_version: get_str("os_loader_version").or_else(|| get_str("OS Loader Version")),
-        serial_number_system: get_str("serial_number")
-            .or_else(|| get_str("Serial Number (system)")),
-        hardware_uuid: get_str("hardware_uuid").or_else(|| get_str("Hardware UUID")),
-        provisioning_udid: get_str("provisioning_udid").or_else(|| get_str("Provisioning UDID")),
-        activation_lock_status: get_str("activation_lock_status")
-            .or_else(|| get_str("Activation Lock Status")),
-    })
+/// 单个进程的详情（环境变量、cwd 等需要相应权限，读不到时为空）
+#[tauri::command]
+async fn get_process_details(
+    sampler: tauri::State<'_, SharedSampler>,
+    pid: u32,
+) -> Result<ProcessDetails, String> {
+    let sampler = sampler.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || process::details(&mut sampler.collectors(), pid))
+        .await
+        .map_err(|e| format!("get_process_details error: {:?}", e))?
+        .ok_or_else(|| format!("process {} not found", pid))
 }
 
-// 辅助：从字符串中提取第一个浮点数（如 "CPU die temperature: 69.54 C" -> 69.54）
-fn extract_first_float(s: &str) -> Option<f32> {
-    let mut num = String::new();
-    let mut in_num = false;
-    for ch in s.chars() {
-        if ch.is_digit(10) || ch == '.' || ch == '-' {
-            num.push(ch);
-            in_num = true;
-        } else if in_num {
-            break;
-        }
-    }
-    if num.is_empty() {
-        None
-    } else {
-        num.parse::<f32>().ok()
-    }
+/// 进程树，每个节点带子树合计的 CPU 与内存
+#[tauri::command]
+async fn get_process_tree(
+    sampler: tauri::State<'_, SharedSampler>,
+) -> Result<Vec<ProcessNode>, String> {
+    let sampler = sampler.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || process::tree(&mut sampler.collectors()))
+        .await
+        .map_err(|e| format!("get_process_tree error: {:?}", e))
 }
 
-#[cfg(target_os = "macos")]
-fn macos_try_get_temps() -> Option<Temps> {
-    // powermetrics 通常需要 root 权限，输出中包含 "CPU die temperature"
-    // best-effort: 尝试直接运行 powermetrics; 如果失败返回 None 并在前端提示用户权限或安装 helper
-    let out = Command::new("powermetrics")
-        .args(&["--samplers", "smc", "-i1", "-n1"])
-        .output();
-
-    let output = match out {
-        Ok(o) if o.status.success() => String::from_utf8_lossy(&o.stdout).into_owned(),
-        Ok(o) => {
-            // 可能是没有权限或者 powermetrics 没有输出 smc
-            let stderr = String::from_utf8_lossy(&o.stderr).into_owned();
-            // 返回 None（前端可显示 stderr 做提示）
-            eprintln!("powermetrics failed: {}", stderr);
-            return None;
-        }
-        Err(e) => {
-            eprintln!("failed to run powermetrics: {}", e);
-            return None;
-        }
-    };
+/// 操作进程前先领取确认令牌，前端展示返回的进程名让用户确认
+#[tauri::command]
+fn prepare_process_action(
+    sampler: tauri::State<'_, SharedSampler>,
+    guard: tauri::State<'_, ActionGuard>,
+    pid: u32,
+) -> Result<ActionTicket, String> {
+    guard
+        .issue(&sampler.collectors(), pid)
+        .map_err(|e| format!("prepare_process_action error: {:?}", e))
+}
 
-    // 解析文本，找 "CPU die temperature: 69.54 C" 之类
-    let mut cpu_temp: Option<f32> = None;
-    let mut gpu_temp: Option<f32> = None;
-    let mut others = Vec::new();
-
-    for line in output.lines() {
-        let l = line.trim();
-        if l.to_lowercase().contains("cpu die temperature")
-            || l.to_lowercase().contains("cpu temperature")
-        {
-            if let Some(num) = extract_first_float(l) {
-                cpu_temp = Some(num);
-            }
-        } else if l.to_lowercase().contains("gpu die temperature")
-            || l.to_lowercase().contains("gpu temperature")
-        {
-            if let Some(num) = extract_first_float(l) {
-                gpu_temp = Some(num);
-            }
-        } else if l.to_lowercase().contains("temperature") {
-            if let Some(num) = extract_first_float(l) {
-                others.push((l.to_string(), num));
-            }
-        }
-    }
+/// 发送 TERM / KILL / STOP / CONT，需要 `prepare_process_action` 签发的令牌
+#[tauri::command]
+fn signal_process(
+    sampler: tauri::State<'_, SharedSampler>,
+    guard: tauri::State<'_, ActionGuard>,
+    pid: u32,
+    signal: ProcessSignal,
+    token: String,
+) -> Result<(), String> {
+    guard
+        .redeem(&sampler.collectors(), pid, &token)
+        .and_then(|_| process::control::send_signal(pid, signal))
+        .map_err(|e| format!("signal_process error: {:?}", e))
+}
+
+/// 调整 nice 值（-20..=19），需要 `prepare_process_action` 签发的令牌
+#[tauri::command]
+fn renice_process(
+    sampler: tauri::State<'_, SharedSampler>,
+    guard: tauri::State<'_, ActionGuard>,
+    pid: u32,
+    nice: i32,
+    token: String,
+) -> Result<(), String> {
+    guard
+        .redeem(&sampler.collectors(), pid, &token)
+        .and_then(|_| process::control::renice(pid, nice))
+        .map_err(|e| format!("renice_process error: {:?}", e))
+}
 
-    Some(Temps {
-        cpu: cpu_temp,
-        gpu: gpu_temp,
-        others,
+/// 查询磁盘上保存的长期历史，`since`/`until` 为 unix 毫秒（until 默认当前时间），
+/// 按时间跨度自动从原始数据、分钟汇总、小时汇总中取数
+#[tauri::command]
+async fn get_stored_metrics(
+    store: tauri::State<'_, SharedStore>,
+    metric: String,
+    since: u64,
+    until: Option<u64>,
+    resolution: Option<u64>,
+) -> Result<Vec<Bucket>, String> {
+    let store = store.inner().clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let now = store::now_ms();
+        store.query(
+            &metric,
+            since,
+            until.unwrap_or(now),
+            resolution.unwrap_or(0),
+            now,
+        )
     })
+    .await
+    .map_err(|e| format!("get_stored_metrics error: {:?}", e))?
+    .map_err(|e| format!("get_stored_metrics error: {:?}", e))
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -416,7 +486,7 @@ pub struct SingleBattery {
     pub model: Option<String>,
     pub serial_number: Option<String>,
 
-    /// Charging / Discharging / Full / Unknown
+    /// Charging / Discharging / Full / NotCharging / Unknown
     pub state: String,
 
     /// 0.0 - 100.0
@@ -450,13 +520,18 @@ pub struct SingleBattery {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct BatteryInfo {
     pub batteries: Vec<SingleBattery>,
+    /// 交流适配器是否接通（无法判断时为 None）
+    pub ac_online: Option<bool>,
     pub timestamp_unix: u64,
 }
 
 /// 主 async 命令，前端调用 invoke("get_battery_info")
 #[tauri::command]
-async fn get_battery_info() -> Result<BatteryInfo, String> {
-    let res = tauri::async_runtime::spawn_blocking(move || fetch_battery_blocking()).await;
+async fn get_battery_info(
+    platform: tauri::State<'_, SharedPlatform>,
+) -> Result<BatteryInfo, String> {
+    let platform = platform.inner().clone();
+    let res = tauri::async_runtime::spawn_blocking(move || platform.battery()).await;
     match res {
         Ok(Ok(info)) => Ok(info),
         Ok(Err(e)) => Err(format!("fetch battery error: {:?}", e)),
@@ -464,213 +539,531 @@ async fn get_battery_info() -> Result<BatteryInfo, String> {
     }
 }
 
-/// 阻塞实现：按平台分别获取
-fn fetch_battery_blocking() -> anyhow::Result<BatteryInfo> {
-    #[cfg(target_os = "macos")]
-    {
-        fetch_battery_macos().context("macOS battery fetch failed")
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Sensors {
+    pub fans: Vec<FanSensor>,
+    /// 电压（V）
+    pub voltages: Vec<ElectricalSensor>,
+    /// 电流（A）
+    pub currents: Vec<ElectricalSensor>,
+    /// 功率（W）
+    pub power: Vec<ElectricalSensor>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FanSensor {
+    pub name: String,
+    pub rpm: f32,
+    pub min_rpm: Option<f32>,
+    pub max_rpm: Option<f32>,
+    /// PWM 占空比 0.0..100.0（如果能拿到）
+    pub pwm_percent: Option<f32>,
+}
+
+/// 电压 / 电流 / 功率读数，单位见 `Sensors` 中对应字段
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ElectricalSensor {
+    pub name: String,
+    pub value: f32,
+    pub min: Option<f32>,
+    pub max: Option<f32>,
+    pub critical: Option<f32>,
+}
+
+/// 风扇、电压、电流与功率传感器，前端调用 invoke("get_sensors")
+#[tauri::command]
+async fn get_sensors(platform: tauri::State<'_, SharedPlatform>) -> Result<Sensors, String> {
+    let platform = platform.inner().clone();
+    let res = tauri::async_runtime::spawn_blocking(move || platform.sensors()).await;
+    match res {
+        Ok(Ok(sensors)) => Ok(sensors),
+        Ok(Err(e)) => Err(format!("fetch sensors error: {:?}", e)),
+        Err(e) => Err(format!("task join error: {:?}", e)),
     }
 }
 
-fn fetch_battery_macos() -> anyhow::Result<BatteryInfo> {
-    use std::time::{Duration, SystemTime, UNIX_EPOCH};
-
-    // 1) 尝试用 pmset -g batt 获取 percentage 与 charging state（文本）
-    let pm = Command::new("pmset")
-        .arg("-g")
-        .arg("batt")
-        .output()
-        .context("failed to run pmset")?;
-
-    let pm_stdout = String::from_utf8_lossy(&pm.stdout);
-    // pmset 输出示例行可能包含 "93%; discharging;" 或 "Now drawing from 'AC Power'"
-    let mut percentage: Option<f32> = None;
-    let mut state: String = "Unknown".to_string();
-    // 匹配 0-100%
-    if let Some(cap) = Regex::new(r"(\d{1,3})%")
-        .unwrap()
-        .captures(&pm_stdout)
-        .and_then(|c| c.get(1))
-    {
-        if let Ok(v) = cap.as_str().parse::<f32>() {
-            percentage = Some(v);
-        }
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CpuDetails {
+    pub vendor: Option<String>,
+    pub brand: Option<String>,
+    pub family: Option<u32>,
+    pub model: Option<u32>,
+    pub stepping: Option<u32>,
+    pub packages: Option<u32>,
+    pub physical_cores: Option<u32>,
+    pub logical_cores: u32,
+    pub performance_cores: Option<u32>,
+    pub efficiency_cores: Option<u32>,
+    /// 按逻辑 CPU 编号排列
+    pub cores: Vec<CpuCore>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct CpuCore {
+    /// 逻辑 CPU 编号
+    pub id: u32,
+    pub package_id: Option<u32>,
+    pub die_id: Option<u32>,
+    pub cluster_id: Option<u32>,
+    /// 物理核心编号，SMT 的兄弟线程相同
+    pub core_id: Option<u32>,
+    /// 共享同一物理核心的逻辑 CPU（含自身）
+    pub smt_siblings: Vec<u32>,
+    /// "performance" / "efficiency"，非混合架构为 None
+    pub kind: Option<String>,
+    pub current_mhz: Option<u64>,
+    pub min_mhz: Option<u64>,
+    pub max_mhz: Option<u64>,
+    pub usage_percent: Option<f32>,
+}
+
+/// 一个缓存实例；被多个核心共享的缓存只出现一次
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheInfo {
+    pub level: u32,
+    /// "data" / "instruction" / "unified"
+    pub kind: String,
+    pub size_bytes: u64,
+    pub ways: Option<u32>,
+    pub line_size: Option<u32>,
+    pub sets: Option<u32>,
+    /// 共享该缓存的逻辑 CPU 数
+    pub shared_by: u32,
+    /// 共享该缓存的逻辑 CPU 编号；CPUID 回退拿不到具体编号时为空
+    pub shared_cpus: Vec<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CpuFeatures {
+    /// "x86_64" / "aarch64"
+    pub arch: String,
+    pub categories: Vec<FeatureCategory>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FeatureCategory {
+    pub name: String,
+    pub features: Vec<CpuFeature>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CpuFeature {
+    pub name: String,
+    /// CPU 支持该扩展
+    pub supported: bool,
+    /// 支持且系统已启用，可以实际使用
+    pub enabled: bool,
+    /// 支持但不可用时的原因
+    pub disabled_reason: Option<String>,
+}
+
+/// L1i/L1d/L2/L3 缓存层级，前端调用 invoke("get_cpu_caches")
+#[tauri::command]
+async fn get_cpu_caches(
+    platform: tauri::State<'_, SharedPlatform>,
+) -> Result<Vec<CacheInfo>, String> {
+    let platform = platform.inner().clone();
+    let res = tauri::async_runtime::spawn_blocking(move || platform.caches()).await;
+    match res {
+        Ok(Ok(caches)) => Ok(caches),
+        Ok(Err(e)) => Err(format!("fetch cpu caches error: {:?}", e)),
+        Err(e) => Err(format!("task join error: {:?}", e)),
     }
+}
 
-    println!("pmset output: {}", pm_stdout);
-    if pm_stdout
-        .to_lowercase()
-        .contains("now drawing from 'ac power'")
-    {
-        state = "Charging".to_string();
-    } else if pm_stdout.to_lowercase().contains("discharging")
-        || pm_stdout.to_lowercase().contains("battery power")
-    {
-        state = "Discharging".to_string();
-    } else if pm_stdout.to_lowercase().contains("charged")
-        || pm_stdout.to_lowercase().contains("full")
-    {
-        state = "Full".to_string();
+/// 指令集扩展（SSE/AVX/AVX-512、NEON/SVE、加密、原子操作等），前端调用 invoke("get_cpu_features")
+#[tauri::command]
+async fn get_cpu_features(
+    platform: tauri::State<'_, SharedPlatform>,
+) -> Result<CpuFeatures, String> {
+    let platform = platform.inner().clone();
+    let res = tauri::async_runtime::spawn_blocking(move || platform.cpu_features()).await;
+    match res {
+        Ok(Ok(features)) => Ok(features),
+        Ok(Err(e)) => Err(format!("fetch cpu features error: {:?}", e)),
+        Err(e) => Err(format!("task join error: {:?}", e)),
     }
+}
 
-    // 2) 用 ioreg 获取更多字段（CycleCount, DesignCapacity, MaxCapacity/CurrentCapacity, Temperature）
-    // ioreg 输出行形如: | | "CycleCount" = 123
-    let ioreg = Command::new("ioreg")
-        .args(&["-rn", "AppleSmartBattery"])
-        .output()
-        .context("failed to run ioreg")?;
-    let ioreg_out = String::from_utf8_lossy(&ioreg.stdout);
-
-    let kv_re = Regex::new(r#"\"(?P<k>[A-Za-z0-9_]+)\"\s+=\s+(?P<v>.+)"#).unwrap();
-
-    let mut cycle_count: Option<u32> = None;
-    let mut design_capacity: Option<u32> = None;
-    let mut current_capacity: Option<u32> = None;
-    let mut max_capacity: Option<u32> = None;
-    let mut temp_c: Option<f32> = None;
-    let mut voltage: Option<f32> = None;
-    let mut serial: Option<String> = None;
-    let mut model: Option<String> = None;
-    let mut vendor: Option<String> = None;
-    let mut apple_raw_current_capacity: Option<u64> = None;
-    let mut apple_raw_max_capacity: Option<u64> = None;
-    let mut avg_time_to_full: Option<u32> = None;
-    let mut remain_time: Option<u32> = None;
-    let mut is_charging: bool = false;
-
-    for cap in kv_re.captures_iter(&ioreg_out) {
-        let key = cap.name("k").unwrap().as_str();
-        let val_raw = cap.name("v").unwrap().as_str().trim();
-
-        match key {
-            "CycleCount" => {
-                if let Ok(n) = val_raw.parse::<u32>() {
-                    cycle_count = Some(n);
-                }
-            }
-            "AvgTimeToFull" => {
-                if let Ok(n) = val_raw.parse::<u32>() {
-                    if is_charging {
-                        avg_time_to_full = Some(n * 60); // minutes to seconds
-                    } else {
-                        avg_time_to_full = None;
-                    }
-                }
-            }
-            "TimeRemaining" => {
-                if let Ok(n) = val_raw.parse::<u32>() {
-                    remain_time = Some(n * 60); // minutes to seconds
-                }
-            }
-            "AppleRawCurrentCapacity" => {
-                if let Ok(n) = val_raw.parse::<u64>() {
-                    apple_raw_current_capacity = Some(n);
-                }
-            }
-            "AppleRawMaxCapacity" => {
-                if let Ok(n) = val_raw.parse::<u64>() {
-                    apple_raw_max_capacity = Some(n);
-                }
-            }
-            // 单位mAh/mWh，非 Wh
-            "DesignCapacity" => {
-                if let Ok(n) = val_raw.parse::<u32>() {
-                    design_capacity = Some(n);
-                } // ioreg often in mAh/mWh — best-effort convert
-            }
-            "MaxCapacity" | "MaxCapacityOperation" => {
-                if let Ok(n) = val_raw.parse::<u32>() {
-                    max_capacity = Some(n);
-                }
-            }
-            "CurrentCapacity" | "CurrentCapacityOperation" => {
-                if let Ok(n) = val_raw.parse::<u32>() {
-                    current_capacity = Some(n);
-                }
-            }
-            "IsCharging" => {
-                if let Ok(n) = val_raw.parse::<bool>() {
-                    is_charging = n;
-                }
-            }
-            "Temperature" => {
-                // ioreg temperature sometimes in 0.1 C units
-                if let Ok(n) = val_raw.parse::<f32>() {
-                    // Heuristic: if > 1000 likely 0.1C format; else assume C
-                    temp_c = Some(if n > 1000.0 { n / 100.0 } else { n / 1.0 });
-                }
-            }
-            "Voltage" => {
-                if let Ok(n) = val_raw.parse::<f32>() {
-                    // mV
-                    voltage = Some(n);
-                }
-            }
-            "Serial" | "BatterySerial" => {
-                // remove quotes if present
-                let cleaned = val_raw.trim_matches('"').to_string();
-                if !cleaned.is_empty() {
-                    serial = Some(cleaned);
-                }
-            }
-            "DeviceName" | "Model" => {
-                let cleaned = val_raw.trim_matches('"').to_string();
-                if !cleaned.is_empty() {
-                    model = Some(cleaned);
-                }
-            }
-            "Manufacturer" | "BatteryManufacturer" => {
-                let cleaned = val_raw.trim_matches('"').to_string();
-                if !cleaned.is_empty() {
-                    vendor = Some(cleaned);
-                }
+/// CPU 型号、拓扑与每个核心的频率、使用率，前端调用 invoke("get_cpu_details")
+#[tauri::command]
+async fn get_cpu_details(
+    platform: tauri::State<'_, SharedPlatform>,
+    sampler: tauri::State<'_, SharedSampler>,
+) -> Result<CpuDetails, String> {
+    let platform = platform.inner().clone();
+    let sampler = sampler.inner().clone();
+    let res = tauri::async_runtime::spawn_blocking(move || {
+        let mut details = platform.cpu_details()?;
+
+        // 使用率取自后台采样；平台拿不到当前频率时用 sysinfo 的值补上
+        let usage = sampler
+            .latest()
+            .map(|m| m.per_core_usage)
+            .unwrap_or_default();
+        let mut c = sampler.collectors();
+        c.sys.refresh_cpu_frequency();
+        for core in &mut details.cores {
+            let i = core.id as usize;
+            core.usage_percent = usage.get(i).copied();
+            if core.current_mhz.is_none() {
+                core.current_mhz = c
+                    .sys
+                    .cpus()
+                    .get(i)
+                    .map(|cpu| cpu.frequency())
+                    .filter(|f| *f > 0);
             }
-            _ => {}
         }
+        anyhow::Ok(details)
+    })
+    .await;
+    match res {
+        Ok(Ok(details)) => Ok(details),
+        Ok(Err(e)) => Err(format!("fetch cpu details error: {:?}", e)),
+        Err(e) => Err(format!("task join error: {:?}", e)),
     }
+}
 
-    // 时间估算（pmset 可能给出 estimate, 但解析 pmset 复杂；这里不做复杂估计）
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
-
-    // 构造 SingleBattery（mac 一般只有一块 internal battery）
-    let b = SingleBattery {
-        vendor,
-        model,
-        serial_number: serial,
-        state,
-        apple_raw_current_capacity,
-        apple_raw_max_capacity,
-        percentage,
-        current_capacity,
-        max_capacity,
-        design_capacity,
-        voltage,
-        temperature_c: temp_c,
-        cycle_count,
-        time_to_full_seconds: avg_time_to_full,
-        time_to_empty_seconds: remain_time,
-    };
+/// 主板页数据：BIOS、系统、主板与机箱，来自 SMBIOS（macOS 为 system_profiler）
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BoardInfo {
+    pub bios: Option<BiosInfo>,
+    pub system: Option<ProductInfo>,
+    pub baseboard: Option<BaseboardInfo>,
+    pub chassis: Option<ChassisInfo>,
+}
 
-    Ok(BatteryInfo {
-        batteries: vec![b],
-        timestamp_unix: now,
-    })
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BiosInfo {
+    pub vendor: Option<String>,
+    pub version: Option<String>,
+    /// 固件原样给出的日期，通常为 MM/DD/YYYY
+    pub release_date: Option<String>,
+    pub rom_size_kb: Option<u64>,
+    /// "major.minor"
+    pub bios_revision: Option<String>,
+    pub ec_revision: Option<String>,
 }
 
-#[derive(Serialize)]
+/// SMBIOS type 1：整机厂商与型号
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ProductInfo {
+    pub manufacturer: Option<String>,
+    pub product_name: Option<String>,
+    pub version: Option<String>,
+    pub serial_number: Option<String>,
+    pub uuid: Option<String>,
+    pub sku: Option<String>,
+    pub family: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct BaseboardInfo {
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+    pub version: Option<String>,
+    pub serial_number: Option<String>,
+    pub asset_tag: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct ChassisInfo {
+    pub manufacturer: Option<String>,
+    /// "Notebook" / "Desktop" 等，见 SMBIOS 规范的机箱类型表
+    pub chassis_type: Option<String>,
+    pub lock_present: bool,
+    pub version: Option<String>,
+    pub serial_number: Option<String>,
+    pub asset_tag: Option<String>,
+}
+
+/// 主板与 BIOS 信息，前端调用 invoke("get_board_info")
+#[tauri::command]
+async fn get_board_info(platform: tauri::State<'_, SharedPlatform>) -> Result<BoardInfo, String> {
+    let platform = platform.inner().clone();
+    let res = tauri::async_runtime::spawn_blocking(move || platform.board_info()).await;
+    match res {
+        Ok(Ok(info)) => Ok(info),
+        Ok(Err(e)) => Err(format!("fetch board info error: {:?}", e)),
+        Err(e) => Err(format!("task join error: {:?}", e)),
+    }
+}
+
+/// 内存页数据：插槽汇总与每条内存（含空插槽）
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MemoryLayout {
+    /// 插槽总数，拿不到时为 None（Apple Silicon 为板载内存）
+    pub total_slots: Option<u32>,
+    pub populated_slots: u32,
+    /// 有内存的通道数，无法从插槽位置推断通道时为 None
+    pub channels_populated: Option<u32>,
+    /// 主板支持的最大容量
+    pub max_capacity_bytes: Option<u64>,
+    pub installed_bytes: u64,
+    pub modules: Vec<MemoryModule>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MemoryModule {
+    /// 插槽位置，如 "DIMM 0"、"ChannelA-DIMM0"
+    pub slot: Option<String>,
+    pub bank: Option<String>,
+    pub channel: Option<String>,
+    /// 空插槽为 None
+    pub size_bytes: Option<u64>,
+    /// "DDR4" / "DDR5" / "LPDDR5" 等
+    pub memory_type: Option<String>,
+    /// "DIMM" / "SODIMM" / "Row Of Chips" 等
+    pub form_factor: Option<String>,
+    /// 标称速率（MT/s）
+    pub speed_mts: Option<u32>,
+    /// 实际配置的速率（MT/s）
+    pub configured_speed_mts: Option<u32>,
+    pub manufacturer: Option<String>,
+    pub part_number: Option<String>,
+    pub serial_number: Option<String>,
+    pub rank: Option<u32>,
+}
+
+/// 内存插槽与模组详情，前端调用 invoke("get_memory_modules")
+#[tauri::command]
+async fn get_memory_modules(
+    platform: tauri::State<'_, SharedPlatform>,
+) -> Result<MemoryLayout, String> {
+    let platform = platform.inner().clone();
+    let res = tauri::async_runtime::spawn_blocking(move || platform.memory_modules()).await;
+    match res {
+        Ok(Ok(layout)) => Ok(layout),
+        Ok(Err(e)) => Err(format!("fetch memory modules error: {:?}", e)),
+        Err(e) => Err(format!("task join error: {:?}", e)),
+    }
+}
+
+/// 内存细分、交换与内存压力；各平台能拿到的项不同，拿不到的为 None
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct MemoryBreakdown {
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub free_bytes: u64,
+    /// 不换出就能分配给程序的内存（Linux MemAvailable）
+    pub available_bytes: Option<u64>,
+    pub swap_total_bytes: u64,
+    pub swap_used_bytes: u64,
+    /// 分页与交换速率为两次调用之间的平均值，第一次调用只建立基准，为 None
+    pub page_in_bytes_per_sec: Option<u64>,
+    pub page_out_bytes_per_sec: Option<u64>,
+    pub swap_in_bytes_per_sec: Option<u64>,
+    pub swap_out_bytes_per_sec: Option<u64>,
+    /// 文件页缓存（macOS 为 file-backed pages）
+    pub cached_bytes: Option<u64>,
+    pub buffers_bytes: Option<u64>,
+    pub shared_bytes: Option<u64>,
+    pub slab_bytes: Option<u64>,
+    /// 等待写回磁盘的脏页
+    pub dirty_bytes: Option<u64>,
+    /// 压缩器占用的物理内存（仅 macOS）
+    pub compressed_bytes: Option<u64>,
+    pub wired_bytes: Option<u64>,
+    pub purgeable_bytes: Option<u64>,
+    /// "normal" / "warning" / "critical"（仅 macOS）
+    pub pressure_level: Option<String>,
+    /// 系统可用内存占比（macOS memory_pressure 的 free percentage，Linux 按 MemAvailable 计算）
+    pub free_percent: Option<f32>,
+    /// Linux PSI（/proc/pressure/memory），内核未开启时为 None
+    pub psi: Option<Psi>,
+}
+
+/// Pressure Stall Information：任务因等待资源而停顿的时间占比
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Psi {
+    /// 至少一个任务停顿
+    pub some: PsiLine,
+    /// 所有非空闲任务同时停顿
+    pub full: Option<PsiLine>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PsiLine {
+    /// 最近 10 / 60 / 300 秒的停顿百分比
+    pub avg10: f32,
+    pub avg60: f32,
+    pub avg300: f32,
+    /// 累计停顿时间（微秒）
+    pub total_us: u64,
+}
+
+/// 虚拟内存、交换与内存压力，前端调用 invoke("get_memory_breakdown")
+#[tauri::command]
+async fn get_memory_breakdown(
+    platform: tauri::State<'_, SharedPlatform>,
+) -> Result<MemoryBreakdown, String> {
+    let platform = platform.inner().clone();
+    let res = tauri::async_runtime::spawn_blocking(move || platform.memory_breakdown()).await;
+    match res {
+        Ok(Ok(breakdown)) => Ok(breakdown),
+        Ok(Err(e)) => Err(format!("fetch memory breakdown error: {:?}", e)),
+        Err(e) => Err(format!("task join error: {:?}", e)),
+    }
+}
+
+/// 物理磁盘，分区与其上的逻辑卷嵌套在里面
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PhysicalDisk {
+    /// 设备名，如 "nvme0n1"、"sda"、"disk0"
+    pub device: String,
+    pub model: Option<String>,
+    pub serial_number: Option<String>,
+    pub size_bytes: u64,
+    /// "SSD" / "HDD"
+    pub kind: Option<String>,
+    pub removable: bool,
+    pub read_only: bool,
+    /// 分区表类型："gpt" / "mbr" / "apm"，没有分区表时为 None
+    pub partition_table: Option<String>,
+    pub partitions: Vec<Partition>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Partition {
+    pub device: String,
+    pub size_bytes: u64,
+    /// 分区名或文件系统卷标
+    pub label: Option<String>,
+    pub file_system: Option<String>,
+    pub mount_points: Vec<String>,
+    /// 分区之上的逻辑卷，多层嵌套时按 `parent` 串起来
+    pub volumes: Vec<Volume>,
+}
+
+/// 分区之上的卷：LVM 逻辑卷、LUKS 映射、btrfs 子卷或 APFS 卷
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Volume {
+    pub name: String,
+    /// 块设备名；btrfs 子卷没有独立的设备，为 None
+    pub device: Option<String>,
+    /// "lvm" / "crypt" / "dm" / "btrfs-subvolume" / "apfs"
+    pub kind: String,
+    /// 所在的分区或上一层卷（APFS 为容器，如 "disk3"）
+    pub parent: String,
+    /// APFS 卷共享容器空间，这里为卷的已用空间
+    pub size_bytes: Option<u64>,
+    pub file_system: Option<String>,
+    pub mount_points: Vec<String>,
+}
+
+/// 物理磁盘、分区与卷的层级，前端调用 invoke("get_storage_devices")
+#[tauri::command]
+async fn get_storage_devices(
+    platform: tauri::State<'_, SharedPlatform>,
+) -> Result<Vec<PhysicalDisk>, String> {
+    let platform = platform.inner().clone();
+    let res = tauri::async_runtime::spawn_blocking(move || platform.storage_devices()).await;
+    match res {
+        Ok(Ok(disks)) => Ok(disks),
+        Ok(Err(e)) => Err(format!("fetch storage devices error: {:?}", e)),
+        Err(e) => Err(format!("task join error: {:?}", e)),
+    }
+}
+
+/// 磁盘健康状态（SMART / NVMe Health），字段缺失表示设备或数据源没有提供
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct DiskHealth {
+    /// 设备名，与 `PhysicalDisk::device` 一致
+    pub device: String,
+    pub model: Option<String>,
+    pub serial_number: Option<String>,
+    /// "nvme" / "ata"
+    pub protocol: Option<String>,
+    /// "good" / "warning" / "critical" / "unknown"
+    pub verdict: String,
+    /// 判定为 warning / critical 的原因
+    pub reasons: Vec<String>,
+    pub temperature_c: Option<f32>,
+    /// 已消耗的额定寿命百分比，可能超过 100
+    pub percentage_used: Option<u32>,
+    /// NVMe 剩余备用块百分比及其告警阈值
+    pub available_spare: Option<u32>,
+    pub available_spare_threshold: Option<u32>,
+    pub media_errors: Option<u64>,
+    pub error_log_entries: Option<u64>,
+    pub unsafe_shutdowns: Option<u64>,
+    pub power_on_hours: Option<u64>,
+    pub power_cycles: Option<u64>,
+    pub data_read_bytes: Option<u64>,
+    pub data_written_bytes: Option<u64>,
+    /// ATA SMART 属性表，NVMe 设备为空
+    pub attributes: Vec<SmartAttribute>,
+    /// 数据来源："ioctl" / "smartctl" / "system_profiler"
+    pub source: Option<String>,
+    /// 所有数据源都失败时的原因（如需要 root 权限）
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SmartAttribute {
+    pub id: u8,
+    pub name: String,
+    /// 归一化值，越小越差
+    pub value: u8,
+    pub worst: u8,
+    pub threshold: Option<u8>,
+    pub raw: u64,
+    /// 归一化值已跌到阈值以下
+    pub failing: bool,
+}
+
+/// 各物理磁盘的健康状态，前端调用 invoke("get_disk_health")
+#[tauri::command]
+async fn get_disk_health(
+    platform: tauri::State<'_, SharedPlatform>,
+) -> Result<Vec<DiskHealth>, String> {
+    let platform = platform.inner().clone();
+    let res = tauri::async_runtime::spawn_blocking(move || platform.disk_health()).await;
+    match res {
+        Ok(Ok(health)) => Ok(health),
+        Ok(Err(e)) => Err(format!("fetch disk health error: {:?}", e)),
+        Err(e) => Err(format!("task join error: {:?}", e)),
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct InterfaceInfo {
     pub name: String,
     pub mac: Option<String>,
+    /// 所有地址的文本形式（不含前缀与区域），与 `addresses` 一一对应
     pub ips: Vec<String>,
+    #[serde(default)]
+    pub addresses: Vec<IpAddress>,
+    /// "ethernet" / "wifi" / "bridge" / "tunnel" / "veth" / "virtual" / "loopback"，无法判断时为 None
+    #[serde(default)]
+    pub kind: Option<String>,
     pub is_up: bool,
     pub is_loopback: bool,
     pub mtu: Option<u32>,
 }
 
-#[derive(Serialize)]
+/// 接口上的一个地址
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IpAddress {
+    /// "ipv4" / "ipv6"
+    pub family: String,
+    pub address: IpAddr,
+    pub prefix_len: Option<u8>,
+    pub broadcast: Option<IpAddr>,
+    /// 点对点链路（如 utun）的对端地址
+    pub peer: Option<IpAddr>,
+    /// "global" / "link-local" / "ula" / "loopback"
+    pub scope: String,
+    /// IPv6 隐私扩展生成的临时地址
+    pub temporary: bool,
+    /// 已过首选生存期，不再用于新连接
+    pub deprecated: bool,
+    /// 与前面某个 IPv4 地址同网段的附加地址
+    pub secondary: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct WifiInfo {
     pub ssid: Option<String>,
     pub bssid: Option<String>,
@@ -679,8 +1072,9 @@ pub struct WifiInfo {
     pub iface: Option<String>,
 }
 
+/// 接口、网关、DNS 与 Wi-Fi 汇总，各平台返回同样的结构
 #[derive(Serialize)]
-pub struct MacNetworkStatus {
+pub struct NetworkStatus {
     pub interfaces: Vec<InterfaceInfo>,
     pub online: bool,
     pub default_gateway: Option<String>,
@@ -689,227 +1083,60 @@ pub struct MacNetworkStatus {
     pub public_ip: Option<String>,
 }
 
+/// 网络状态，前端调用 invoke("get_network_status", { includePublicIp })
 #[tauri::command]
-async fn get_network_status_macos(
+async fn get_network_status(
+    platform: tauri::State<'_, SharedPlatform>,
     include_public_ip: Option<bool>,
-) -> Result<MacNetworkStatus, String> {
+) -> Result<NetworkStatus, String> {
     let include_public = include_public_ip.unwrap_or(false);
-    fetch_network_status_macos(include_public)
+    fetch_network_status(platform.inner().clone(), include_public)
         .await
         .map_err(|e| format!("fetch error: {:?}", e))
 }
 
-// ---------- 主逻辑 (async) ----------
-async fn fetch_network_status_macos(include_public: bool) -> anyhow::Result<MacNetworkStatus> {
-    let interfaces = gather_interfaces_via_ifconfig().context("gather interfaces failed")?;
-    let wifi = get_wifi_info().ok().flatten();
-    let default_gateway = get_default_gateway().ok().flatten();
-    let dns_servers = get_dns_servers().unwrap_or_default();
-    let online = is_online_simple();
-    let public_ip = if include_public && online {
-        get_public_ip().await.ok()
-    } else {
-        None
-    };
-
-    Ok(MacNetworkStatus {
-        interfaces,
-        online,
-        default_gateway,
-        dns_servers,
-        wifi,
-        public_ip,
-    })
-}
-
-// ---------- 新实现：用 ifconfig -a 解析接口 ----------
-fn gather_interfaces_via_ifconfig() -> anyhow::Result<Vec<InterfaceInfo>> {
-    // run `ifconfig -a` and parse blocks per interface
-    let out = Command::new("ifconfig")
-        .arg("-a")
-        .output()
-        .context("running ifconfig -a")?;
-    if !out.status.success() {
-        return Err(anyhow::anyhow!("ifconfig failed"));
-    }
-    let txt = String::from_utf8_lossy(&out.stdout).to_string();
-
-    // Split into interface blocks. On macOS, interface header looks like:
-    // en0: flags=... mtu 1500
-    //     inet 192.168.1.10 netmask 0xffffff00 broadcast 192.168.1.255
-    //     inet6 ...
-    //     ether aa:bb:cc:dd:ee:ff
-    //
-    let header_re = Regex::new(r"(?m)^([0-9A-Za-z._-]+):\s+flags=.*?mtu\s+(\d+)").unwrap();
-    let inet_re = Regex::new(r"(?m)^\s+inet\s+([0-9]+\.[0-9]+\.[0-9]+\.[0-9]+)").unwrap();
-    let inet6_re = Regex::new(r"(?m)^\s+inet6\s+([0-9a-fA-F:]+)").unwrap();
-    let ether_re = Regex::new(r"(?m)^\s+ether\s+([0-9a-fA-F:]{17})").unwrap();
-    let status_active_re = Regex::new(r"(?mi)status:\s*active").unwrap();
-    let up_flag_re = Regex::new(r"(?m)flags=[0-9]+<([^>]+)>").unwrap();
-
-    // We'll iterate through header matches, take substring from header.start to next header.start
-    let mut interfaces = Vec::new();
-    let mut positions: Vec<(usize, String, u32)> = Vec::new(); // (start_idx,name,mtu)
-
-    for cap in header_re.captures_iter(&txt) {
-        if let (Some(m0), Some(m1)) = (cap.get(0), cap.get(1)) {
-            let start = m0.start();
-            let name = m1.as_str().to_string();
-            let mtu: u32 = cap
-                .get(2)
-                .and_then(|m| m.as_str().parse::<u32>().ok())
-                .unwrap_or(0);
-            positions.push((start, name, mtu));
-        }
-    }
-
-    // sort by start just in case
-    positions.sort_by_key(|p| p.0);
-
-    for (i, (start, name, mtu_val)) in positions.iter().enumerate() {
-        let end = if i + 1 < positions.len() {
-            positions[i + 1].0
-        } else {
-            txt.len()
-        };
-        let block = &txt[*start..end];
-
-        // parse ips
-        let mut ips: Vec<String> = Vec::new();
-        for cap in inet_re.captures_iter(block) {
-            if let Some(ip) = cap.get(1) {
-                ips.push(ip.as_str().to_string());
-            }
-        }
-        for cap in inet6_re.captures_iter(block) {
-            if let Some(ip) = cap.get(1) {
-                ips.push(ip.as_str().to_string());
-            }
-        }
-
-        // mac
-        let mac = ether_re
-            .captures(block)
-            .and_then(|c| c.get(1).map(|m| m.as_str().to_ascii_lowercase()));
-
-        // is_up: check status: active OR flags contain "UP" or "RUNNING"
-        let is_up = status_active_re.is_match(block)
-            || up_flag_re
-                .captures(block)
-                .map(|c| c.get(1).map(|m| m.as_str().to_string()))
-                .flatten()
-                .map(|s| {
-                    s.split(',')
-                        .any(|f| f.eq_ignore_ascii_case("UP") || f.eq_ignore_ascii_case("RUNNING"))
-                })
-                .unwrap_or(false);
-
-        // is_loopback heuristics: name starts with lo or block contains "LOOPBACK"
-        let is_loopback = name.starts_with("lo") || block.to_lowercase().contains("loopback");
-
-        let mtu = if *mtu_val > 0 { Some(*mtu_val) } else { None };
-
-        interfaces.push(InterfaceInfo {
-            name: name.clone(),
-            mac,
-            ips,
-            is_up,
-            is_loopback,
-            mtu,
-        });
-    }
-
-    // sort for determinism
-    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
-    Ok(interfaces)
-}
-
-// ---------- wifi (airport -I) ----------
-fn get_wifi_info() -> anyhow::Result<Option<WifiInfo>> {
-    let airport_path =
-        "/System/Library/PrivateFrameworks/Apple80211.framework/Versions/Current/Resources/airport";
-    if !std::path::Path::new(airport_path).exists() {
-        return Ok(None);
-    }
-
-    let out = Command::new(airport_path)
-        .arg("-I")
-        .output()
-        .context("running airport")?;
-    if !out.status.success() {
-        return Ok(None);
-    }
-    let s = String::from_utf8_lossy(&out.stdout);
-
-    let ssid = Regex::new(r"(?m)^\s*SSID:\s*(.+)$")
-        .unwrap()
-        .captures_iter(&s)
-        .next()
-        .and_then(|c| c.get(1).map(|m| m.as_str().trim().to_string()));
-    let bssid = Regex::new(r"(?m)^\s*BSSID:\s*([0-9a-fA-F:]{17})")
-        .unwrap()
-        .captures_iter(&s)
-        .next()
-        .and_then(|c| c.get(1).map(|m| m.as_str().to_string()));
-    let signal = Regex::new(r"(?m)^\s*agrCtlRSSI:\s*(-?\d+)")
-        .unwrap()
-        .captures_iter(&s)
-        .next()
-        .and_then(|c| c.get(1).and_then(|m| m.as_str().parse::<i32>().ok()));
-    let iface = Regex::new(r"(?m)^\s*interface:\s*(\w+)")
-        .unwrap()
-        .captures_iter(&s)
-        .next()
-        .and_then(|c| c.get(1).map(|m| m.as_str().to_string()));
-
-    Ok(Some(WifiInfo {
-        ssid,
-        bssid,
-        signal_dbm: signal,
-        frequency_mhz: None,
-        iface,
-    }))
-}
-
-// ---------- default gateway ----------
-fn get_default_gateway() -> anyhow::Result<Option<String>> {
-    let out = Command::new("route")
-        .args(&["-n", "get", "default"])
-        .output()
-        .context("route get default")?;
-    if !out.status.success() {
-        return Ok(None);
-    }
-    let s = String::from_utf8_lossy(&out.stdout);
-    let re = Regex::new(r"gateway:\s*([0-9]+\.[0-9]+\.[0-9]+\.[0-9]+)").unwrap();
-    if let Some(cap) = re.captures(&s) {
-        return Ok(Some(cap.get(1).unwrap().as_str().to_string()));
-    }
-    Ok(None)
+/// 旧名称，保留给尚未迁移的调用方
+#[tauri::command]
+async fn get_network_status_macos(
+    platform: tauri::State<'_, SharedPlatform>,
+    include_public_ip: Option<bool>,
+) -> Result<NetworkStatus, String> {
+    get_network_status(platform, include_public_ip).await
 }
 
-// ---------- dns servers ----------
-fn get_dns_servers() -> Option<Vec<String>> {
-    let out = Command::new("scutil").arg("--dns").output().ok()?;
-    if !out.status.success() {
-        return None;
-    }
-    let s = String::from_utf8_lossy(&out.stdout);
-    let re = Regex::new(r"nameserver\[[0-9]+\]\s*:\s*([0-9]+\.[0-9]+\.[0-9]+\.[0-9]+)").unwrap();
-    let mut v = Vec::new();
-    for cap in re.captures_iter(&s) {
-        v.push(cap.get(1).unwrap().as_str().to_string());
+// ---------- 主逻辑 (async) ----------
+async fn fetch_network_status(
+    platform: SharedPlatform,
+    include_public: bool,
+) -> anyhow::Result<NetworkStatus> {
+    // 读 sysfs / 执行命令与联网探测都会阻塞，放到阻塞线程池里
+    let res = tauri::async_runtime::spawn_blocking(move || -> anyhow::Result<NetworkStatus> {
+        Ok(NetworkStatus {
+            interfaces: platform.interfaces().context("gather interfaces failed")?,
+            online: is_online_simple(),
+            default_gateway: platform.default_gateway().ok().flatten(),
+            dns_servers: platform.dns_servers().unwrap_or_default(),
+            wifi: platform.wifi().ok().flatten(),
+            public_ip: None,
+        })
+    })
+    .await;
+    let mut status = match res {
+        Ok(Ok(status)) => status,
+        Ok(Err(e)) => return Err(e),
+        Err(e) => return Err(anyhow::anyhow!("task join error: {:?}", e)),
+    };
+    if include_public && status.online {
+        status.public_ip = get_public_ip().await.ok();
     }
-    v.sort();
-    v.dedup();
-    Some(v)
+    Ok(status)
 }
 
 // ---------- online check ----------
 fn is_online_simple() -> bool {
     use std::net::TcpStream;
     if let Ok(addr) = "1.1.1.1:53".parse() {
-        if let Ok(_) = TcpStream::connect_timeout(&addr, Duration::from_millis(800)) {
+        if TcpStream::connect_timeout(&addr, Duration::from_millis(800)).is_ok() {
             return true;
         }
     }
@@ -929,13 +1156,40 @@ async fn get_public_ip() -> anyhow::Result<String> {
     Ok(res_ip.ip)
 }
 
+fn open_store(app: &tauri::AppHandle) -> SharedStore {
+    let opened = app
+        .path()
+        .resolve(store::DB_FILE, BaseDirectory::AppData)
+        .map_err(anyhow::Error::from)
+        .and_then(|path| {
+            log::info!("Opening metrics store at {}", path.display());
+            MetricsStore::open(&path)
+        });
+    match opened {
+        Ok(store) => SharedStore::new(store),
+        Err(e) => {
+            log::error!(
+                "Failed to open metrics store, falling back to memory: {:?}",
+                e
+            );
+            SharedStore::new(MetricsStore::open_in_memory().expect("in-memory sqlite"))
+        }
+    }
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     // 初始化日志
     env_logger::init();
 
+    let platform = platform::detect();
+    log::info!("Using {} platform collectors", platform.name());
+
     tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
+        .manage(platform)
+        .manage(SharedSampler::new(Sampler::new(sampler::DEFAULT_INTERVAL)))
+        .manage(ActionGuard::default())
         .invoke_handler(tauri::generate_handler![
             greet,
             log_error,
@@ -944,7 +1198,28 @@ pub fn run() {
             get_device_info,
             get_hardware_data,
             get_system_metrics,
+            set_sampling_interval,
+            get_metrics_history,
+            list_history_metrics,
+            get_stored_metrics,
+            list_processes,
+            list_sockets,
+            get_process_details,
+            get_process_tree,
+            prepare_process_action,
+            signal_process,
+            renice_process,
             get_battery_info,
+            get_sensors,
+            get_cpu_details,
+            get_cpu_caches,
+            get_cpu_features,
+            get_board_info,
+            get_memory_modules,
+            get_memory_breakdown,
+            get_storage_devices,
+            get_disk_health,
+            get_network_status,
             get_network_status_macos
         ])
         .setup(|app| {
@@ -955,6 +1230,19 @@ pub fn run() {
                 log::info!("Tray created successfully");
             }
 
+            // 打开磁盘上的指标库，失败时退回内存库，不影响实时数据
+            let store = open_store(app.handle());
+            app.manage(store.clone());
+            store::spawn_compaction(store.clone());
+
+            // 启动后台采样
+            sampler::spawn(
+                app.handle().clone(),
+                app.state::<SharedSampler>().inner().clone(),
+                app.state::<SharedPlatform>().inner().clone(),
+                store,
+            );
+
             log::info!("Application started successfully");
             log::info!("App version: {}", app.package_info().version);
             Ok(())
@@ -0,0 +1,305 @@
+//! 通过 /sys/class/power_supply 读取电池与电源适配器状态
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+
+use super::{read_attr, read_attr_i64, read_attr_u64};
+use crate::{BatteryInfo, SingleBattery};
+
+pub const POWER_SUPPLY_ROOT: &str = "/sys/class/power_supply";
+
+/// 枚举 `root` 下所有 BAT*/CMB* 电池，并检查交流适配器是否在线
+///
+/// 没有电池（台式机或只有 Mains 条目）时返回空的 `batteries`，只有 `root` 不可读才返回错误
+pub fn read_battery_info(root: &Path, timestamp_unix: u64) -> anyhow::Result<BatteryInfo> {
+    let mut names: Vec<String> = fs::read_dir(root)
+        .with_context(|| format!("failed to read {}", root.display()))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+
+    let batteries = names
+        .iter()
+        .filter(|n| n.starts_with("BAT") || n.starts_with("CMB"))
+        .map(|n| read_single_battery(&root.join(n)))
+        .collect();
+
+    // 适配器：type 为 Mains/USB 的条目，任意一个 online=1 即认为接通
+    let mut ac_online = None;
+    for name in &names {
+        let dir = root.join(name);
+        let kind = read_attr(&dir.join("type")).unwrap_or_default();
+        if kind != "Mains" && kind != "USB" {
+            continue;
+        }
+        if let Some(online) = read_attr_u64(&dir.join("online")) {
+            ac_online = Some(ac_online.unwrap_or(false) || online == 1);
+        }
+    }
+
+    Ok(BatteryInfo {
+        batteries,
+        ac_online,
+        timestamp_unix,
+    })
+}
+
+fn read_single_battery(dir: &Path) -> SingleBattery {
+    let attr = |name: &str| read_attr(&dir.join(name));
+    let attr_u64 = |name: &str| read_attr_u64(&dir.join(name));
+
+    // 内核按驱动不同提供 energy_*（µWh）或 charge_*（µAh），统一换算成 mWh / mAh
+    let energy = attr_u64("energy_now").is_some();
+    let (now, full, full_design) = if energy {
+        (
+            attr_u64("energy_now"),
+            attr_u64("energy_full"),
+            attr_u64("energy_full_design"),
+        )
+    } else {
+        (
+            attr_u64("charge_now"),
+            attr_u64("charge_full"),
+            attr_u64("charge_full_design"),
+        )
+    };
+    // 放电速率的单位要跟容量一致：energy 配 power_now（µW），charge 配 current_now（µA），
+    // 只有另一种时借 voltage_now（µV）换算；部分驱动放电时为负数
+    let power = read_attr_i64(&dir.join("power_now")).map(|r| r.unsigned_abs());
+    let current = read_attr_i64(&dir.join("current_now")).map(|r| r.unsigned_abs());
+    let voltage = attr_u64("voltage_now").filter(|v| *v > 0);
+    let rate = if energy {
+        power.or_else(|| Some((current? as u128 * voltage? as u128 / 1_000_000) as u64))
+    } else {
+        current.or_else(|| Some((power? as u128 * 1_000_000 / voltage? as u128) as u64))
+    }
+    .filter(|r| *r > 0);
+
+    let state = match attr("status").as_deref() {
+        Some("Charging") => "Charging",
+        Some("Discharging") => "Discharging",
+        Some("Full") => "Full",
+        Some("Not charging") => "NotCharging",
+        _ => "Unknown",
+    }
+    .to_string();
+
+    let percentage = attr_u64("capacity")
+        .map(|p| p as f32)
+        .or_else(|| match (now, full) {
+            (Some(n), Some(f)) if f > 0 => Some((n as f32 / f as f32 * 100.0).min(100.0)),
+            _ => None,
+        });
+
+    // 优先使用驱动给出的估算，没有时按当前速率推算
+    let time_to_empty_seconds = attr_u64("time_to_empty_now")
+        .map(|s| s as u32)
+        .or_else(|| match (state.as_str(), now, rate) {
+            ("Discharging", Some(n), Some(r)) => Some((n as f64 / r as f64 * 3600.0) as u32),
+            _ => None,
+        });
+    let time_to_full_seconds = attr_u64("time_to_full_now").map(|s| s as u32).or_else(|| {
+        match (state.as_str(), now, full, rate) {
+            ("Charging", Some(n), Some(f), Some(r)) => {
+                Some((f.saturating_sub(n) as f64 / r as f64 * 3600.0) as u32)
+            }
+            _ => None,
+        }
+    });
+
+    SingleBattery {
+        vendor: attr("manufacturer"),
+        model: attr("model_name"),
+        serial_number: attr("serial_number"),
+        state,
+        percentage,
+        apple_raw_current_capacity: None,
+        apple_raw_max_capacity: None,
+        design_capacity: full_design.map(|v| (v / 1000) as u32),
+        current_capacity: now.map(|v| (v / 1000) as u32),
+        max_capacity: full.map(|v| (v / 1000) as u32),
+        time_to_full_seconds,
+        time_to_empty_seconds,
+        // µV -> mV
+        voltage: voltage.map(|v| v as f32 / 1000.0),
+        // 0.1 °C
+        temperature_c: read_attr_i64(&dir.join("temp")).map(|t| t as f32 / 10.0),
+        cycle_count: attr_u64("cycle_count").filter(|c| *c > 0).map(|c| c as u32),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn supply(root: &Path, name: &str, attrs: &[(&str, &str)]) {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        for (attr, value) in attrs {
+            fs::write(dir.join(attr), format!("{value}\n")).unwrap();
+        }
+    }
+
+    #[test]
+    fn energy_attributes_discharging() {
+        let root = tempfile::tempdir().unwrap();
+        supply(
+            root.path(),
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("status", "Discharging"),
+                ("manufacturer", "SMP"),
+                ("model_name", "5B10W13930"),
+                ("energy_now", "30000000"),
+                ("energy_full", "50000000"),
+                ("energy_full_design", "57000000"),
+                ("power_now", "10000000"),
+                ("voltage_now", "11520000"),
+                ("cycle_count", "0"),
+            ],
+        );
+        supply(root.path(), "AC", &[("type", "Mains"), ("online", "0")]);
+
+        let info = read_battery_info(root.path(), 42).unwrap();
+        assert_eq!(info.timestamp_unix, 42);
+        assert_eq!(info.ac_online, Some(false));
+        assert_eq!(info.batteries.len(), 1);
+        let bat = &info.batteries[0];
+        assert_eq!(bat.state, "Discharging");
+        assert_eq!(bat.vendor.as_deref(), Some("SMP"));
+        assert_eq!(bat.current_capacity, Some(30000));
+        assert_eq!(bat.max_capacity, Some(50000));
+        assert_eq!(bat.design_capacity, Some(57000));
+        assert!((bat.percentage.unwrap() - 60.0).abs() < 0.01);
+        // 30 Wh / 10 W = 3 h
+        assert_eq!(bat.time_to_empty_seconds, Some(3 * 3600));
+        assert_eq!(bat.time_to_full_seconds, None);
+        assert_eq!(bat.voltage, Some(11520.0));
+        assert_eq!(bat.cycle_count, None);
+    }
+
+    #[test]
+    fn charge_attributes_charging() {
+        let root = tempfile::tempdir().unwrap();
+        supply(
+            root.path(),
+            "BAT1",
+            &[
+                ("status", "Charging"),
+                ("capacity", "75"),
+                ("charge_now", "3000000"),
+                ("charge_full", "4000000"),
+                ("charge_full_design", "4400000"),
+                ("current_now", "-2000000"),
+                ("temp", "312"),
+                ("cycle_count", "87"),
+            ],
+        );
+        supply(root.path(), "ADP1", &[("type", "Mains"), ("online", "1")]);
+
+        let info = read_battery_info(root.path(), 0).unwrap();
+        assert_eq!(info.ac_online, Some(true));
+        let bat = &info.batteries[0];
+        assert_eq!(bat.state, "Charging");
+        assert_eq!(bat.percentage, Some(75.0));
+        assert_eq!(bat.current_capacity, Some(3000));
+        assert_eq!(bat.max_capacity, Some(4000));
+        assert_eq!(bat.design_capacity, Some(4400));
+        // 还差 1 Ah，以 2 A 充电需要半小时；负号的 current_now 取绝对值
+        assert_eq!(bat.time_to_full_seconds, Some(1800));
+        assert_eq!(bat.time_to_empty_seconds, None);
+        assert_eq!(bat.temperature_c, Some(31.2));
+        assert_eq!(bat.cycle_count, Some(87));
+    }
+
+    #[test]
+    fn rate_units_match_capacity() {
+        let root = tempfile::tempdir().unwrap();
+        // energy_* 配 current_now：用电压换算成功率，12 V × 2.5 A = 30 W
+        supply(
+            root.path(),
+            "BAT0",
+            &[
+                ("status", "Discharging"),
+                ("energy_now", "30000000"),
+                ("energy_full", "60000000"),
+                ("current_now", "2500000"),
+                ("voltage_now", "12000000"),
+            ],
+        );
+        // 没有电压时两种单位无法换算，不给估算
+        supply(
+            root.path(),
+            "BAT1",
+            &[
+                ("status", "Discharging"),
+                ("energy_now", "30000000"),
+                ("current_now", "2500000"),
+            ],
+        );
+        // charge_* 配 power_now：24 W / 12 V = 2 A，还差 1 Ah 需要半小时
+        supply(
+            root.path(),
+            "BAT2",
+            &[
+                ("status", "Charging"),
+                ("charge_now", "3000000"),
+                ("charge_full", "4000000"),
+                ("power_now", "24000000"),
+                ("voltage_now", "12000000"),
+            ],
+        );
+
+        let info = read_battery_info(root.path(), 0).unwrap();
+        let bats = &info.batteries;
+        // 30 Wh / 30 W = 1 h
+        assert_eq!(bats[0].time_to_empty_seconds, Some(3600));
+        assert_eq!(bats[1].time_to_empty_seconds, None);
+        assert_eq!(bats[2].time_to_full_seconds, Some(1800));
+    }
+
+    #[test]
+    fn full_and_unknown_status() {
+        let root = tempfile::tempdir().unwrap();
+        supply(
+            root.path(),
+            "BAT0",
+            &[("status", "Full"), ("capacity", "100")],
+        );
+        supply(root.path(), "CMB1", &[("status", "Bogus")]);
+
+        let info = read_battery_info(root.path(), 0).unwrap();
+        let states: Vec<&str> = info.batteries.iter().map(|b| b.state.as_str()).collect();
+        assert_eq!(states, ["Full", "Unknown"]);
+        assert_eq!(info.ac_online, None);
+    }
+
+    #[test]
+    fn mains_only_has_no_batteries() {
+        let root = tempfile::tempdir().unwrap();
+        supply(root.path(), "AC", &[("type", "Mains"), ("online", "1")]);
+        supply(
+            root.path(),
+            "ucsi-source-psy-1",
+            &[("type", "USB"), ("online", "0")],
+        );
+
+        let info = read_battery_info(root.path(), 0).unwrap();
+        assert!(info.batteries.is_empty());
+        assert_eq!(info.ac_online, Some(true));
+    }
+
+    #[test]
+    fn empty_root_and_missing_root() {
+        let root = tempfile::tempdir().unwrap();
+        let info = read_battery_info(root.path(), 0).unwrap();
+        assert!(info.batteries.is_empty());
+        assert_eq!(info.ac_online, None);
+
+        assert!(read_battery_info(&root.path().join("missing"), 0).is_err());
+    }
+}
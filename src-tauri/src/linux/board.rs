@@ -0,0 +1,83 @@
+//! 主板与 BIOS 信息：优先解析 /sys/firmware/dmi/tables/DMI 中的原始 SMBIOS 表
+//!
+//! 原始表只有 root 可读，普通用户运行时退回到 /sys/class/dmi/id 下内核已经解码好的属性；
+//! 这些属性里序列号类字段同样只有 root 可读，读不到时为 None。
+//! 内存模组（SMBIOS type 17）内核没有导出对应属性，只能读原始表。
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+
+use super::read_attr;
+use crate::smbios;
+use crate::{BaseboardInfo, BiosInfo, BoardInfo, ChassisInfo, MemoryLayout, ProductInfo};
+
+pub const DMI_TABLE_PATH: &str = "/sys/firmware/dmi/tables/DMI";
+
+pub fn read_board_info(dmi_table: &Path, dmi_id: &Path) -> BoardInfo {
+    match fs::read(dmi_table)
+        .map_err(anyhow::Error::from)
+        .and_then(|table| smbios::decode(&table))
+    {
+        Ok(info) => info,
+        Err(e) => {
+            log::debug!("SMBIOS table unavailable, using {:?}: {:?}", dmi_id, e);
+            read_dmi_id(dmi_id)
+        }
+    }
+}
+
+pub fn read_memory_modules(dmi_table: &Path) -> anyhow::Result<MemoryLayout> {
+    let table = fs::read(dmi_table).with_context(|| {
+        format!(
+            "failed to read {} (reading memory modules requires root)",
+            dmi_table.display()
+        )
+    })?;
+    smbios::decode_memory(&table)
+}
+
+/// 从内核导出的 DMI 属性组装，字段与 SMBIOS 解码结果一致
+pub fn read_dmi_id(dir: &Path) -> BoardInfo {
+    let attr = |name: &str| read_attr(&dir.join(name)).filter(|v| !smbios::is_placeholder(v));
+
+    BoardInfo {
+        bios: Some(BiosInfo {
+            vendor: attr("bios_vendor"),
+            version: attr("bios_version"),
+            release_date: attr("bios_date"),
+            rom_size_kb: None,
+            bios_revision: attr("bios_release"),
+            ec_revision: attr("ec_firmware_release"),
+        }),
+        system: Some(ProductInfo {
+            manufacturer: attr("sys_vendor"),
+            product_name: attr("product_name"),
+            version: attr("product_version"),
+            serial_number: attr("product_serial"),
+            uuid: attr("product_uuid").map(|u| u.to_uppercase()),
+            sku: attr("product_sku"),
+            family: attr("product_family"),
+        }),
+        baseboard: Some(BaseboardInfo {
+            manufacturer: attr("board_vendor"),
+            product: attr("board_name"),
+            version: attr("board_version"),
+            serial_number: attr("board_serial"),
+            asset_tag: attr("board_asset_tag"),
+        }),
+        chassis: Some(ChassisInfo {
+            manufacturer: attr("chassis_vendor"),
+            // chassis_type 是数字；内核不导出锁标志位
+            chassis_type: attr("chassis_type")
+                .and_then(|t| t.parse().ok())
+                .and_then(smbios::chassis_type_name)
+                .map(str::to_string),
+            lock_present: false,
+            version: attr("chassis_version"),
+            serial_number: attr("chassis_serial"),
+            asset_tag: attr("chassis_asset_tag"),
+        }),
+    }
+}
@@ -0,0 +1,135 @@
+//! 通过 /sys/devices/system/cpu/cpuN/cache/indexM 读取缓存层级
+//!
+//! 每个逻辑 CPU 都列出自己能看到的全部缓存，共享缓存会在多个 CPU 下重复出现，
+//! 按 shared_cpu_list 去重后每个缓存实例只保留一条。
+
+use std::path::Path;
+
+use super::cpu::read_cpu_list;
+use super::{numbered_entries, read_attr, read_attr_u64};
+use crate::CacheInfo;
+
+/// `sys_devices` 通常为 /sys/devices；读不到任何缓存时返回空列表
+pub fn read_caches(sys_devices: &Path) -> Vec<CacheInfo> {
+    let mut caches = Vec::new();
+    for cpu in numbered_entries(&sys_devices.join("system/cpu"), "cpu") {
+        for index in numbered_entries(&cpu.join("cache"), "index") {
+            if let Some(cache) = read_index(&index) {
+                caches.push(cache);
+            }
+        }
+    }
+    crate::cache::dedup(caches)
+}
+
+fn read_index(dir: &Path) -> Option<CacheInfo> {
+    let kind = match read_attr(&dir.join("type"))?.as_str() {
+        "Data" => "data",
+        "Instruction" => "instruction",
+        "Unified" => "unified",
+        _ => return None,
+    };
+    let size_bytes = parse_size(&read_attr(&dir.join("size"))?)?;
+    let attr = |name: &str| read_attr_u64(&dir.join(name)).map(|v| v as u32);
+    let shared_cpus = read_cpu_list(&dir.join("shared_cpu_list"));
+
+    Some(CacheInfo {
+        level: attr("level")?,
+        kind: kind.to_string(),
+        size_bytes,
+        ways: attr("ways_of_associativity").filter(|w| *w > 0),
+        line_size: attr("coherency_line_size"),
+        sets: attr("number_of_sets"),
+        shared_by: shared_cpus.len().max(1) as u32,
+        shared_cpus,
+    })
+}
+
+/// sysfs 的 size 形如 `48K`、`12M`
+pub fn parse_size(s: &str) -> Option<u64> {
+    let s = s.trim();
+    let (num, unit) = match s.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => s.split_at(i),
+        None => (s, ""),
+    };
+    let mult = match unit.trim() {
+        "" => 1,
+        "K" => 1024,
+        "M" => 1024 * 1024,
+        "G" => 1024 * 1024 * 1024,
+        _ => return None,
+    };
+    num.parse::<u64>().ok().map(|n| n * mult)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linux::fixture_root;
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("32K"), Some(32 * 1024));
+        assert_eq!(parse_size("1M"), Some(1024 * 1024));
+        assert_eq!(parse_size("12288K\n"), Some(12 * 1024 * 1024));
+        assert_eq!(parse_size("2G"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("512"), Some(512));
+        assert_eq!(parse_size("1T"), None);
+        assert_eq!(parse_size("K"), None);
+        assert_eq!(parse_size(""), None);
+    }
+
+    #[test]
+    fn intel_laptop_shared_caches() {
+        let caches = read_caches(&fixture_root("intel-laptop").join("devices"));
+        let count = |level: u32, kind: &str| {
+            caches
+                .iter()
+                .filter(|c| c.level == level && c.kind == kind)
+                .count()
+        };
+        // 2 个超线程性能核 + 8 个能效核各有自己的 L1
+        assert_eq!(count(1, "instruction"), 10);
+        assert_eq!(count(1, "data"), 10);
+        // 性能核各自一个 L2，能效核每 4 个一组共享
+        assert_eq!(count(2, "unified"), 4);
+        // 12 个 CPU 下重复出现的 L3 只保留一条
+        assert_eq!(count(3, "unified"), 1);
+        assert_eq!(caches.len(), 25);
+
+        assert_eq!(caches[0].kind, "instruction");
+        assert_eq!(caches[0].shared_cpus, [0, 1]);
+        assert_eq!(caches[0].size_bytes, 32 * 1024);
+        let e_l1i = &caches[2];
+        assert_eq!(e_l1i.shared_cpus, [4]);
+        assert_eq!(e_l1i.size_bytes, 64 * 1024);
+
+        let l2: Vec<(&[u32], u64)> = caches
+            .iter()
+            .filter(|c| c.level == 2)
+            .map(|c| (c.shared_cpus.as_slice(), c.size_bytes))
+            .collect();
+        assert_eq!(
+            l2,
+            [
+                (&[0, 1][..], 1280 * 1024),
+                (&[2, 3], 1280 * 1024),
+                (&[4, 5, 6, 7], 2048 * 1024),
+                (&[8, 9, 10, 11], 2048 * 1024),
+            ]
+        );
+
+        let l3 = caches.last().unwrap();
+        assert_eq!(l3.size_bytes, 12 * 1024 * 1024);
+        assert_eq!(l3.shared_by, 12);
+        assert_eq!(
+            (l3.ways, l3.line_size, l3.sets),
+            (Some(12), Some(64), Some(16384))
+        );
+    }
+
+    #[test]
+    fn missing_cache_dirs() {
+        assert!(read_caches(&fixture_root("intel-laptop").join("missing")).is_empty());
+    }
+}
@@ -0,0 +1,273 @@
+//! 通过 /proc/cpuinfo 与 /sys/devices/system/cpu 读取 CPU 型号、拓扑和频率
+//!
+//! - 型号：cpuinfo 第一个处理器块的 vendor_id / model name / cpu family / model / stepping
+//! - 拓扑：`cpuN/topology/` 下的 physical_package_id、die_id、cluster_id、core_id、
+//!   thread_siblings_list
+//! - 频率：`cpuN/cpufreq/` 下的 scaling_cur_freq、cpuinfo_min_freq、cpuinfo_max_freq（kHz）
+//! - 大小核：Intel 混合架构看 `/sys/devices/cpu_core/cpus` 与 `cpu_atom/cpus`，
+//!   ARM big.LITTLE 看各核心的 `cpu_capacity`，容量最大的为性能核
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use super::{numbered_entries, read_attr_u64};
+use crate::{CpuCore, CpuDetails};
+
+pub const SYS_DEVICES_ROOT: &str = "/sys/devices";
+pub const CPUINFO_PATH: &str = "/proc/cpuinfo";
+
+/// `sys_devices` 通常为 /sys/devices，`cpuinfo` 为 /proc/cpuinfo 的内容
+pub fn read_cpu_details(sys_devices: &Path, cpuinfo: &str) -> CpuDetails {
+    let mut details = parse_cpuinfo(cpuinfo);
+
+    let hybrid_p = read_cpu_list(&sys_devices.join("cpu_core/cpus"));
+    let hybrid_e = read_cpu_list(&sys_devices.join("cpu_atom/cpus"));
+
+    for dir in numbered_entries(&sys_devices.join("system/cpu"), "cpu") {
+        let Some(id) = dir
+            .file_name()
+            .and_then(|n| n.to_str())
+            .and_then(|n| n.strip_prefix("cpu"))
+            .and_then(|n| n.parse().ok())
+        else {
+            continue;
+        };
+        let topo = |name: &str| read_attr_u64(&dir.join("topology").join(name)).map(|v| v as u32);
+        // kHz -> MHz
+        let freq = |name: &str| read_attr_u64(&dir.join("cpufreq").join(name)).map(|v| v / 1000);
+
+        let kind = if hybrid_p.contains(&id) {
+            Some("performance")
+        } else if hybrid_e.contains(&id) {
+            Some("efficiency")
+        } else {
+            None
+        };
+
+        details.cores.push(CpuCore {
+            id,
+            package_id: topo("physical_package_id"),
+            die_id: topo("die_id"),
+            cluster_id: topo("cluster_id"),
+            core_id: topo("core_id"),
+            smt_siblings: read_cpu_list(&dir.join("topology/thread_siblings_list")),
+            kind: kind.map(str::to_string),
+            current_mhz: freq("scaling_cur_freq"),
+            min_mhz: freq("cpuinfo_min_freq"),
+            max_mhz: freq("cpuinfo_max_freq"),
+            usage_percent: None,
+        });
+    }
+
+    if hybrid_p.is_empty() && hybrid_e.is_empty() {
+        classify_by_capacity(sys_devices, &mut details.cores);
+    }
+    summarize(&mut details);
+    details
+}
+
+/// cpuinfo 中第一个处理器块的型号信息（各核心相同）
+pub fn parse_cpuinfo(cpuinfo: &str) -> CpuDetails {
+    let mut details = CpuDetails::default();
+    for line in cpuinfo.lines() {
+        if line.trim().is_empty() {
+            break;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "vendor_id" => details.vendor = Some(value.to_string()),
+            "model name" => details.brand = Some(value.to_string()),
+            "cpu family" => details.family = value.parse().ok(),
+            "model" => details.model = value.parse().ok(),
+            "stepping" => details.stepping = value.parse().ok(),
+            _ => {}
+        }
+    }
+    details
+}
+
+/// 解析 `0-3,8,10-11` 形式的 CPU 列表
+pub fn parse_cpu_list(s: &str) -> Vec<u32> {
+    let mut cpus = Vec::new();
+    for part in s.trim().split(',').filter(|p| !p.is_empty()) {
+        match part.split_once('-') {
+            Some((a, b)) => {
+                if let (Ok(a), Ok(b)) = (a.parse::<u32>(), b.parse::<u32>()) {
+                    cpus.extend(a..=b);
+                }
+            }
+            None => cpus.extend(part.parse::<u32>().ok()),
+        }
+    }
+    cpus
+}
+
+pub fn read_cpu_list(path: &Path) -> Vec<u32> {
+    fs::read_to_string(path)
+        .map(|s| parse_cpu_list(&s))
+        .unwrap_or_default()
+}
+
+/// 没有混合架构接口时按 cpu_capacity 区分，所有核心容量相同则不区分
+fn classify_by_capacity(sys_devices: &Path, cores: &mut [CpuCore]) {
+    let capacities: Vec<Option<u64>> = cores
+        .iter()
+        .map(|c| read_attr_u64(&sys_devices.join(format!("system/cpu/cpu{}/cpu_capacity", c.id))))
+        .collect();
+    let max = capacities.iter().flatten().max().copied();
+    let min = capacities.iter().flatten().min().copied();
+    let (Some(max), Some(min)) = (max, min) else {
+        return;
+    };
+    if max == min {
+        return;
+    }
+    for (core, capacity) in cores.iter_mut().zip(capacities) {
+        core.kind = capacity.map(|c| {
+            if c == max {
+                "performance"
+            } else {
+                "efficiency"
+            }
+            .to_string()
+        });
+    }
+}
+
+/// 由逻辑核心列表汇总封装数、物理核心数与大小核数量
+fn summarize(details: &mut CpuDetails) {
+    details.logical_cores = details.cores.len() as u32;
+
+    let packages: HashSet<u32> = details.cores.iter().filter_map(|c| c.package_id).collect();
+    details.packages = (!packages.is_empty()).then_some(packages.len() as u32);
+
+    // SMT 兄弟线程共享一个物理核心，按兄弟列表去重
+    let mut physical = HashSet::new();
+    let (mut p_cores, mut e_cores) = (0, 0);
+    for core in &details.cores {
+        if core.smt_siblings.is_empty() || !physical.insert(core.smt_siblings.clone()) {
+            continue;
+        }
+        match core.kind.as_deref() {
+            Some("performance") => p_cores += 1,
+            Some("efficiency") => e_cores += 1,
+            _ => {}
+        }
+    }
+    details.physical_cores = (!physical.is_empty()).then_some(physical.len() as u32);
+    if p_cores + e_cores > 0 {
+        details.performance_cores = Some(p_cores);
+        details.efficiency_cores = Some(e_cores);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linux::fixture_root;
+
+    #[test]
+    fn intel_laptop_hybrid() {
+        let root = fixture_root("intel-laptop");
+        let cpuinfo = fs::read_to_string(root.join("cpuinfo")).unwrap();
+        let details = read_cpu_details(&root.join("devices"), &cpuinfo);
+
+        assert_eq!(details.vendor.as_deref(), Some("GenuineIntel"));
+        assert_eq!(
+            details.brand.as_deref(),
+            Some("12th Gen Intel(R) Core(TM) i5-1235U")
+        );
+        assert_eq!(
+            (details.family, details.model, details.stepping),
+            (Some(6), Some(154), Some(4))
+        );
+        assert_eq!(details.packages, Some(1));
+        assert_eq!(details.logical_cores, 12);
+        // 2 个带超线程的性能核 + 8 个能效核
+        assert_eq!(details.physical_cores, Some(10));
+        assert_eq!(details.performance_cores, Some(2));
+        assert_eq!(details.efficiency_cores, Some(8));
+
+        let ids: Vec<u32> = details.cores.iter().map(|c| c.id).collect();
+        assert_eq!(ids, (0..12).collect::<Vec<_>>());
+        let kinds: Vec<&str> = details
+            .cores
+            .iter()
+            .map(|c| c.kind.as_deref().unwrap())
+            .collect();
+        assert_eq!(&kinds[..4], ["performance"; 4]);
+        assert_eq!(&kinds[4..], ["efficiency"; 8]);
+
+        let p = &details.cores[1];
+        assert_eq!(p.core_id, Some(0));
+        assert_eq!(p.cluster_id, Some(0));
+        assert_eq!(p.smt_siblings, [0, 1]);
+        assert_eq!(
+            (p.current_mhz, p.min_mhz, p.max_mhz),
+            (Some(1400), Some(400), Some(4400))
+        );
+        let e = &details.cores[11];
+        assert_eq!(e.core_id, Some(15));
+        assert_eq!(e.cluster_id, Some(12));
+        assert_eq!(e.smt_siblings, [11]);
+        assert_eq!(
+            (e.current_mhz, e.min_mhz, e.max_mhz),
+            (Some(1450), Some(400), Some(3300))
+        );
+    }
+
+    #[test]
+    fn big_little_by_capacity() {
+        let tmp = tempfile::tempdir().unwrap();
+        for (id, capacity) in [(0, 446), (1, 446), (2, 1024), (3, 1024)] {
+            let dir = tmp.path().join(format!("system/cpu/cpu{}", id));
+            fs::create_dir_all(dir.join("topology")).unwrap();
+            fs::write(dir.join("cpu_capacity"), format!("{}\n", capacity)).unwrap();
+            fs::write(
+                dir.join("topology/thread_siblings_list"),
+                format!("{}\n", id),
+            )
+            .unwrap();
+            fs::write(dir.join("topology/physical_package_id"), "0\n").unwrap();
+        }
+        let details = read_cpu_details(tmp.path(), "");
+        let kinds: Vec<Option<&str>> = details.cores.iter().map(|c| c.kind.as_deref()).collect();
+        assert_eq!(
+            kinds,
+            [
+                Some("efficiency"),
+                Some("efficiency"),
+                Some("performance"),
+                Some("performance")
+            ]
+        );
+        assert_eq!(details.performance_cores, Some(2));
+        assert_eq!(details.efficiency_cores, Some(2));
+        assert_eq!(details.cores[0].current_mhz, None);
+
+        // 容量相同时不区分大小核
+        for id in 0..2 {
+            fs::write(
+                tmp.path()
+                    .join(format!("system/cpu/cpu{}/cpu_capacity", id)),
+                "1024\n",
+            )
+            .unwrap();
+        }
+        let details = read_cpu_details(tmp.path(), "");
+        assert!(details.cores.iter().all(|c| c.kind.is_none()));
+        assert_eq!(details.performance_cores, None);
+    }
+
+    #[test]
+    fn cpu_lists() {
+        assert_eq!(parse_cpu_list("0-3,8,10-11\n"), [0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(parse_cpu_list("5"), [5]);
+        assert_eq!(parse_cpu_list("x,2,3-y"), [2]);
+        assert!(parse_cpu_list("\n").is_empty());
+    }
+}
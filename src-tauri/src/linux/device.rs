@@ -0,0 +1,161 @@
+//! 设备信息：/sys/class/dmi/id、/etc/machine-id 与 /proc/stat
+//!
+//! DMI 里的 product_serial、product_uuid、board_serial 默认只有 root 可读，
+//! 读取被拒绝时记入 `DeviceInfo::restricted`，与“固件没有提供”区分开。
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use crate::platform::format_utc;
+use crate::smbios;
+use crate::DeviceInfo;
+
+pub const DMI_ROOT: &str = "/sys/class/dmi/id";
+pub const MACHINE_ID_PATH: &str = "/etc/machine-id";
+pub const PROC_STAT_PATH: &str = "/proc/stat";
+
+pub fn read_device_info(
+    dmi: &Path,
+    machine_id: &Path,
+    proc_stat: &Path,
+    uptime_seconds: u64,
+) -> DeviceInfo {
+    let mut restricted = Vec::new();
+    let mut read = |path: &Path, field: &str| match fs::read_to_string(path) {
+        Ok(s) => clean(&s),
+        Err(e) => {
+            if e.kind() == ErrorKind::PermissionDenied {
+                restricted.push(field.to_string());
+            }
+            None
+        }
+    };
+
+    let serial_number = read(&dmi.join("product_serial"), "serial_number");
+    let hardware_uuid = read(&dmi.join("product_uuid"), "hardware_uuid");
+    let product_name = read(&dmi.join("product_name"), "product_name");
+    let manufacturer = read(&dmi.join("sys_vendor"), "manufacturer");
+    let board_vendor = read(&dmi.join("board_vendor"), "board_vendor");
+    let board_name = read(&dmi.join("board_name"), "board_name");
+    let board_serial = read(&dmi.join("board_serial"), "board_serial");
+    let machine_id = read(machine_id, "machine_id");
+
+    let boot_time_utc = fs::read_to_string(proc_stat)
+        .ok()
+        .and_then(|s| parse_btime(&s))
+        .and_then(format_utc);
+
+    DeviceInfo {
+        serial_number,
+        hardware_uuid,
+        model_identifier: product_name.clone(),
+        boot_time_utc,
+        uptime_seconds,
+        manufacturer,
+        product_name,
+        board_vendor,
+        board_name,
+        board_serial,
+        machine_id,
+        restricted,
+    }
+}
+
+fn clean(s: &str) -> Option<String> {
+    let s = s.trim();
+    (!s.is_empty() && !smbios::is_placeholder(s)).then(|| s.to_string())
+}
+
+/// /proc/stat 中的 `btime <秒>`
+pub fn parse_btime(stat: &str) -> Option<i64> {
+    stat.lines()
+        .find_map(|line| line.strip_prefix("btime "))
+        .and_then(|v| v.trim().parse().ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linux::fixture_root;
+
+    #[test]
+    fn intel_laptop() {
+        let root = fixture_root("intel-laptop");
+        let info = read_device_info(
+            &root.join("dmi/id"),
+            &root.join("machine-id"),
+            &root.join("stat"),
+            3600,
+        );
+        assert_eq!(info.manufacturer.as_deref(), Some("LENOVO"));
+        assert_eq!(info.product_name.as_deref(), Some("21CB0067CD"));
+        assert_eq!(info.model_identifier.as_deref(), Some("21CB0067CD"));
+        assert_eq!(info.serial_number.as_deref(), Some("PF3ABCDE"));
+        assert_eq!(
+            info.hardware_uuid.as_deref(),
+            Some("4c4c4544-0031-3510-8052-b3c04f4a3732")
+        );
+        assert_eq!(info.board_vendor.as_deref(), Some("LENOVO"));
+        assert_eq!(info.board_name.as_deref(), Some("21CB0067CD"));
+        assert_eq!(info.board_serial.as_deref(), Some("L1HF2AB00CD"));
+        assert_eq!(
+            info.machine_id.as_deref(),
+            Some("0f3c9e2a7b5d4e1c8a6b2d9f0e7c5a13")
+        );
+        assert_eq!(info.boot_time_utc.as_deref(), Some("2025-09-30T01:12:44Z"));
+        assert_eq!(info.uptime_seconds, 3600);
+        assert!(info.restricted.is_empty());
+    }
+
+    #[test]
+    fn placeholders_and_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let dmi = dir.path();
+        fs::write(dmi.join("sys_vendor"), "To Be Filled By O.E.M.\n").unwrap();
+        fs::write(dmi.join("board_name"), "\n").unwrap();
+
+        let info = read_device_info(dmi, &dmi.join("machine-id"), &dmi.join("stat"), 0);
+        assert_eq!(info.manufacturer, None);
+        assert_eq!(info.board_name, None);
+        assert_eq!(info.board_serial, None);
+        assert_eq!(info.machine_id, None);
+        assert_eq!(info.boot_time_utc, None);
+        // 不存在的文件不算受限
+        assert!(info.restricted.is_empty());
+    }
+
+    #[test]
+    fn root_only_fields_are_restricted() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let dmi = dir.path();
+        for name in ["product_serial", "board_serial"] {
+            fs::write(dmi.join(name), "PF3ABCDE\n").unwrap();
+            fs::set_permissions(dmi.join(name), fs::Permissions::from_mode(0o000)).unwrap();
+        }
+
+        let info = read_device_info(dmi, &dmi.join("machine-id"), &dmi.join("stat"), 0);
+        // root 不受文件权限限制，这时应当照常读到，而不是误报受限
+        // SAFETY: geteuid 不接收参数，总是成功
+        if unsafe { nix::libc::geteuid() } == 0 {
+            assert_eq!(info.serial_number.as_deref(), Some("PF3ABCDE"));
+            assert_eq!(info.board_serial.as_deref(), Some("PF3ABCDE"));
+            assert!(info.restricted.is_empty());
+            return;
+        }
+        assert_eq!(info.serial_number, None);
+        assert_eq!(info.board_serial, None);
+        assert_eq!(info.restricted, ["serial_number", "board_serial"]);
+    }
+
+    #[test]
+    fn btime() {
+        assert_eq!(
+            parse_btime("cpu  1 2 3\nbtime 1759194764\n"),
+            Some(1759194764)
+        );
+        assert_eq!(parse_btime("cpu  1 2 3\n"), None);
+    }
+}
@@ -0,0 +1,297 @@
+//! 块设备 I/O 计数：/proc/diskstats，按 /sys/block 归并分区，再用 /proc/mounts 找挂载点
+//!
+//! diskstats 每行在主次设备号和设备名之后依次为：读完成数、读合并数、读扇区数、读耗时、
+//! 写完成数、写合并数、写扇区数、写耗时、进行中的请求数、io_ticks（ms）、加权耗时（ms）。
+//! 扇区固定按 512 字节计，与设备的实际扇区大小无关。
+//!
+//! 挂载源与设备的对应：整盘和分区按设备名匹配（分区是 `/sys/block/<disk>` 下以盘名开头的子目录）；
+//! LUKS、LVM 等 device-mapper 设备在 /sys/block 中是 `dm-N`，而 /proc/mounts 里通常写成
+//! `/dev/mapper/<name>`（LVM 逻辑卷为 `<vg>-<lv>`），`<name>` 取自 `/sys/block/dm-N/dm/name`。
+//! 下层物理盘的计数里已经包含了 dm 设备的 I/O，两者的挂载点不会重复归到物理盘上。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+
+use super::read_attr;
+use crate::disk::DiskCounters;
+
+pub const DISKSTATS_PATH: &str = "/proc/diskstats";
+pub const SYS_BLOCK_ROOT: &str = "/sys/block";
+pub const MOUNTS_PATH: &str = "/proc/mounts";
+
+const SECTOR_SIZE: u64 = 512;
+
+/// 回环与内存盘不是真正的存储设备
+const IGNORED_PREFIXES: &[&str] = &["loop", "ram"];
+
+pub fn read_disk_counters(
+    diskstats: &Path,
+    sys_block: &Path,
+    mounts: &Path,
+) -> anyhow::Result<Vec<DiskCounters>> {
+    let stats = fs::read_to_string(diskstats)
+        .with_context(|| format!("failed to read {}", diskstats.display()))?;
+    let stats = parse_diskstats(&stats);
+    let mounts = fs::read_to_string(mounts)
+        .map(|s| parse_mounts(&s))
+        .unwrap_or_default();
+
+    let mut devices: Vec<String> = fs::read_dir(sys_block)
+        .with_context(|| format!("failed to read {}", sys_block.display()))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|name| !IGNORED_PREFIXES.iter().any(|p| name.starts_with(p)))
+        .collect();
+    devices.sort();
+
+    Ok(devices
+        .into_iter()
+        .filter_map(|device| {
+            let fields = stats.get(&device)?;
+            let members = members(&sys_block.join(&device), &device);
+            let mut mount_points: Vec<String> = mounts
+                .iter()
+                .filter(|m| members.contains(&m.source))
+                .map(|m| m.target.clone())
+                .collect();
+            mount_points.sort();
+            let field = |i: usize| fields.get(i).copied().unwrap_or(0);
+            Some(DiskCounters {
+                device,
+                mount_points,
+                read_bytes: field(2) * SECTOR_SIZE,
+                write_bytes: field(6) * SECTOR_SIZE,
+                reads: field(0),
+                writes: field(4),
+                busy_ms: fields.get(9).copied(),
+                queue_ms: fields.get(10).copied(),
+            })
+        })
+        .collect())
+}
+
+/// 设备名 -> 设备名之后的各计数字段
+fn parse_diskstats(content: &str) -> HashMap<String, Vec<u64>> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace().skip(2);
+            let name = parts.next()?.to_string();
+            let fields = parts.filter_map(|f| f.parse().ok()).collect();
+            Some((name, fields))
+        })
+        .collect()
+}
+
+/// /proc/mounts 中来自 `/dev/` 的一条挂载
+pub struct Mount {
+    /// 去掉 `/dev/` 前缀的设备名，如 "nvme0n1p2"、"mapper/luks-home"
+    pub source: String,
+    pub target: String,
+    pub fs_type: String,
+    pub options: String,
+}
+
+impl Mount {
+    /// btrfs 挂载的子卷路径（`subvol=/@home`），顶层子卷与其他文件系统为 None
+    pub fn subvolume(&self) -> Option<&str> {
+        self.options
+            .split(',')
+            .find_map(|o| o.strip_prefix("subvol="))
+            .filter(|s| *s != "/")
+    }
+}
+
+/// 挂载点中的空格被转义成 `\040`
+pub fn parse_mounts(content: &str) -> Vec<Mount> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some(Mount {
+                source: parts.next()?.strip_prefix("/dev/")?.to_string(),
+                target: parts.next()?.replace("\\040", " "),
+                fs_type: parts.next()?.to_string(),
+                options: parts.next()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// 属于该设备的 /dev 名字：自身、分区（sysfs 中以设备名开头的子目录），
+/// 以及 device-mapper 设备在 /dev/mapper 下的别名
+fn members(dir: &Path, device: &str) -> Vec<String> {
+    let mut names = vec![device.to_string()];
+    if let Ok(entries) = fs::read_dir(dir) {
+        names.extend(
+            entries
+                .filter_map(|e| e.ok())
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .filter(|name| name.starts_with(device) && name != device),
+        );
+    }
+    if let Some(name) = read_attr(&dir.join("dm/name")) {
+        names.push(format!("mapper/{}", name));
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linux::fixture_root;
+
+    #[test]
+    fn intel_laptop() {
+        let root = fixture_root("intel-laptop");
+        let disks = read_disk_counters(
+            &root.join("diskstats"),
+            &root.join("block"),
+            &root.join("mounts"),
+        )
+        .unwrap();
+
+        // loop0 被忽略，分区并入整盘
+        let names: Vec<&str> = disks.iter().map(|d| d.device.as_str()).collect();
+        assert_eq!(names, ["dm-0", "nvme0n1"]);
+
+        // /dev/mapper/luks-home 归到 dm-0，挂载点中的 \040 还原为空格
+        let dm = &disks[0];
+        assert_eq!(dm.mount_points, ["/home", "/var/lib/My Data"]);
+        assert_eq!(dm.reads, 137104);
+        assert_eq!(dm.read_bytes, 8525618 * 512);
+        assert_eq!(dm.writes, 517968);
+        assert_eq!(dm.write_bytes, 21708102 * 512);
+        assert_eq!(dm.busy_ms, Some(302110));
+        assert_eq!(dm.queue_ms, Some(852661));
+
+        // 分区 p1、p2 的挂载点；p3 是 LUKS 容器，自身没有挂载
+        let nvme = &disks[1];
+        assert_eq!(
+            nvme.mount_points,
+            ["/", "/.snapshots", "/boot/efi", "/mnt/btrfs-top"]
+        );
+        assert_eq!(nvme.reads, 412930);
+        assert_eq!(nvme.read_bytes, 28734210 * 512);
+        assert_eq!(nvme.write_bytes, 61820392 * 512);
+        assert_eq!(nvme.busy_ms, Some(1022816));
+    }
+
+    #[test]
+    fn lvm_volume() {
+        let tmp = tempfile::tempdir().unwrap();
+        let block = tmp.path().join("block");
+        fs::create_dir_all(block.join("sda/sda1")).unwrap();
+        fs::create_dir_all(block.join("sda/sda2")).unwrap();
+        fs::create_dir_all(block.join("dm-1/dm")).unwrap();
+        fs::write(block.join("dm-1/dm/name"), "vg0-root\n").unwrap();
+        fs::create_dir_all(block.join("dm-2/dm")).unwrap();
+        fs::write(block.join("dm-2/dm/name"), "vg0-swap\n").unwrap();
+
+        let diskstats = tmp.path().join("diskstats");
+        fs::write(
+            &diskstats,
+            "8 0 sda 100 0 800 10 50 0 400 5 0 20 15\n\
+             8 1 sda1 10 0 80 1 5 0 40 1 0 2 2\n\
+             8 2 sda2 90 0 720 9 45 0 360 4 0 18 13\n\
+             253 1 dm-1 80 0 640 8 40 0 320 4 0 16 12\n",
+        )
+        .unwrap();
+        // dm-2 没有 diskstats 记录时跳过；dm-1 既以别名也以 dm-N 挂载
+        let mounts = tmp.path().join("mounts");
+        fs::write(
+            &mounts,
+            "/dev/mapper/vg0-root / xfs rw,relatime 0 0\n\
+             /dev/dm-1 /mnt/raw xfs rw,relatime 0 0\n\
+             /dev/sda1 /boot ext4 rw,relatime 0 0\n",
+        )
+        .unwrap();
+
+        let disks = read_disk_counters(&diskstats, &block, &mounts).unwrap();
+        let summary: Vec<(&str, Vec<&str>)> = disks
+            .iter()
+            .map(|d| {
+                let mounts = d.mount_points.iter().map(String::as_str).collect();
+                (d.device.as_str(), mounts)
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [("dm-1", vec!["/", "/mnt/raw"]), ("sda", vec!["/boot"])]
+        );
+        assert_eq!(disks[0].read_bytes, 640 * 512);
+        // 只有 11 个字段的旧内核格式
+        assert_eq!(disks[1].queue_ms, Some(15));
+    }
+
+    #[test]
+    fn members_of_a_device() {
+        let block = fixture_root("intel-laptop").join("block");
+        let mut nvme = members(&block.join("nvme0n1"), "nvme0n1");
+        nvme.sort();
+        assert_eq!(nvme, ["nvme0n1", "nvme0n1p1", "nvme0n1p2", "nvme0n1p3"]);
+        assert_eq!(
+            members(&block.join("dm-0"), "dm-0"),
+            ["dm-0", "mapper/luks-home"]
+        );
+        assert_eq!(members(&block.join("sdz"), "sdz"), ["sdz"]);
+    }
+
+    #[test]
+    fn mounts_and_subvolumes() {
+        let content = fs::read_to_string(fixture_root("intel-laptop").join("mounts")).unwrap();
+        let mounts = parse_mounts(&content);
+
+        // sysfs、proc、tmpfs 不是 /dev 设备
+        let sources: Vec<&str> = mounts.iter().map(|m| m.source.as_str()).collect();
+        assert_eq!(
+            sources,
+            [
+                "nvme0n1p2",
+                "nvme0n1p2",
+                "nvme0n1p2",
+                "nvme0n1p1",
+                "mapper/luks-home",
+                "mapper/luks-home",
+                "loop0",
+            ]
+        );
+        assert_eq!(mounts[0].target, "/");
+        assert_eq!(mounts[0].fs_type, "btrfs");
+        assert_eq!(mounts[5].target, "/var/lib/My Data");
+        assert_eq!(mounts[6].options, "ro,nodev,relatime");
+
+        let subvolumes: Vec<Option<&str>> = mounts.iter().map(Mount::subvolume).collect();
+        assert_eq!(
+            subvolumes,
+            [
+                Some("/root"),
+                Some("/snapshots"),
+                None,
+                None,
+                None,
+                None,
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_files() {
+        let root = fixture_root("intel-laptop");
+        let missing = root.join("does-not-exist");
+        assert!(read_disk_counters(&missing, &root.join("block"), &root.join("mounts")).is_err());
+        assert!(
+            read_disk_counters(&root.join("diskstats"), &missing, &root.join("mounts")).is_err()
+        );
+
+        // 读不到 mounts 时仍返回计数，只是没有挂载点
+        let disks =
+            read_disk_counters(&root.join("diskstats"), &root.join("block"), &missing).unwrap();
+        assert_eq!(disks.len(), 2);
+        assert!(disks.iter().all(|d| d.mount_points.is_empty()));
+    }
+}
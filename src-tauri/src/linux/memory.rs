@@ -0,0 +1,199 @@
+//! 内存细分：/proc/meminfo、/proc/vmstat 与 /proc/pressure/memory
+//!
+//! meminfo 的单位是 kB；vmstat 中 pgpgin/pgpgout 以 KB 计，pswpin/pswpout 以页计。
+//! PSI 需要内核开启 CONFIG_PSI，没有该文件时不报错。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+
+use crate::memory::PagingCounters;
+use crate::{MemoryBreakdown, Psi, PsiLine};
+
+pub const MEMINFO_PATH: &str = "/proc/meminfo";
+pub const VMSTAT_PATH: &str = "/proc/vmstat";
+pub const PSI_MEMORY_PATH: &str = "/proc/pressure/memory";
+
+/// 读取内存细分与累计分页计数；vmstat 读不到时计数为 None
+pub fn read_memory_breakdown(
+    meminfo: &Path,
+    vmstat: &Path,
+    psi: &Path,
+    page_size: u64,
+) -> anyhow::Result<(MemoryBreakdown, Option<PagingCounters>)> {
+    let content = fs::read_to_string(meminfo)
+        .with_context(|| format!("failed to read {}", meminfo.display()))?;
+    let mut breakdown = parse_meminfo(&content);
+    breakdown.psi = fs::read_to_string(psi).ok().and_then(|s| parse_psi(&s));
+    let counters = fs::read_to_string(vmstat)
+        .ok()
+        .and_then(|s| parse_vmstat(&s, page_size));
+    Ok((breakdown, counters))
+}
+
+/// `MemTotal:       16131452 kB` 形式的行，值换算为字节
+pub fn parse_meminfo(content: &str) -> MemoryBreakdown {
+    let kv: HashMap<&str, u64> = content
+        .lines()
+        .filter_map(|line| {
+            let (key, rest) = line.split_once(':')?;
+            let kb: u64 = rest.split_whitespace().next()?.parse().ok()?;
+            Some((key.trim(), kb * 1024))
+        })
+        .collect();
+    let get = |key: &str| kv.get(key).copied();
+
+    let total = get("MemTotal").unwrap_or(0);
+    let free = get("MemFree").unwrap_or(0);
+    let available = get("MemAvailable");
+    let cached = get("Cached");
+    let buffers = get("Buffers");
+    // 3.14 之前的内核没有 MemAvailable，按 free + buffers + cached 估算已用
+    let reclaimable = available.unwrap_or(free + buffers.unwrap_or(0) + cached.unwrap_or(0));
+    let swap_total = get("SwapTotal").unwrap_or(0);
+
+    MemoryBreakdown {
+        total_bytes: total,
+        used_bytes: total.saturating_sub(reclaimable),
+        free_bytes: free,
+        available_bytes: available,
+        swap_total_bytes: swap_total,
+        swap_used_bytes: swap_total.saturating_sub(get("SwapFree").unwrap_or(swap_total)),
+        cached_bytes: cached,
+        buffers_bytes: buffers,
+        shared_bytes: get("Shmem"),
+        slab_bytes: get("Slab"),
+        dirty_bytes: get("Dirty"),
+        free_percent: available
+            .filter(|_| total > 0)
+            .map(|a| (a as f64 / total as f64 * 100.0) as f32),
+        ..Default::default()
+    }
+}
+
+/// vmstat 中的累计分页量，换算为字节
+pub fn parse_vmstat(content: &str, page_size: u64) -> Option<PagingCounters> {
+    let kv: HashMap<&str, u64> = content
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(' ')?;
+            Some((key, value.trim().parse().ok()?))
+        })
+        .collect();
+    Some(PagingCounters {
+        page_in: kv.get("pgpgin")? * 1024,
+        page_out: kv.get("pgpgout")? * 1024,
+        swap_in: kv.get("pswpin")? * page_size,
+        swap_out: kv.get("pswpout")? * page_size,
+    })
+}
+
+/// `some avg10=0.00 avg60=0.00 avg300=0.00 total=0`，另有一行 `full ...`（较老内核的 cpu 文件没有）
+pub fn parse_psi(content: &str) -> Option<Psi> {
+    let line = |kind: &str| {
+        content.lines().find_map(|l| {
+            let rest = l.strip_prefix(kind)?.strip_prefix(' ')?;
+            let kv: HashMap<&str, &str> = rest
+                .split_whitespace()
+                .filter_map(|f| f.split_once('='))
+                .collect();
+            Some(PsiLine {
+                avg10: kv.get("avg10")?.parse().ok()?,
+                avg60: kv.get("avg60")?.parse().ok()?,
+                avg300: kv.get("avg300")?.parse().ok()?,
+                total_us: kv.get("total")?.parse().ok()?,
+            })
+        })
+    };
+    Some(Psi {
+        some: line("some")?,
+        full: line("full"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linux::fixture_root;
+
+    const KB: u64 = 1024;
+
+    #[test]
+    fn intel_laptop() {
+        let root = fixture_root("intel-laptop");
+        let (breakdown, counters) = read_memory_breakdown(
+            &root.join("meminfo"),
+            &root.join("vmstat"),
+            &root.join("pressure/memory"),
+            4096,
+        )
+        .unwrap();
+
+        assert_eq!(breakdown.total_bytes, 15989212 * KB);
+        assert_eq!(breakdown.free_bytes, 1803224 * KB);
+        assert_eq!(breakdown.available_bytes, Some(8712540 * KB));
+        assert_eq!(breakdown.used_bytes, (15989212 - 8712540) * KB);
+        assert_eq!(breakdown.swap_total_bytes, 8388604 * KB);
+        assert_eq!(breakdown.swap_used_bytes, (8388604 - 7601148) * KB);
+        assert_eq!(breakdown.cached_bytes, Some(6604132 * KB));
+        assert_eq!(breakdown.buffers_bytes, Some(412876 * KB));
+        assert_eq!(breakdown.shared_bytes, Some(901232 * KB));
+        assert_eq!(breakdown.slab_bytes, Some(689412 * KB));
+        assert_eq!(breakdown.dirty_bytes, Some(1844 * KB));
+        assert!((breakdown.free_percent.unwrap() - 54.49).abs() < 0.01);
+
+        let psi = breakdown.psi.unwrap();
+        assert_eq!(psi.some.avg60, 0.12);
+        assert_eq!(psi.some.avg300, 0.35);
+        assert_eq!(psi.some.total_us, 4821634);
+        let full = psi.full.unwrap();
+        assert_eq!(full.avg60, 0.05);
+        assert_eq!(full.total_us, 2217709);
+
+        // pgpgin/pgpgout 以 KB 计，pswpin/pswpout 以页计
+        let counters = counters.unwrap();
+        assert_eq!(counters.page_in, 18734412 * KB);
+        assert_eq!(counters.page_out, 42119876 * KB);
+        assert_eq!(counters.swap_in, 61023 * 4096);
+        assert_eq!(counters.swap_out, 258114 * 4096);
+    }
+
+    #[test]
+    fn optional_files_missing() {
+        let root = fixture_root("intel-laptop");
+        let missing = root.join("does-not-exist");
+        let (breakdown, counters) =
+            read_memory_breakdown(&root.join("meminfo"), &missing, &missing, 4096).unwrap();
+        assert_eq!(breakdown.total_bytes, 15989212 * KB);
+        assert!(breakdown.psi.is_none());
+        assert!(counters.is_none());
+
+        assert!(read_memory_breakdown(&missing, &missing, &missing, 4096).is_err());
+    }
+
+    #[test]
+    fn meminfo_without_available() {
+        // 3.14 之前的内核：已用 = 总量 - (free + buffers + cached)
+        let breakdown = parse_meminfo(
+            "MemTotal: 1000 kB\nMemFree: 100 kB\nBuffers: 50 kB\nCached: 250 kB\nSwapTotal: 0 kB\n",
+        );
+        assert_eq!(breakdown.used_bytes, 600 * KB);
+        assert_eq!(breakdown.available_bytes, None);
+        assert_eq!(breakdown.free_percent, None);
+        assert_eq!(breakdown.swap_used_bytes, 0);
+        assert_eq!(breakdown.shared_bytes, None);
+    }
+
+    #[test]
+    fn partial_vmstat_and_psi() {
+        assert!(parse_vmstat("pgpgin 1\npgpgout 2\npswpin 3\n", 4096).is_none());
+
+        let psi = parse_psi("some avg10=1.50 avg60=0.75 avg300=0.20 total=1234\n").unwrap();
+        assert_eq!(psi.some.avg10, 1.5);
+        assert!(psi.full.is_none());
+        assert!(parse_psi("full avg10=0.00 avg60=0.00 avg300=0.00 total=0\n").is_none());
+        assert!(parse_psi("some avg10=x avg60=0.00 avg300=0.00 total=0\n").is_none());
+    }
+}
@@ -0,0 +1,377 @@
+//! Linux 平台采集实现：数据主要来自 sysfs / procfs。
+//!
+//! 所有读取函数都接收根目录参数，便于对着伪造的 sysfs 目录树验证。
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use nix::unistd::{sysconf, SysconfVar};
+use sysinfo::{Networks, System};
+
+use crate::command::SystemRunner;
+use crate::disk::DiskCounters;
+use crate::features;
+use crate::memory::PagingRates;
+use crate::network::NetCounters;
+use crate::platform::Platform;
+use crate::process::sockets::SocketInfo;
+use crate::{
+    BatteryInfo, BoardInfo, CacheInfo, CpuDetails, CpuFeatures, DeviceInfo, DiskHealth,
+    HardwareData, InterfaceInfo, MemoryBreakdown, MemoryLayout, PhysicalDisk, Power, Sensors,
+    Temps, WifiInfo,
+};
+
+pub mod battery;
+pub mod board;
+pub mod cache;
+pub mod cpu;
+pub mod device;
+pub mod disk;
+pub mod memory;
+pub mod network;
+pub mod power;
+pub mod sensors;
+pub mod smart;
+pub mod sockets;
+pub mod storage;
+pub mod thermal;
+
+/// hwmon、thermal 与 net 所在的 sysfs class 根目录，温度、传感器与网卡共用
+pub const SYS_CLASS_ROOT: &str = "/sys/class";
+
+pub struct LinuxPlatform {
+    /// RAPL 功耗需要前后两次读数，跨采样保存上一次的计数器
+    rapl: Mutex<power::RaplReader>,
+    /// 分页速率同理，保存上一次的 vmstat 计数
+    paging: Mutex<PagingRates>,
+}
+
+impl LinuxPlatform {
+    pub fn new() -> Self {
+        Self {
+            rapl: Mutex::new(power::RaplReader::new(power::POWERCAP_ROOT)),
+            paging: Mutex::new(PagingRates::default()),
+        }
+    }
+}
+
+impl Default for LinuxPlatform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Platform for LinuxPlatform {
+    fn name(&self) -> &'static str {
+        "linux"
+    }
+
+    fn device_info(&self) -> anyhow::Result<DeviceInfo> {
+        Ok(device::read_device_info(
+            Path::new(device::DMI_ROOT),
+            Path::new(device::MACHINE_ID_PATH),
+            Path::new(device::PROC_STAT_PATH),
+            System::uptime(),
+        ))
+    }
+
+    fn hardware_data(&self) -> anyhow::Result<HardwareData> {
+        let dmi = |name: &str| read_attr(&Path::new(device::DMI_ROOT).join(name));
+        let mut sys = System::new();
+        sys.refresh_cpu_list(sysinfo::CpuRefreshKind::nothing());
+        sys.refresh_memory();
+
+        // 内核会保留一部分内存，按 GiB 四舍五入才能与标称容量一致（15.6 GiB 显示为 16 GB）
+        let memory_gb = (sys.total_memory() as f64 / (1u64 << 30) as f64).round() as u64;
+
+        // DMI 没有与 Apple 的型号标识符（MacBookPro17,1）和零售型号编号对应的字段，留空
+        Ok(HardwareData {
+            model_name: dmi("product_name"),
+            model_identifier: None,
+            model_number: None,
+            chip: sys.cpus().first().map(|c| c.brand().trim().to_string()),
+            total_number_of_cores: System::physical_core_count().map(|n| n.to_string()),
+            memory: Some(format!("{memory_gb} GB")),
+            system_firmware_version: dmi("bios_version"),
+            os_loader_version: None,
+            serial_number_system: None,
+            hardware_uuid: None,
+            provisioning_udid: None,
+            activation_lock_status: None,
+        })
+    }
+
+    fn board_info(&self) -> anyhow::Result<BoardInfo> {
+        Ok(board::read_board_info(
+            Path::new(board::DMI_TABLE_PATH),
+            Path::new(device::DMI_ROOT),
+        ))
+    }
+
+    fn memory_modules(&self) -> anyhow::Result<MemoryLayout> {
+        board::read_memory_modules(Path::new(board::DMI_TABLE_PATH))
+    }
+
+    fn memory_breakdown(&self) -> anyhow::Result<MemoryBreakdown> {
+        let page_size = sysconf(SysconfVar::PAGE_SIZE)
+            .ok()
+            .flatten()
+            .map_or(4096, |n| n as u64);
+        let (mut breakdown, counters) = memory::read_memory_breakdown(
+            Path::new(memory::MEMINFO_PATH),
+            Path::new(memory::VMSTAT_PATH),
+            Path::new(memory::PSI_MEMORY_PATH),
+            page_size,
+        )?;
+        if let Some(counters) = counters {
+            self.paging.lock().unwrap().apply(&mut breakdown, counters);
+        }
+        Ok(breakdown)
+    }
+
+    fn cpu_details(&self) -> anyhow::Result<CpuDetails> {
+        let cpuinfo = fs::read_to_string(cpu::CPUINFO_PATH)
+            .with_context(|| format!("failed to read {}", cpu::CPUINFO_PATH))?;
+        Ok(cpu::read_cpu_details(
+            Path::new(cpu::SYS_DEVICES_ROOT),
+            &cpuinfo,
+        ))
+    }
+
+    fn caches(&self) -> anyhow::Result<Vec<CacheInfo>> {
+        let caches = cache::read_caches(Path::new(cpu::SYS_DEVICES_ROOT));
+        if caches.is_empty() {
+            // 部分容器或虚拟机没有暴露 cache 目录
+            return crate::cache::read_cpuid();
+        }
+        Ok(caches)
+    }
+
+    fn cpu_features(&self) -> anyhow::Result<CpuFeatures> {
+        let cpuinfo = fs::read_to_string(cpu::CPUINFO_PATH)
+            .with_context(|| format!("failed to read {}", cpu::CPUINFO_PATH))?;
+        let flags = features::parse_cpuinfo_flags(&cpuinfo);
+        let cpuid = features::read_cpuid();
+        Ok(features::detect(
+            std::env::consts::ARCH,
+            &|f| flags.as_ref().map(|flags| flags.contains(f.linux)),
+            cpuid.as_ref(),
+        ))
+    }
+
+    fn temps(&self) -> Option<Temps> {
+        thermal::read_temps(Path::new(SYS_CLASS_ROOT))
+    }
+
+    fn power(&self) -> Option<Power> {
+        self.rapl.lock().unwrap().read()
+    }
+
+    fn disk_counters(&self) -> anyhow::Result<Vec<DiskCounters>> {
+        disk::read_disk_counters(
+            Path::new(disk::DISKSTATS_PATH),
+            Path::new(disk::SYS_BLOCK_ROOT),
+            Path::new(disk::MOUNTS_PATH),
+        )
+    }
+
+    fn net_counters(&self) -> anyhow::Result<Vec<NetCounters>> {
+        network::read_net_counters(Path::new(network::NET_DEV_PATH))
+    }
+
+    fn storage_devices(&self) -> anyhow::Result<Vec<PhysicalDisk>> {
+        storage::read_storage_devices(
+            Path::new(disk::SYS_BLOCK_ROOT),
+            Path::new(storage::UDEV_DATA_ROOT),
+            Path::new(disk::MOUNTS_PATH),
+        )
+    }
+
+    fn disk_health(&self) -> anyhow::Result<Vec<DiskHealth>> {
+        smart::read_disk_health(
+            Path::new(disk::SYS_BLOCK_ROOT),
+            Path::new(smart::DEV_ROOT),
+            &SystemRunner,
+        )
+    }
+
+    fn sockets(&self) -> anyhow::Result<Vec<SocketInfo>> {
+        sockets::read_sockets(Path::new(sockets::PROC_ROOT))
+    }
+
+    fn battery(&self) -> anyhow::Result<BatteryInfo> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        battery::read_battery_info(Path::new(battery::POWER_SUPPLY_ROOT), now)
+    }
+
+    fn sensors(&self) -> anyhow::Result<Sensors> {
+        Ok(sensors::read_sensors(Path::new(SYS_CLASS_ROOT)))
+    }
+
+    fn interfaces(&self) -> anyhow::Result<Vec<InterfaceInfo>> {
+        // IPv4 地址在 sysfs / procfs 里没有，仍取自 sysinfo（getifaddrs）
+        let networks = Networks::new_with_refreshed_list();
+        let ipv4: HashMap<String, Vec<(Ipv4Addr, u8)>> = networks
+            .iter()
+            .map(|(name, data)| {
+                let addrs = data
+                    .ip_networks()
+                    .iter()
+                    .filter_map(|n| match n.addr {
+                        IpAddr::V4(v4) => Some((v4, n.prefix)),
+                        IpAddr::V6(_) => None,
+                    })
+                    .collect();
+                (name.clone(), addrs)
+            })
+            .collect();
+        network::read_interfaces(
+            Path::new(SYS_CLASS_ROOT),
+            Path::new(network::IF_INET6_PATH),
+            &ipv4,
+        )
+    }
+
+    fn wifi(&self) -> anyhow::Result<Option<WifiInfo>> {
+        Ok(network::read_wifi(Path::new(network::WIRELESS_PATH)))
+    }
+
+    fn default_gateway(&self) -> anyhow::Result<Option<String>> {
+        Ok(network::read_default_gateway(
+            Path::new(network::ROUTE_PATH),
+            Path::new(network::IPV6_ROUTE_PATH),
+        ))
+    }
+
+    fn dns_servers(&self) -> Option<Vec<String>> {
+        network::read_dns_servers(
+            Path::new(network::RESOLV_CONF_PATH),
+            Path::new(network::RESOLVED_CONF_PATH),
+        )
+    }
+}
+
+/// 读取 sysfs 属性文件并去掉首尾空白，空文件视为不存在
+pub fn read_attr(path: &Path) -> Option<String> {
+    let s = fs::read_to_string(path).ok()?;
+    let s = s.trim();
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+/// 读取数值型 sysfs 属性
+pub fn read_attr_u64(path: &Path) -> Option<u64> {
+    read_attr(path)?.parse().ok()
+}
+
+/// 读取有符号数值型 sysfs 属性（如温度、电流可能为负）
+pub fn read_attr_i64(path: &Path) -> Option<i64> {
+    read_attr(path)?.parse().ok()
+}
+
+/// 列出 `dir` 下以 `prefix` 加数字命名的条目，按数字排序（hwmon10 排在 hwmon9 之后）
+pub fn numbered_entries(dir: &Path, prefix: &str) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut found: Vec<(u32, PathBuf)> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            let n = name.strip_prefix(prefix)?.parse().ok()?;
+            Some((n, e.path()))
+        })
+        .collect();
+    found.sort_by_key(|(n, _)| *n);
+    found.into_iter().map(|(_, p)| p).collect()
+}
+
+/// hwmon 目录下某类属性（如 `temp`+`_input`、`power`+`_average`）所有 `<kind>K<suffix>` 的序号 K（升序）
+pub fn hwmon_indices(dir: &Path, kind: &str, suffix: &str) -> Vec<u32> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut indices: Vec<u32> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            name.strip_prefix(kind)?.strip_suffix(suffix)?.parse().ok()
+        })
+        .collect();
+    indices.sort();
+    indices
+}
+
+/// 同名传感器（例如两块 nvme）追加序号区分，返回的名字会记入 `used`
+pub fn unique_name(used: &mut HashSet<String>, name: String) -> String {
+    let name = if used.contains(&name) {
+        (2..)
+            .map(|n| format!("{} #{}", name, n))
+            .find(|candidate| !used.contains(candidate))
+            .unwrap()
+    } else {
+        name
+    };
+    used.insert(name.clone());
+    name
+}
+
+/// 仓库里录制的 sysfs / procfs 目录树（fixtures/sysfs/<machine>）
+#[cfg(test)]
+pub fn fixture_root(machine: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures/sysfs")
+        .join(machine)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn numbered_entries_sort_numerically() {
+        let dir = fixture_root("ryzen-desktop").join("hwmon");
+        let names: Vec<String> = numbered_entries(&dir, "hwmon")
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().into_owned())
+            .collect();
+        assert_eq!(
+            names,
+            ["hwmon0", "hwmon1", "hwmon2", "hwmon3", "hwmon4", "hwmon10"]
+        );
+        assert!(numbered_entries(&dir.join("missing"), "hwmon").is_empty());
+    }
+
+    #[test]
+    fn hwmon_indices_skip_other_attributes() {
+        let dir = fixture_root("ryzen-desktop").join("hwmon/hwmon4");
+        assert_eq!(hwmon_indices(&dir, "fan", "_input"), [1, 2, 7]);
+        assert_eq!(hwmon_indices(&dir, "in", "_input"), [0, 1, 2]);
+        assert!(hwmon_indices(&dir, "power", "_average").is_empty());
+    }
+
+    #[test]
+    fn unique_names() {
+        let mut used = HashSet::new();
+        assert_eq!(
+            unique_name(&mut used, "nvme Composite".into()),
+            "nvme Composite"
+        );
+        assert_eq!(
+            unique_name(&mut used, "nvme Composite".into()),
+            "nvme Composite #2"
+        );
+        assert_eq!(
+            unique_name(&mut used, "nvme Composite".into()),
+            "nvme Composite #3"
+        );
+        assert_eq!(unique_name(&mut used, "acpitz".into()), "acpitz");
+    }
+}
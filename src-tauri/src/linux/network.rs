@@ -0,0 +1,654 @@
+//! Linux 网络信息：/sys/class/net、/proc/net/dev、/proc/net/route、/proc/net/ipv6_route、
+//! /proc/net/wireless 与 resolv.conf
+//!
+//! - 接口：`operstate`、`mtu`、`address`，`flags` 为十六进制的 IFF_* 位；
+//!   IPv6 地址来自 /proc/net/if_inet6，IPv4 地址不在 sysfs / procfs 中，由调用方传入
+//! - 流量：/proc/net/dev 每个接口一行，冒号后依次为 8 个接收计数与 8 个发送计数
+//! - 默认网关：IPv4 路由表中目的与掩码都为 0 的项，字段是小端序的十六进制；
+//!   没有时再看 IPv6 路由表中前缀长度为 0 的项
+//! - DNS：systemd-resolved 接管时 /etc/resolv.conf 只有本地桩 127.0.0.53，
+//!   真正的上游服务器在 /run/systemd/resolve/resolv.conf
+
+use std::collections::HashMap;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+use anyhow::Context;
+
+use super::{read_attr, read_attr_u64};
+use crate::network::{self, NetCounters};
+use crate::{InterfaceInfo, IpAddress, WifiInfo};
+
+pub const IF_INET6_PATH: &str = "/proc/net/if_inet6";
+pub const NET_DEV_PATH: &str = "/proc/net/dev";
+pub const ROUTE_PATH: &str = "/proc/net/route";
+pub const IPV6_ROUTE_PATH: &str = "/proc/net/ipv6_route";
+pub const WIRELESS_PATH: &str = "/proc/net/wireless";
+pub const RESOLV_CONF_PATH: &str = "/etc/resolv.conf";
+pub const RESOLVED_CONF_PATH: &str = "/run/systemd/resolve/resolv.conf";
+
+const IFF_UP: u64 = 0x1;
+const IFF_BROADCAST: u64 = 0x2;
+const IFF_LOOPBACK: u64 = 0x8;
+const IFA_F_TEMPORARY: u32 = 0x01;
+const IFA_F_DEPRECATED: u32 = 0x20;
+const ARPHRD_LOOPBACK: u64 = 772;
+/// ARPHRD_NONE（tun、WireGuard）、IPIP、SIT、IP6IP6、GRE、IP6GRE
+const TUNNEL_ARPHRD: &[u64] = &[65534, 768, 776, 769, 778, 823];
+/// RTF_UP | RTF_GATEWAY
+const RTF_UP_GATEWAY: u32 = 0x3;
+
+/// 读取 `class_root`（通常为 /sys/class）下的网络接口。
+/// IPv4 地址不在 sysfs / procfs 中，由调用方按接口名传入（地址, 前缀长度）；
+/// IPv6 地址及其作用域、标志来自 /proc/net/if_inet6
+pub fn read_interfaces(
+    class_root: &Path,
+    if_inet6: &Path,
+    ipv4: &HashMap<String, Vec<(Ipv4Addr, u8)>>,
+) -> anyhow::Result<Vec<InterfaceInfo>> {
+    let net = class_root.join("net");
+    let mut ipv6 = fs::read_to_string(if_inet6)
+        .map(|s| parse_if_inet6(&s))
+        .unwrap_or_default();
+    let mut interfaces: Vec<InterfaceInfo> = fs::read_dir(&net)
+        .with_context(|| format!("failed to read {}", net.display()))?
+        .filter_map(|e| e.ok())
+        .map(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            let dir = e.path();
+            let flags = read_attr(&dir.join("flags"))
+                .and_then(|f| u64::from_str_radix(f.trim_start_matches("0x"), 16).ok())
+                .unwrap_or(0);
+            // 回环与部分虚拟接口不报告载波状态，operstate 为 unknown
+            let operstate = read_attr(&dir.join("operstate"));
+            let is_up =
+                flags & IFF_UP != 0 && matches!(operstate.as_deref(), Some("up") | Some("unknown"));
+            let is_loopback = flags & IFF_LOOPBACK != 0;
+            let mac = read_attr(&dir.join("address"))
+                .filter(|m| !is_loopback && m.chars().any(|c| c != '0' && c != ':'));
+
+            let mut addresses: Vec<IpAddress> = ipv4
+                .get(&name)
+                .into_iter()
+                .flatten()
+                .map(|(addr, prefix_len)| {
+                    let mut a = IpAddress::new(IpAddr::V4(*addr), Some(*prefix_len));
+                    if flags & IFF_BROADCAST != 0 {
+                        a.broadcast = network::ipv4_broadcast(*addr, *prefix_len).map(IpAddr::V4);
+                    }
+                    a
+                })
+                .collect();
+            network::mark_secondary(&mut addresses);
+            addresses.extend(ipv6.remove(&name).unwrap_or_default());
+
+            InterfaceInfo {
+                mac,
+                ips: addresses.iter().map(|a| a.address.to_string()).collect(),
+                addresses,
+                kind: Some(interface_kind(&dir, &name, flags).to_string()),
+                is_up,
+                is_loopback,
+                mtu: read_attr_u64(&dir.join("mtu"))
+                    .and_then(|m| u32::try_from(m).ok())
+                    .filter(|m| *m > 0),
+                name,
+            }
+        })
+        .collect();
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(interfaces)
+}
+
+/// 按 sysfs 判断接口类型：`type` 为 ARPHRD_* 硬件类型，`uevent` 中的 DEVTYPE 区分无线、网桥等，
+/// 有 `device` 链接的是真实硬件，veth 两端的 `iflink` 指向对端而不是自己
+fn interface_kind(dir: &Path, name: &str, flags: u64) -> &'static str {
+    let devtype = read_attr(&dir.join("uevent")).and_then(|u| {
+        u.lines()
+            .find_map(|l| l.strip_prefix("DEVTYPE="))
+            .map(str::to_string)
+    });
+    let arphrd = read_attr_u64(&dir.join("type")).unwrap_or(0);
+    // VLAN 等同样指向下层接口，但带有 DEVTYPE
+    let is_veth = devtype.is_none()
+        && read_attr(&dir.join("ifindex")) != read_attr(&dir.join("iflink"))
+        && !dir.join("device").exists();
+
+    if flags & IFF_LOOPBACK != 0 || arphrd == ARPHRD_LOOPBACK {
+        "loopback"
+    } else if devtype.as_deref() == Some("wlan") || dir.join("wireless").exists() {
+        "wifi"
+    } else if devtype.as_deref() == Some("bridge") || dir.join("bridge").exists() {
+        "bridge"
+    } else if dir.join("tun_flags").exists()
+        || devtype.as_deref() == Some("wireguard")
+        || TUNNEL_ARPHRD.contains(&arphrd)
+    {
+        "tunnel"
+    } else if is_veth || name.starts_with("veth") {
+        "veth"
+    } else if dir.join("device").exists() {
+        "ethernet"
+    } else {
+        "virtual"
+    }
+}
+
+/// /proc/net/if_inet6：`地址 接口序号 前缀长度 作用域 标志 接口名`，除接口名外都是十六进制，
+/// 地址为 32 位不带冒号的十六进制
+pub fn parse_if_inet6(s: &str) -> HashMap<String, Vec<IpAddress>> {
+    let mut out: HashMap<String, Vec<IpAddress>> = HashMap::new();
+    for line in s.lines() {
+        let f: Vec<&str> = line.split_whitespace().collect();
+        if f.len() < 6 {
+            continue;
+        }
+        let (Ok(addr), Ok(prefix_len), Ok(flags)) = (
+            u128::from_str_radix(f[0], 16),
+            u8::from_str_radix(f[2], 16),
+            u32::from_str_radix(f[4], 16),
+        ) else {
+            continue;
+        };
+        let mut a = IpAddress::new(IpAddr::V6(Ipv6Addr::from(addr)), Some(prefix_len));
+        a.temporary = flags & IFA_F_TEMPORARY != 0;
+        a.deprecated = flags & IFA_F_DEPRECATED != 0;
+        out.entry(f[5].to_string()).or_default().push(a);
+    }
+    out
+}
+
+pub fn read_net_counters(net_dev: &Path) -> anyhow::Result<Vec<NetCounters>> {
+    let s = fs::read_to_string(net_dev)
+        .with_context(|| format!("failed to read {}", net_dev.display()))?;
+    Ok(parse_net_dev(&s))
+}
+
+/// 接收：bytes packets errs drop fifo frame compressed multicast；
+/// 发送：bytes packets errs drop fifo colls carrier compressed
+pub fn parse_net_dev(s: &str) -> Vec<NetCounters> {
+    s.lines()
+        .skip(2)
+        .filter_map(|line| {
+            let (name, rest) = line.split_once(':')?;
+            let f: Vec<u64> = rest
+                .split_whitespace()
+                .map(|v| v.parse().ok())
+                .collect::<Option<_>>()?;
+            if f.len() < 16 {
+                return None;
+            }
+            Some(NetCounters {
+                interface: name.trim().to_string(),
+                rx_bytes: f[0],
+                rx_packets: f[1],
+                rx_errors: f[2],
+                rx_dropped: Some(f[3]),
+                multicast: Some(f[7]),
+                tx_bytes: f[8],
+                tx_packets: f[9],
+                tx_errors: f[10],
+                tx_dropped: Some(f[11]),
+            })
+        })
+        .collect()
+}
+
+/// 默认网关，优先 IPv4；IPv6 链路本地网关带上 `%接口名`
+pub fn read_default_gateway(route: &Path, ipv6_route: &Path) -> Option<String> {
+    fs::read_to_string(route)
+        .ok()
+        .and_then(|s| parse_route(&s))
+        .or_else(|| {
+            fs::read_to_string(ipv6_route)
+                .ok()
+                .and_then(|s| parse_ipv6_route(&s))
+        })
+}
+
+/// /proc/net/route：`Iface Destination Gateway Flags RefCnt Use Metric Mask ...`，
+/// 多条默认路由时取 metric 最小的
+pub fn parse_route(s: &str) -> Option<String> {
+    s.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            if f.len() < 8 || f[1] != "00000000" || f[7] != "00000000" {
+                return None;
+            }
+            let flags = u32::from_str_radix(f[3], 16).ok()?;
+            if flags & RTF_UP_GATEWAY != RTF_UP_GATEWAY {
+                return None;
+            }
+            let gateway = u32::from_str_radix(f[2], 16).ok()?;
+            let metric: u32 = f[6].parse().ok()?;
+            Some((metric, Ipv4Addr::from(gateway.swap_bytes())))
+        })
+        .min_by_key(|(metric, _)| *metric)
+        .map(|(_, gateway)| gateway.to_string())
+}
+
+/// /proc/net/ipv6_route：`dest plen src splen next_hop metric refcnt use flags iface`，
+/// 地址为 32 位十六进制（网络字节序）
+pub fn parse_ipv6_route(s: &str) -> Option<String> {
+    s.lines()
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            if f.len() < 10 || f[1] != "00" || u128::from_str_radix(f[0], 16).ok()? != 0 {
+                return None;
+            }
+            let next_hop = Ipv6Addr::from(u128::from_str_radix(f[4], 16).ok()?);
+            if next_hop.is_unspecified() {
+                return None;
+            }
+            let metric = u32::from_str_radix(f[5], 16).ok()?;
+            let gateway = if next_hop.segments()[0] & 0xffc0 == 0xfe80 {
+                format!("{}%{}", next_hop, f[9])
+            } else {
+                next_hop.to_string()
+            };
+            Some((metric, gateway))
+        })
+        .min_by_key(|(metric, _)| *metric)
+        .map(|(_, gateway)| gateway)
+}
+
+/// DNS 服务器；resolv.conf 只指向 systemd-resolved 本地桩时改读其上游配置
+pub fn read_dns_servers(resolv_conf: &Path, resolved_conf: &Path) -> Option<Vec<String>> {
+    let servers = parse_resolv_conf(&fs::read_to_string(resolv_conf).ok()?);
+    if !servers.is_empty() && servers.iter().all(|s| s == "127.0.0.53") {
+        if let Some(upstream) = fs::read_to_string(resolved_conf)
+            .ok()
+            .map(|s| parse_resolv_conf(&s))
+            .filter(|u| !u.is_empty())
+        {
+            return Some(upstream);
+        }
+    }
+    Some(servers)
+}
+
+/// `nameserver <地址>` 行，去重并保持顺序
+pub fn parse_resolv_conf(s: &str) -> Vec<String> {
+    let mut servers: Vec<String> = Vec::new();
+    for line in s.lines() {
+        let mut parts = line.split_whitespace();
+        if parts.next() != Some("nameserver") {
+            continue;
+        }
+        if let Some(server) = parts.next() {
+            if !servers.iter().any(|s| s == server) {
+                servers.push(server.to_string());
+            }
+        }
+    }
+    servers
+}
+
+/// /proc/net/wireless 中第一个无线接口的信号强度；SSID 与 BSSID 需要 nl80211，这里不提供
+pub fn read_wifi(wireless: &Path) -> Option<WifiInfo> {
+    parse_wireless(&fs::read_to_string(wireless).ok()?)
+}
+
+/// 前两行为表头，之后每行 `iface: status link level noise ...`，level 为 dBm（带尾随的 `.`）
+pub fn parse_wireless(s: &str) -> Option<WifiInfo> {
+    s.lines().skip(2).find_map(|line| {
+        let (iface, rest) = line.split_once(':')?;
+        let level = rest.split_whitespace().nth(2)?;
+        Some(WifiInfo {
+            ssid: None,
+            bssid: None,
+            signal_dbm: level.trim_end_matches('.').parse().ok(),
+            frequency_mhz: None,
+            iface: Some(iface.trim().to_string()),
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linux::fixture_root;
+
+    #[test]
+    fn default_gateway() {
+        let root = fixture_root("intel-laptop");
+        let missing = root.join("does-not-exist");
+        assert_eq!(
+            read_default_gateway(&root.join("route"), &root.join("ipv6_route")).as_deref(),
+            Some("192.168.1.1")
+        );
+        // 没有 IPv4 默认路由时用 IPv6，链路本地网关带上接口名
+        assert_eq!(
+            read_default_gateway(&missing, &root.join("ipv6_route")).as_deref(),
+            Some("fe80::1a5b:3dff:fe2c:9a01%wlp0s20f3")
+        );
+        assert_eq!(read_default_gateway(&missing, &missing), None);
+    }
+
+    #[test]
+    fn route_tables() {
+        let header =
+            "Iface\tDestination\tGateway \tFlags\tRefCnt\tUse\tMetric\tMask\t\tMTU\tWindow\tIRTT\n";
+        // 多条默认路由取 metric 最小的；没有 RTF_GATEWAY 的默认路由跳过
+        let route = format!(
+            "{}wlan0\t00000000\t0101A8C0\t0003\t0\t0\t600\t00000000\t0\t0\t0\n\
+             eth0\t00000000\t010010AC\t0003\t0\t0\t100\t00000000\t0\t0\t0\n\
+             wg0\t00000000\t00000000\t0001\t0\t0\t50\t00000000\t0\t0\t0\n",
+            header
+        );
+        assert_eq!(parse_route(&route).as_deref(), Some("172.16.0.1"));
+        assert_eq!(parse_route(header), None);
+
+        let ipv6 = "00000000000000000000000000000000 00 00000000000000000000000000000000 00 \
+                    20010db8000000000000000000000001 00000400 00000001 00000000 00000003 eth0\n\
+                    00000000000000000000000000000000 00 00000000000000000000000000000000 00 \
+                    fe800000000000000000000000000001 00000258 00000001 00000000 00000003 wlan0\n";
+        assert_eq!(parse_ipv6_route(ipv6).as_deref(), Some("fe80::1%wlan0"));
+        assert_eq!(
+            parse_ipv6_route(&ipv6.replace("00000258", "00000800")).as_deref(),
+            Some("2001:db8::1")
+        );
+    }
+
+    #[test]
+    fn dns_servers() {
+        let root = fixture_root("intel-laptop");
+        let missing = root.join("does-not-exist");
+        // resolv.conf 只有 systemd-resolved 的本地桩，改读上游配置
+        assert_eq!(
+            read_dns_servers(&root.join("resolv.conf"), &root.join("resolve/resolv.conf")).unwrap(),
+            [
+                "192.168.1.1",
+                "100.100.100.100",
+                "fe80::1a5b:3dff:fe2c:9a01%wlp0s20f3"
+            ]
+        );
+        assert_eq!(
+            read_dns_servers(&root.join("resolv.conf"), &missing).unwrap(),
+            ["127.0.0.53"]
+        );
+        assert_eq!(read_dns_servers(&missing, &missing), None);
+
+        assert_eq!(
+            parse_resolv_conf("nameserver 1.1.1.1\n# nameserver 9.9.9.9\nnameserver 1.1.1.1\nnameserver\nnameserver 8.8.8.8\n"),
+            ["1.1.1.1", "8.8.8.8"]
+        );
+    }
+
+    #[test]
+    fn wireless() {
+        let root = fixture_root("intel-laptop");
+        let wifi = read_wifi(&root.join("wireless")).unwrap();
+        assert_eq!(wifi.iface.as_deref(), Some("wlp0s20f3"));
+        assert_eq!(wifi.signal_dbm, Some(-52));
+        assert_eq!(wifi.ssid, None);
+        assert_eq!(wifi.bssid, None);
+
+        // 只有表头：没有无线接口
+        let header: String = fs::read_to_string(root.join("wireless"))
+            .unwrap()
+            .lines()
+            .take(2)
+            .map(|l| format!("{}\n", l))
+            .collect();
+        assert!(parse_wireless(&header).is_none());
+        assert!(read_wifi(&root.join("does-not-exist")).is_none());
+    }
+
+    #[test]
+    fn net_dev_counters() {
+        let root = fixture_root("intel-laptop");
+        let counters = read_net_counters(&root.join("net_dev")).unwrap();
+        let names: Vec<&str> = counters.iter().map(|c| c.interface.as_str()).collect();
+        assert_eq!(
+            names,
+            ["lo", "enp0s31f6", "wlp0s20f3", "docker0", "tailscale0"]
+        );
+
+        let wlan = &counters[2];
+        assert_eq!(wlan.rx_bytes, 8734920113);
+        assert_eq!(wlan.rx_packets, 6412875);
+        assert_eq!(wlan.rx_errors, 0);
+        assert_eq!(wlan.rx_dropped, Some(2841));
+        assert_eq!(wlan.multicast, Some(91245));
+        assert_eq!(wlan.tx_bytes, 912837465);
+        assert_eq!(wlan.tx_packets, 2210934);
+        assert_eq!(wlan.tx_dropped, Some(0));
+
+        let docker = &counters[3];
+        assert_eq!(docker.rx_bytes, 0);
+        assert_eq!(docker.tx_bytes, 83211);
+        assert_eq!(docker.tx_dropped, Some(37));
+
+        // 字段不足或无法解析的行跳过
+        let short = "h1\nh2\n  eth0: 1 2 3\n  eth1: 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 x\n";
+        assert!(parse_net_dev(short).is_empty());
+        assert!(read_net_counters(&root.join("does-not-exist")).is_err());
+    }
+
+    /// (地址, 作用域, 前缀长度, temporary, deprecated, secondary)
+    type AddressSummary<'a> = (String, &'a str, Option<u8>, bool, bool, bool);
+
+    fn addresses(iface: &InterfaceInfo) -> Vec<AddressSummary<'_>> {
+        iface
+            .addresses
+            .iter()
+            .map(|a| {
+                (
+                    a.address.to_string(),
+                    a.scope.as_str(),
+                    a.prefix_len,
+                    a.temporary,
+                    a.deprecated,
+                    a.secondary,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn intel_laptop_interfaces() {
+        let root = fixture_root("intel-laptop");
+        let ipv4: HashMap<String, Vec<(Ipv4Addr, u8)>> = [
+            ("lo", vec![(Ipv4Addr::new(127, 0, 0, 1), 8)]),
+            (
+                "wlp0s20f3",
+                vec![
+                    (Ipv4Addr::new(192, 168, 1, 23), 24),
+                    (Ipv4Addr::new(192, 168, 1, 24), 24),
+                ],
+            ),
+            ("docker0", vec![(Ipv4Addr::new(172, 17, 0, 1), 16)]),
+        ]
+        .into_iter()
+        .map(|(name, addrs)| (name.to_string(), addrs))
+        .collect();
+        let interfaces = read_interfaces(&root, &root.join("if_inet6"), &ipv4).unwrap();
+
+        let kinds: Vec<(&str, Option<&str>, bool)> = interfaces
+            .iter()
+            .map(|i| (i.name.as_str(), i.kind.as_deref(), i.is_up))
+            .collect();
+        assert_eq!(
+            kinds,
+            [
+                ("docker0", Some("bridge"), false),
+                ("enp0s31f6", Some("ethernet"), false),
+                ("lo", Some("loopback"), true),
+                ("tailscale0", Some("tunnel"), true),
+                ("veth7c3e1a2", Some("veth"), true),
+                ("wlp0s20f3", Some("wifi"), true),
+            ]
+        );
+
+        let wlan = &interfaces[5];
+        assert_eq!(wlan.mac.as_deref(), Some("f4:7b:09:c1:5d:a8"));
+        assert_eq!(wlan.mtu, Some(1500));
+        assert!(!wlan.is_loopback);
+        assert_eq!(
+            addresses(wlan),
+            [
+                (
+                    "192.168.1.23".to_string(),
+                    "global",
+                    Some(24),
+                    false,
+                    false,
+                    false
+                ),
+                (
+                    "192.168.1.24".to_string(),
+                    "global",
+                    Some(24),
+                    false,
+                    false,
+                    true
+                ),
+                (
+                    "240e:3b7:3272:d8d0:a1c4:e2ff:fe1b:9d03".to_string(),
+                    "global",
+                    Some(64),
+                    false,
+                    false,
+                    false
+                ),
+                (
+                    "240e:3b7:3272:d8d0:5d8e:3b2a:91c4:7f06".to_string(),
+                    "global",
+                    Some(64),
+                    true,
+                    false,
+                    false
+                ),
+                (
+                    "240e:3b7:3272:d8d0:2b9a:77c3:e0f4:1d58".to_string(),
+                    "global",
+                    Some(64),
+                    true,
+                    true,
+                    false
+                ),
+                (
+                    "fe80::f67b:9ff:fec1:5da8".to_string(),
+                    "link-local",
+                    Some(64),
+                    false,
+                    false,
+                    false
+                ),
+            ]
+        );
+        assert_eq!(wlan.ips.len(), 6);
+        assert_eq!(
+            wlan.addresses[0].broadcast,
+            Some(IpAddr::V4(Ipv4Addr::new(192, 168, 1, 255)))
+        );
+
+        let docker = &interfaces[0];
+        assert_eq!(
+            docker.addresses[0].broadcast,
+            Some(IpAddr::V4(Ipv4Addr::new(172, 17, 255, 255)))
+        );
+
+        // 回环没有 MAC 与广播地址
+        let lo = &interfaces[2];
+        assert!(lo.is_loopback);
+        assert_eq!(lo.mac, None);
+        assert_eq!(lo.mtu, Some(65536));
+        assert_eq!(lo.ips, ["127.0.0.1", "::1"]);
+        assert_eq!(lo.addresses[0].broadcast, None);
+        assert_eq!(lo.addresses[1].scope, "loopback");
+
+        // tun 设备的 address 为空
+        let tailscale = &interfaces[3];
+        assert_eq!(tailscale.mac, None);
+        assert_eq!(tailscale.mtu, Some(1280));
+        assert_eq!(
+            addresses(tailscale)[0],
+            (
+                "fd7a:115c:a1e0::a1:b2c3".to_string(),
+                "ula",
+                Some(128),
+                false,
+                false,
+                false
+            )
+        );
+    }
+
+    #[test]
+    fn interface_kinds() {
+        let tmp = tempfile::tempdir().unwrap();
+        let iface = |name: &str, files: &[(&str, &str)]| {
+            let dir = tmp.path().join(name);
+            fs::create_dir_all(&dir).unwrap();
+            for (file, content) in files {
+                let path = dir.join(file);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(path, content).unwrap();
+            }
+            dir
+        };
+
+        let wg = iface(
+            "wg0",
+            &[("type", "65534"), ("uevent", "DEVTYPE=wireguard\n")],
+        );
+        assert_eq!(interface_kind(&wg, "wg0", 0x91), "tunnel");
+        let sit = iface(
+            "sit0",
+            &[("type", "776"), ("ifindex", "3"), ("iflink", "0")],
+        );
+        assert_eq!(interface_kind(&sit, "sit0", 0x80), "tunnel");
+        let vlan = iface(
+            "eth0.100",
+            &[
+                ("type", "1"),
+                ("uevent", "DEVTYPE=vlan\n"),
+                ("ifindex", "5"),
+                ("iflink", "2"),
+            ],
+        );
+        assert_eq!(interface_kind(&vlan, "eth0.100", 0x1003), "virtual");
+        // 名字不以 veth 开头，但 iflink 指向别的接口
+        let peer = iface("eth0", &[("type", "1"), ("ifindex", "9"), ("iflink", "10")]);
+        assert_eq!(interface_kind(&peer, "eth0", 0x1003), "veth");
+        let dummy = iface(
+            "dummy0",
+            &[("type", "1"), ("ifindex", "4"), ("iflink", "4")],
+        );
+        assert_eq!(interface_kind(&dummy, "dummy0", 0x82), "virtual");
+        let usb = iface(
+            "enx001122334455",
+            &[
+                ("type", "1"),
+                ("ifindex", "6"),
+                ("iflink", "6"),
+                ("device/uevent", "DRIVER=r8152\n"),
+            ],
+        );
+        assert_eq!(interface_kind(&usb, "enx001122334455", 0x1003), "ethernet");
+    }
+
+    #[test]
+    fn if_inet6_lines() {
+        let parsed = parse_if_inet6(
+            "00000000000000000000000000000001 01 80 10 80       lo\n\
+             short line\n\
+             zz000000000000000000000000000001 02 40 20 80 eth0\n",
+        );
+        assert_eq!(parsed.len(), 1);
+        let lo = &parsed["lo"][0];
+        assert_eq!(lo.address, IpAddr::V6(Ipv6Addr::LOCALHOST));
+        assert_eq!(lo.prefix_len, Some(128));
+        assert_eq!(lo.family, "ipv6");
+        assert!(read_interfaces(
+            Path::new("/does-not-exist"),
+            Path::new("/does-not-exist"),
+            &HashMap::new()
+        )
+        .is_err());
+    }
+}
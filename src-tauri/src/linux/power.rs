@@ -0,0 +1,218 @@
+//! 通过 /sys/class/powercap 的 RAPL 能量计数器计算功耗
+//!
+//! 每个域一个 `intel-rapl:N`（package / psys）或 `intel-rapl:N:M`（core / uncore / dram）目录，
+//! `energy_uj` 为单调递增的累计能量（µJ），到 `max_energy_range_uj` 后回绕到 0。
+//! 功率 = 两次读数之差 / 间隔时间，所以第一次读取只记录基准。
+//!
+//! 较新的内核默认只允许 root 读取 `energy_uj`，读不到时按“拿不到数据”处理。
+//! AMD Zen 也通过同名的 `intel-rapl` 接口暴露 package / core 域。
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use super::{read_attr, read_attr_u64};
+use crate::Power;
+
+pub const POWERCAP_ROOT: &str = "/sys/class/powercap";
+
+/// 上一次读数：域目录 -> 累计能量
+struct Snapshot {
+    at: Instant,
+    energy: HashMap<PathBuf, u64>,
+}
+
+pub struct RaplReader {
+    root: PathBuf,
+    last: Option<Snapshot>,
+}
+
+impl RaplReader {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            last: None,
+        }
+    }
+
+    pub fn read(&mut self) -> Option<Power> {
+        self.read_at(Instant::now())
+    }
+
+    /// 以 `now` 作为本次读数的时间计算各域功率
+    pub fn read_at(&mut self, now: Instant) -> Option<Power> {
+        let domains = list_domains(&self.root);
+        let energy: HashMap<PathBuf, u64> = domains
+            .iter()
+            .filter_map(|d| Some((d.clone(), read_attr_u64(&d.join("energy_uj"))?)))
+            .collect();
+        if energy.is_empty() {
+            return None;
+        }
+
+        let previous = self.last.replace(Snapshot {
+            at: now,
+            energy: energy.clone(),
+        })?;
+        let elapsed = now.saturating_duration_since(previous.at).as_secs_f64();
+        if elapsed <= 0.0 {
+            return None;
+        }
+
+        let mut power = Power::default();
+        for dir in &domains {
+            let (Some(cur), Some(prev)) = (energy.get(dir), previous.energy.get(dir)) else {
+                continue;
+            };
+            let range = read_attr_u64(&dir.join("max_energy_range_uj"));
+            let watts = (energy_delta(*prev, *cur, range) as f64 / elapsed / 1_000_000.0) as f32;
+            let name = read_attr(&dir.join("name")).unwrap_or_default();
+
+            // 多路服务器上有多个 package-N，各域按名字累加
+            let add = |slot: &mut Option<f32>| *slot = Some(slot.unwrap_or(0.0) + watts);
+            match name.as_str() {
+                n if n.starts_with("package") => add(&mut power.package),
+                "core" => add(&mut power.cpu),
+                "uncore" => add(&mut power.gpu),
+                "dram" => add(&mut power.dram),
+                "psys" => add(&mut power.system),
+                _ => power.others.push((name, watts)),
+            }
+        }
+        Some(power)
+    }
+}
+
+/// 两次累计能量之差，计数器回绕时按 `max_energy_range_uj` 补齐
+pub fn energy_delta(prev: u64, cur: u64, max_range: Option<u64>) -> u64 {
+    if cur >= prev {
+        return cur - prev;
+    }
+    match max_range {
+        Some(range) if range >= prev => range - prev + cur,
+        // 不知道量程时无法判断回绕了多少，丢弃这一次
+        _ => 0,
+    }
+}
+
+/// `intel-rapl:N` 与 `intel-rapl:N:M` 目录，按名字排序；`intel-rapl-mmio` 与 package 重复，跳过
+fn list_domains(root: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+    let mut domains: Vec<PathBuf> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.file_name()
+                .to_string_lossy()
+                .strip_prefix("intel-rapl:")
+                .is_some_and(|rest| !rest.is_empty())
+        })
+        .map(|e| e.path())
+        .collect();
+    domains.sort();
+    domains
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    use crate::linux::fixture_root;
+
+    /// 把录制的 powercap 目录复制出来，测试里改写计数器模拟时间推移
+    fn copy_powercap(machine: &str) -> tempfile::TempDir {
+        let tmp = tempfile::tempdir().unwrap();
+        let src = fixture_root(machine).join("powercap");
+        for domain in fs::read_dir(&src).unwrap() {
+            let domain = domain.unwrap();
+            let dst = tmp.path().join(domain.file_name());
+            fs::create_dir_all(&dst).unwrap();
+            for attr in fs::read_dir(domain.path()).unwrap() {
+                let attr = attr.unwrap();
+                fs::copy(attr.path(), dst.join(attr.file_name())).unwrap();
+            }
+        }
+        tmp
+    }
+
+    fn bump(root: &Path, domain: &str, uj: u64) {
+        let path = root.join(domain).join("energy_uj");
+        let cur = read_attr_u64(&path).unwrap();
+        fs::write(path, format!("{}\n", cur + uj)).unwrap();
+    }
+
+    #[test]
+    fn energy_delta_wraparound() {
+        assert_eq!(energy_delta(100, 250, Some(1000)), 150);
+        assert_eq!(energy_delta(900, 50, Some(1000)), 150);
+        // 不知道量程时丢弃
+        assert_eq!(energy_delta(900, 50, None), 0);
+        // 量程比上一次读数还小，说明数据不可信
+        assert_eq!(energy_delta(900, 50, Some(800)), 0);
+    }
+
+    #[test]
+    fn intel_domains_and_wraparound() {
+        let tmp = copy_powercap("intel-laptop");
+        let root = tmp.path();
+        let mut reader = RaplReader::new(root);
+        let t0 = Instant::now();
+
+        // 第一次只建立基准
+        assert!(reader.read_at(t0).is_none());
+
+        bump(root, "intel-rapl:0", 20_000_000);
+        bump(root, "intel-rapl:0:0", 8_000_000);
+        bump(root, "intel-rapl:0:1", 2_000_000);
+        bump(root, "intel-rapl:1", 30_000_000);
+        // intel-rapl-mmio 与 intel-rapl:0 是同一个 package，不能重复计入
+        bump(root, "intel-rapl-mmio:0", 20_000_000);
+        // dram 计数器回绕：max_energy_range_uj 为 65712999613，上一次为 65711903211
+        fs::write(root.join("intel-rapl:0:2/energy_uj"), "903598\n").unwrap();
+
+        let power = reader.read_at(t0 + Duration::from_secs(2)).unwrap();
+        assert_eq!(power.package, Some(10.0));
+        assert_eq!(power.cpu, Some(4.0));
+        assert_eq!(power.gpu, Some(1.0));
+        assert_eq!(power.dram, Some(1.0));
+        assert_eq!(power.system, Some(15.0));
+        assert!(power.others.is_empty());
+    }
+
+    #[test]
+    fn ryzen_package_and_core() {
+        let tmp = copy_powercap("ryzen-desktop");
+        let root = tmp.path();
+        let mut reader = RaplReader::new(root);
+        let t0 = Instant::now();
+        assert!(reader.read_at(t0).is_none());
+
+        bump(root, "intel-rapl:0", 45_000_000);
+        bump(root, "intel-rapl:0:0", 30_000_000);
+        let power = reader.read_at(t0 + Duration::from_secs(1)).unwrap();
+        assert_eq!(power.package, Some(45.0));
+        assert_eq!(power.cpu, Some(30.0));
+        assert_eq!(power.gpu, None);
+        assert_eq!(power.system, None);
+
+        // 时间没有前进时不计算
+        assert!(reader.read_at(t0 + Duration::from_secs(1)).is_none());
+    }
+
+    #[test]
+    fn unreadable_counters() {
+        let root = tempfile::tempdir().unwrap();
+        let mut reader = RaplReader::new(root.path());
+        assert!(reader.read().is_none());
+
+        // 非 root 时 energy_uj 不可读，等同于文件不存在
+        let domain = root.path().join("intel-rapl:0");
+        fs::create_dir_all(&domain).unwrap();
+        fs::write(domain.join("name"), "package-0\n").unwrap();
+        assert!(reader.read().is_none());
+        assert!(reader.read().is_none());
+    }
+}
@@ -0,0 +1,174 @@
+//! 通过 /sys/class/hwmon 读取风扇、电压、电流与功率传感器
+//!
+//! hwmon 的单位：`fanK_input` 为 RPM，`pwmK` 为 0..255 的占空比，`inK_input` 为 mV，
+//! `currK_input` 为 mA，`powerK_input` / `powerK_average` 为 µW。
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::{
+    hwmon_indices, numbered_entries, read_attr, read_attr_i64, read_attr_u64, unique_name,
+};
+use crate::{ElectricalSensor, FanSensor, Sensors};
+
+/// 读取 `class_root`（通常为 /sys/class）下所有 hwmon 设备的非温度传感器
+pub fn read_sensors(class_root: &Path) -> Sensors {
+    let mut sensors = Sensors::default();
+    let mut names = HashSet::new();
+
+    for dir in numbered_entries(&class_root.join("hwmon"), "hwmon") {
+        let Some(chip) = read_attr(&dir.join("name")) else {
+            continue;
+        };
+        let label = |kind: &str, index: u32| {
+            let label = read_attr(&dir.join(format!("{}{}_label", kind, index)))
+                .unwrap_or_else(|| format!("{}{}", kind, index));
+            format!("{} {}", chip, label)
+        };
+
+        for index in hwmon_indices(&dir, "fan", "_input") {
+            let attr = |suffix: &str| {
+                read_attr_u64(&dir.join(format!("fan{}_{}", index, suffix))).map(|v| v as f32)
+            };
+            let Some(rpm) = attr("input") else {
+                continue;
+            };
+            sensors.fans.push(FanSensor {
+                name: unique_name(&mut names, label("fan", index)),
+                rpm,
+                min_rpm: attr("min"),
+                max_rpm: attr("max"),
+                // pwmK 与 fanK 不一定一一对应，但绝大多数驱动按相同序号排列
+                pwm_percent: read_attr_u64(&dir.join(format!("pwm{}", index)))
+                    .map(|v| v.min(255) as f32 / 255.0 * 100.0),
+            });
+        }
+
+        // mV -> V，mA -> A
+        for (kind, scale, out) in [
+            ("in", 1000.0, &mut sensors.voltages),
+            ("curr", 1000.0, &mut sensors.currents),
+        ] {
+            for index in hwmon_indices(&dir, kind, "_input") {
+                let attr = |suffix: &str| {
+                    read_attr_i64(&dir.join(format!("{}{}_{}", kind, index, suffix)))
+                        .map(|v| v as f32 / scale)
+                };
+                let Some(value) = attr("input") else {
+                    continue;
+                };
+                out.push(ElectricalSensor {
+                    name: unique_name(&mut names, label(kind, index)),
+                    value,
+                    min: attr("min"),
+                    // 部分 Super I/O 芯片未设置的上限读出来是 0
+                    max: attr("max").filter(|v| *v != 0.0),
+                    critical: attr("crit").filter(|v| *v != 0.0),
+                });
+            }
+        }
+
+        // 功率：amdgpu 等只提供 powerK_average，没有 powerK_input
+        let mut power_indices = hwmon_indices(&dir, "power", "_input");
+        power_indices.extend(hwmon_indices(&dir, "power", "_average"));
+        power_indices.sort();
+        power_indices.dedup();
+        for index in power_indices {
+            // µW -> W
+            let attr = |suffix: &str| {
+                read_attr_u64(&dir.join(format!("power{}_{}", index, suffix)))
+                    .map(|v| v as f32 / 1_000_000.0)
+            };
+            let Some(value) = attr("input").or_else(|| attr("average")) else {
+                continue;
+            };
+            sensors.power.push(ElectricalSensor {
+                name: unique_name(&mut names, label("power", index)),
+                value,
+                min: None,
+                max: attr("cap").or_else(|| attr("max")),
+                critical: attr("crit"),
+            });
+        }
+    }
+
+    sensors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linux::fixture_root;
+
+    fn find<'a>(list: &'a [ElectricalSensor], name: &str) -> &'a ElectricalSensor {
+        list.iter()
+            .find(|s| s.name == name)
+            .unwrap_or_else(|| panic!("no sensor {name}"))
+    }
+
+    #[test]
+    fn intel_laptop() {
+        let sensors = read_sensors(&fixture_root("intel-laptop"));
+
+        assert_eq!(sensors.fans.len(), 1);
+        let fan = &sensors.fans[0];
+        assert_eq!(fan.name, "thinkpad fan1");
+        assert_eq!(fan.rpm, 2437.0);
+        assert_eq!(fan.pwm_percent, Some(100.0));
+
+        // 电池 hwmon 的 mV / mA 换算成 V / A，且不会被当成温度
+        assert_eq!(find(&sensors.voltages, "BAT0 in0").value, 12.5);
+        assert_eq!(find(&sensors.currents, "BAT0 curr1").value, 1.35);
+        assert!(sensors.power.is_empty());
+    }
+
+    #[test]
+    fn ryzen_desktop() {
+        let sensors = read_sensors(&fixture_root("ryzen-desktop"));
+
+        let fans: Vec<(&str, f32)> = sensors
+            .fans
+            .iter()
+            .map(|f| (f.name.as_str(), f.rpm))
+            .collect();
+        assert_eq!(
+            fans,
+            [
+                ("amdgpu fan1", 0.0),
+                ("nct6798 fan1", 0.0),
+                ("nct6798 fan2", 1093.0),
+                ("nct6798 fan7", 842.0)
+            ]
+        );
+        let gpu_fan = &sensors.fans[0];
+        assert_eq!(gpu_fan.max_rpm, Some(3300.0));
+        assert_eq!(gpu_fan.pwm_percent, Some(0.0));
+        let fan2 = &sensors.fans[2];
+        assert_eq!(fan2.min_rpm, Some(200.0));
+        assert_eq!(fan2.pwm_percent, Some(40.0));
+
+        assert_eq!(find(&sensors.voltages, "amdgpu vddgfx").value, 0.806);
+        let vcore = find(&sensors.voltages, "nct6798 in0");
+        assert_eq!(vcore.value, 1.016);
+        assert_eq!(vcore.min, Some(0.0));
+        assert_eq!(vcore.max, Some(1.744));
+        // 未设置的上限读出来是 0，按没有处理
+        assert_eq!(find(&sensors.voltages, "nct6798 in1").max, None);
+
+        // amdgpu 只有 power1_average，上限取 power1_cap
+        assert_eq!(sensors.power.len(), 1);
+        let gpu_power = &sensors.power[0];
+        assert_eq!(gpu_power.name, "amdgpu power1");
+        assert_eq!(gpu_power.value, 18.0);
+        assert_eq!(gpu_power.max, Some(186.0));
+        assert!(sensors.currents.is_empty());
+    }
+
+    #[test]
+    fn no_hwmon() {
+        let root = tempfile::tempdir().unwrap();
+        let sensors = read_sensors(root.path());
+        assert!(sensors.fans.is_empty());
+        assert!(sensors.voltages.is_empty());
+    }
+}
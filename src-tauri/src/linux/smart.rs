@@ -0,0 +1,233 @@
+//! 磁盘健康：NVMe 通过 `NVME_IOCTL_ADMIN_CMD` 读取 SMART / Health 日志页，
+//! SATA 盘通过 `HDIO_DRIVE_CMD` 发送 SMART READ DATA / READ THRESHOLDS。
+//!
+//! 两个 ioctl 都需要 root（CAP_SYS_ADMIN / CAP_SYS_RAWIO）；失败时回退到
+//! `smartctl --json -a`，它同样需要 root，但可能以 setuid 或 sudo 规则的方式授权。
+//! 都拿不到时该磁盘的判定为 "unknown"，并在 `error` 中说明原因。
+
+use std::fs::{self, File};
+use std::io;
+use std::os::fd::AsRawFd;
+use std::path::Path;
+
+use anyhow::Context;
+use nix::libc;
+
+use super::read_attr;
+use crate::command::CommandRunner;
+use crate::smart::{self, ATA_SMART_SIZE, NVME_LOG_SIZE};
+use crate::DiskHealth;
+
+pub const DEV_ROOT: &str = "/dev";
+
+/// _IOWR('N', 0x41, struct nvme_admin_cmd)
+const NVME_IOCTL_ADMIN_CMD: libc::c_ulong = 0xC048_4E41;
+/// Get Log Page
+const NVME_ADMIN_GET_LOG_PAGE: u8 = 0x02;
+/// SMART / Health Information
+const NVME_LOG_SMART: u32 = 0x02;
+
+const HDIO_DRIVE_CMD: libc::c_ulong = 0x031f;
+const ATA_SMART_CMD: u8 = 0xB0;
+const ATA_SMART_READ_VALUES: u8 = 0xD0;
+const ATA_SMART_READ_THRESHOLDS: u8 = 0xD1;
+
+/// linux/nvme_ioctl.h 中的 struct nvme_admin_cmd
+#[repr(C)]
+#[derive(Default)]
+struct NvmeAdminCmd {
+    opcode: u8,
+    flags: u8,
+    rsvd1: u16,
+    nsid: u32,
+    cdw2: u32,
+    cdw3: u32,
+    metadata: u64,
+    addr: u64,
+    metadata_len: u32,
+    data_len: u32,
+    cdw10: u32,
+    cdw11: u32,
+    cdw12: u32,
+    cdw13: u32,
+    cdw14: u32,
+    cdw15: u32,
+    timeout_ms: u32,
+    result: u32,
+}
+
+pub fn read_disk_health(
+    sys_block: &Path,
+    dev_root: &Path,
+    runner: &dyn CommandRunner,
+) -> anyhow::Result<Vec<DiskHealth>> {
+    let mut names: Vec<String> = fs::read_dir(sys_block)
+        .with_context(|| format!("failed to read {}", sys_block.display()))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        // 只有 NVMe 命名空间与 SCSI/SATA 磁盘支持 SMART，虚拟盘、回环设备等跳过
+        .filter(|name| name.starts_with("nvme") || name.starts_with("sd"))
+        .collect();
+    names.sort();
+
+    Ok(names
+        .iter()
+        .map(|name| {
+            let dir = sys_block.join(name);
+            let mut health = DiskHealth::new(name);
+            health.model = read_attr(&dir.join("device/model"));
+            health.serial_number = read_attr(&dir.join("device/serial"));
+            read_one(&mut health, &dev_root.join(name), runner);
+            health
+        })
+        .collect())
+}
+
+fn read_one(health: &mut DiskHealth, node: &Path, runner: &dyn CommandRunner) {
+    let ioctl = if health.device.starts_with("nvme") {
+        read_nvme_log(node).and_then(|page| {
+            smart::apply_nvme_log(health, &page).map_err(|e| io::Error::other(e.to_string()))
+        })
+    } else {
+        read_ata_smart(node).and_then(|(values, thresholds)| {
+            smart::apply_ata_smart(health, &values, Some(&thresholds))
+                .map_err(|e| io::Error::other(e.to_string()))
+        })
+    };
+    let ioctl_err = match ioctl {
+        Ok(()) => {
+            health.source = Some("ioctl".to_string());
+            return;
+        }
+        Err(e) => e,
+    };
+
+    // smartctl 的退出码是位掩码（例如有错误日志也会非 0），这里只看 JSON 内容
+    let node = node.to_string_lossy();
+    let smartctl = runner
+        .run("smartctl", &["--json", "-a", &node])
+        .map_err(anyhow::Error::from)
+        .and_then(|output| smart::parse_smartctl(health, &output.stdout));
+    if let Err(e) = smartctl {
+        let hint = if ioctl_err.kind() == io::ErrorKind::PermissionDenied {
+            " (root privileges required)"
+        } else {
+            ""
+        };
+        health.error = Some(format!("{}: {}{}; {}", node, ioctl_err, hint, e));
+    }
+}
+
+/// 读取 NVMe SMART / Health 日志页（全部命名空间）
+fn read_nvme_log(node: &Path) -> io::Result<[u8; NVME_LOG_SIZE]> {
+    let file = File::open(node)?;
+    let mut page = [0u8; NVME_LOG_SIZE];
+    let mut cmd = NvmeAdminCmd {
+        opcode: NVME_ADMIN_GET_LOG_PAGE,
+        nsid: 0xFFFF_FFFF,
+        addr: page.as_mut_ptr() as u64,
+        data_len: NVME_LOG_SIZE as u32,
+        // 低 8 位为日志页 ID，高 16 位为要读取的双字数减一
+        cdw10: ((NVME_LOG_SIZE as u32 / 4 - 1) << 16) | NVME_LOG_SMART,
+        ..Default::default()
+    };
+    // SAFETY: cmd 与 page 在调用期间有效，data_len 与 page 的长度一致
+    let ret = unsafe { libc::ioctl(file.as_raw_fd(), NVME_IOCTL_ADMIN_CMD as _, &mut cmd) };
+    if ret < 0 {
+        return Err(io::Error::last_os_error());
+    }
+    if ret > 0 {
+        // 正数是 NVMe 状态码
+        return Err(io::Error::other(format!("nvme status {:#x}", ret)));
+    }
+    Ok(page)
+}
+
+/// 读取 ATA SMART 属性值与阈值
+fn read_ata_smart(node: &Path) -> io::Result<(Vec<u8>, Vec<u8>)> {
+    let file = File::open(node)?;
+    let values = drive_cmd(&file, ATA_SMART_READ_VALUES, 0)?;
+    let thresholds = drive_cmd(&file, ATA_SMART_READ_THRESHOLDS, 1)?;
+    Ok((values, thresholds))
+}
+
+/// HDIO_DRIVE_CMD：前 4 字节依次为命令、扇区号、feature、扇区数，数据紧随其后
+fn drive_cmd(file: &File, feature: u8, sector: u8) -> io::Result<Vec<u8>> {
+    let mut buf = vec![0u8; 4 + ATA_SMART_SIZE];
+    buf[..4].copy_from_slice(&[ATA_SMART_CMD, sector, feature, 1]);
+    // SAFETY: buf 的长度满足一个扇区的数据返回
+    let ret = unsafe { libc::ioctl(file.as_raw_fd(), HDIO_DRIVE_CMD as _, buf.as_mut_ptr()) };
+    if ret != 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(buf.split_off(4))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{fixture_name, ReplayRunner};
+
+    /// 假的 /sys/block 与 /dev：设备节点是普通文件，ioctl 一定失败，只能回退到 smartctl
+    #[test]
+    fn falls_back_to_smartctl() {
+        let tmp = tempfile::tempdir().unwrap();
+        let sys_block = tmp.path().join("block");
+        let dev = tmp.path().join("dev");
+        let smartctl = tmp.path().join("smartctl");
+        for name in ["nvme0n1", "nvme1n1", "sda", "loop0", "dm-0"] {
+            fs::create_dir_all(sys_block.join(name).join("device")).unwrap();
+        }
+        fs::write(sys_block.join("sda/device/model"), "WDC WD10EZEX\n").unwrap();
+        fs::create_dir_all(&dev).unwrap();
+        fs::write(dev.join("nvme0n1"), "").unwrap();
+        fs::write(dev.join("nvme1n1"), "").unwrap();
+        fs::create_dir_all(&smartctl).unwrap();
+
+        let recorded = [
+            ("nvme0n1", "smartctl-nvme.json"),
+            ("nvme1n1", "smartctl-permission-denied.json"),
+        ];
+        for (name, fixture) in recorded {
+            let node = dev.join(name);
+            let file = fixture_name("smartctl", &["--json", "-a", &node.to_string_lossy()]);
+            let content = fs::read_to_string(
+                Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join("fixtures/smart")
+                    .join(fixture),
+            )
+            .unwrap();
+            fs::write(smartctl.join(file), content).unwrap();
+        }
+
+        let disks = read_disk_health(&sys_block, &dev, &ReplayRunner::new(&smartctl)).unwrap();
+        let names: Vec<&str> = disks.iter().map(|d| d.device.as_str()).collect();
+        assert_eq!(names, ["nvme0n1", "nvme1n1", "sda"]);
+
+        let nvme = &disks[0];
+        assert_eq!(nvme.source.as_deref(), Some("smartctl"));
+        assert_eq!(nvme.verdict, "good");
+        assert_eq!(nvme.error, None);
+
+        // smartctl 同样打不开设备：判定为 unknown，并带上两边的错误
+        let denied = &disks[1];
+        assert_eq!(denied.verdict, "unknown");
+        assert_eq!(denied.source, None);
+        let error = denied.error.as_deref().unwrap();
+        assert!(error.contains("nvme1n1"), "{}", error);
+        assert!(error.contains("Permission denied"), "{}", error);
+
+        // 没有设备节点，也没有 smartctl
+        let sda = &disks[2];
+        assert_eq!(sda.model.as_deref(), Some("WDC WD10EZEX"));
+        assert_eq!(sda.verdict, "unknown");
+        assert!(sda.error.is_some());
+
+        assert!(read_disk_health(
+            &tmp.path().join("missing"),
+            &dev,
+            &ReplayRunner::new(&smartctl)
+        )
+        .is_err());
+    }
+}
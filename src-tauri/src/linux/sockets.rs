@@ -0,0 +1,253 @@
+//! 套接字表：/proc/net/{tcp,tcp6,udp,udp6}，所属进程由 /proc/<pid>/fd 中的 `socket:[inode]` 链接反查
+//!
+//! 每行 `sl local_address rem_address st ... uid timeout inode`；地址是按本机字节序打印的
+//! 32 位字（IPv6 为 4 个），端口已是主机序的十六进制。
+
+use std::collections::HashMap;
+use std::fs;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::Path;
+
+use super::read_attr;
+use crate::process::sockets::SocketInfo;
+
+pub const PROC_ROOT: &str = "/proc";
+
+/// (文件名, 协议, 地址族)
+const TABLES: &[(&str, &str, &str)] = &[
+    ("tcp", "tcp", "ipv4"),
+    ("tcp6", "tcp", "ipv6"),
+    ("udp", "udp", "ipv4"),
+    ("udp6", "udp", "ipv6"),
+];
+
+/// include/net/tcp_states.h
+const TCP_STATES: &[&str] = &[
+    "UNKNOWN",
+    "ESTABLISHED",
+    "SYN_SENT",
+    "SYN_RECV",
+    "FIN_WAIT1",
+    "FIN_WAIT2",
+    "TIME_WAIT",
+    "CLOSE",
+    "CLOSE_WAIT",
+    "LAST_ACK",
+    "LISTEN",
+    "CLOSING",
+    "NEW_SYN_RECV",
+];
+
+pub fn read_sockets(proc_root: &Path) -> anyhow::Result<Vec<SocketInfo>> {
+    let owners = socket_owners(proc_root);
+    let mut sockets = Vec::new();
+    let mut any = false;
+    for (file, protocol, family) in TABLES {
+        // 关闭了 IPv6 的系统没有 tcp6 / udp6
+        let Ok(table) = fs::read_to_string(proc_root.join("net").join(file)) else {
+            continue;
+        };
+        any = true;
+        for (mut socket, inode) in parse_table(&table, protocol, family) {
+            if let Some((pid, name)) = owners.get(&inode) {
+                socket.pid = Some(*pid);
+                socket.process_name = name.clone();
+            }
+            sockets.push(socket);
+        }
+    }
+    if !any {
+        anyhow::bail!("failed to read {}/net/tcp", proc_root.display());
+    }
+    Ok(sockets)
+}
+
+/// 解析一张表，返回套接字及其 inode（inode 为 0 的是已经没有所属文件的 TIME_WAIT 等）
+pub fn parse_table(s: &str, protocol: &str, family: &str) -> Vec<(SocketInfo, u64)> {
+    s.lines()
+        .skip(1)
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            if f.len() < 10 {
+                return None;
+            }
+            let (local_address, local_port) = parse_endpoint(f[1])?;
+            let (remote_address, remote_port) = parse_endpoint(f[2])?;
+            let st = usize::from_str_radix(f[3], 16).ok()?;
+            let connected = remote_port != 0 || !remote_address.is_unspecified();
+            let state = match protocol {
+                "udp" if connected => "ESTABLISHED",
+                "udp" => "UNCONN",
+                _ => TCP_STATES.get(st).copied().unwrap_or("UNKNOWN"),
+            };
+            let inode = f[9].parse().ok()?;
+            Some((
+                SocketInfo {
+                    protocol: protocol.to_string(),
+                    family: family.to_string(),
+                    local_address,
+                    local_port,
+                    remote_address: connected.then_some(remote_address),
+                    remote_port: connected.then_some(remote_port),
+                    state: state.to_string(),
+                    pid: None,
+                    process_name: None,
+                },
+                inode,
+            ))
+        })
+        .collect()
+}
+
+/// `0100007F:1F90` 或 IPv6 的 32 位十六进制地址加端口
+fn parse_endpoint(s: &str) -> Option<(IpAddr, u16)> {
+    let (addr, port) = s.split_once(':')?;
+    let port = u16::from_str_radix(port, 16).ok()?;
+    let mut bytes = Vec::with_capacity(16);
+    for i in (0..addr.len()).step_by(8) {
+        let word = u32::from_str_radix(addr.get(i..i + 8)?, 16).ok()?;
+        bytes.extend_from_slice(&word.to_ne_bytes());
+    }
+    let addr = match bytes.len() {
+        4 => IpAddr::V4(Ipv4Addr::from(<[u8; 4]>::try_from(bytes).ok()?)),
+        16 => {
+            let v6 = Ipv6Addr::from(<[u8; 16]>::try_from(bytes).ok()?);
+            // 双栈套接字上的 IPv4 连接显示为 ::ffff:a.b.c.d
+            v6.to_ipv4_mapped().map_or(IpAddr::V6(v6), IpAddr::V4)
+        }
+        _ => return None,
+    };
+    Some((addr, port))
+}
+
+/// 套接字 inode -> (pid, 进程名)；没有权限读取的进程直接跳过
+fn socket_owners(proc_root: &Path) -> HashMap<u64, (u32, Option<String>)> {
+    let mut owners = HashMap::new();
+    let Ok(entries) = fs::read_dir(proc_root) else {
+        return owners;
+    };
+    for entry in entries.filter_map(|e| e.ok()) {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|n| n.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        let Ok(fds) = fs::read_dir(entry.path().join("fd")) else {
+            continue;
+        };
+        let mut name = None;
+        for fd in fds.filter_map(|e| e.ok()) {
+            let Ok(target) = fs::read_link(fd.path()) else {
+                continue;
+            };
+            let Some(inode) = target
+                .to_str()
+                .and_then(|t| t.strip_prefix("socket:["))
+                .and_then(|t| t.strip_suffix(']'))
+                .and_then(|t| t.parse::<u64>().ok())
+            else {
+                continue;
+            };
+            let name = name.get_or_insert_with(|| read_attr(&entry.path().join("comm")));
+            // 同一个套接字可能被父子进程共享，保留 pid 较小的（通常是父进程）
+            owners
+                .entry(inode)
+                .and_modify(|(p, n)| {
+                    if pid < *p {
+                        (*p, *n) = (pid, name.clone());
+                    }
+                })
+                .or_insert((pid, name.clone()));
+        }
+    }
+    owners
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linux::fixture_root;
+
+    /// 与 `ss -tuanp` 相近的一行摘要，便于整表比较
+    fn row(s: &SocketInfo) -> String {
+        let remote = match (s.remote_address, s.remote_port) {
+            (Some(a), Some(p)) => format!("{a}:{p}"),
+            _ => "*".to_string(),
+        };
+        format!(
+            "{} {} {}:{} {} {} {:?} {}",
+            s.protocol,
+            s.family,
+            s.local_address,
+            s.local_port,
+            remote,
+            s.state,
+            s.pid,
+            s.process_name.as_deref().unwrap_or("-"),
+        )
+    }
+
+    #[test]
+    fn intel_laptop_sockets() {
+        let sockets = read_sockets(&fixture_root("intel-laptop").join("proc")).unwrap();
+        let rows: Vec<String> = sockets.iter().map(row).collect();
+        assert_eq!(
+            rows,
+            [
+                "tcp ipv4 127.0.0.53:53 * LISTEN Some(612) systemd-resolve",
+                "tcp ipv4 127.0.0.1:631 * LISTEN Some(1877) cupsd",
+                // 24190 与 24215 共享同一个监听套接字，归到 pid 较小的父进程
+                "tcp ipv4 0.0.0.0:8080 * LISTEN Some(24190) node",
+                "tcp ipv4 192.168.1.42:54182 140.187.45.94:443 ESTABLISHED Some(25102) firefox",
+                // inode 为 0，已没有所属进程
+                "tcp ipv4 192.168.1.42:46306 142.250.193.34:443 TIME_WAIT None -",
+                "tcp ipv6 :::22 * LISTEN Some(845) sshd",
+                "tcp ipv6 ::1:5432 * LISTEN Some(26011) code",
+                // 双栈套接字上的 IPv4 连接
+                "tcp ipv6 127.0.0.1:8080 127.0.0.1:57796 ESTABLISHED Some(24190) node",
+                "udp ipv4 127.0.0.53:53 * UNCONN Some(612) systemd-resolve",
+                "udp ipv4 0.0.0.0:5353 * UNCONN Some(903) avahi-daemon",
+                "udp ipv4 192.168.1.42:59601 192.168.1.1:53 ESTABLISHED Some(25102) firefox",
+                "udp ipv6 :::5353 * UNCONN Some(903) avahi-daemon",
+            ]
+        );
+    }
+
+    #[test]
+    fn missing_tables() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(read_sockets(tmp.path()).is_err());
+
+        // 关闭了 IPv6 的系统只有 tcp / udp
+        let tcp = fs::read_to_string(fixture_root("intel-laptop").join("proc/net/tcp")).unwrap();
+        fs::create_dir(tmp.path().join("net")).unwrap();
+        fs::write(tmp.path().join("net/tcp"), tcp).unwrap();
+        let sockets = read_sockets(tmp.path()).unwrap();
+        assert_eq!(sockets.len(), 5);
+        assert!(sockets.iter().all(|s| s.pid.is_none()));
+    }
+
+    #[test]
+    fn malformed_lines() {
+        let table = "  sl  local_address rem_address   st\n\
+                     0: 0100007F:1F90 00000000:0000 0A 0:0 00:0 0 1000 0\n\
+                     1: 0100007F 00000000:0000 0A 0:0 00:0 0 1000 0 1\n\
+                     2: 0100007F:1F90 00000000:0000 ZZ 0:0 00:0 0 1000 0 1\n\
+                     3: 01007F:1F90 00000000:0000 0A 0:0 00:0 0 1000 0 1\n\
+                     4: 0100007F:1F90 00000000:0000 0A 0:0 00:0 0 1000 0 77\n";
+        let parsed = parse_table(table, "tcp", "ipv4");
+        assert_eq!(parsed.len(), 1);
+        assert_eq!(parsed[0].1, 77);
+        assert_eq!(parsed[0].0.local_port, 8080);
+
+        // 未知状态号
+        let parsed = parse_table(
+            "header\n0: 0100007F:1F90 00000000:0000 1F 0:0 00:0 0 1000 0 1\n",
+            "tcp",
+            "ipv4",
+        );
+        assert_eq!(parsed[0].0.state, "UNKNOWN");
+    }
+}
@@ -0,0 +1,519 @@
+//! 物理磁盘与分区：/sys/block、udev 数据库（/run/udev/data）与 /proc/mounts
+//!
+//! sysfs 的 `size` 以 512 字节扇区计；分区是磁盘目录下带 `partition` 文件的子目录，
+//! 叠在分区上的 device-mapper 设备（LVM、LUKS）列在 `holders/` 中，可以多层嵌套。
+//! 分区表类型、文件系统与卷标来自 udev 的 `E:ID_*` 属性，文件名为 `b<主设备号>:<次设备号>`。
+//! btrfs 子卷没有独立的块设备，只能从挂载参数里的 `subvol=` 看出来。
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+
+use super::disk::{parse_mounts, Mount};
+use super::{read_attr, read_attr_u64};
+use crate::{Partition, PhysicalDisk, Volume};
+
+pub const UDEV_DATA_ROOT: &str = "/run/udev/data";
+
+/// 回环、内存盘与 zram 不是物理磁盘；dm-* 作为分区上的卷出现
+const IGNORED_PREFIXES: &[&str] = &["loop", "ram", "zram", "dm-"];
+
+const SECTOR_SIZE: u64 = 512;
+
+pub fn read_storage_devices(
+    sys_block: &Path,
+    udev_data: &Path,
+    mounts: &Path,
+) -> anyhow::Result<Vec<PhysicalDisk>> {
+    let mounts = fs::read_to_string(mounts)
+        .map(|s| parse_mounts(&s))
+        .unwrap_or_default();
+    let mut names: Vec<String> = fs::read_dir(sys_block)
+        .with_context(|| format!("failed to read {}", sys_block.display()))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+        .filter(|name| !IGNORED_PREFIXES.iter().any(|p| name.starts_with(p)))
+        .collect();
+    names.sort();
+
+    let reader = Reader {
+        sys_block,
+        udev_data,
+        mounts: &mounts,
+    };
+    Ok(names.iter().map(|name| reader.disk(name)).collect())
+}
+
+/// 读取过程中共用的路径与挂载表
+struct Reader<'a> {
+    sys_block: &'a Path,
+    udev_data: &'a Path,
+    mounts: &'a [Mount],
+}
+
+impl Reader<'_> {
+    fn disk(&self, name: &str) -> PhysicalDisk {
+        let dir = self.sys_block.join(name);
+        let udev = self.udev(&dir);
+        let attr = |path: &str| read_attr(&dir.join(path));
+
+        let mut partitions: Vec<(u64, Partition)> = fs::read_dir(&dir)
+            .map(|entries| {
+                entries
+                    .filter_map(|e| e.ok())
+                    .filter_map(|e| {
+                        let number = read_attr_u64(&e.path().join("partition"))?;
+                        let part = e.file_name().to_string_lossy().into_owned();
+                        Some((number, self.partition(&e.path(), &part)))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        partitions.sort_by_key(|(n, _)| *n);
+        let mut partitions: Vec<Partition> = partitions.into_iter().map(|(_, p)| p).collect();
+        // 直接格式化整块盘（没有分区表）时，把磁盘本身当作唯一的分区
+        if partitions.is_empty() {
+            let whole = self.partition(&dir, name);
+            if whole.file_system.is_some() || !whole.volumes.is_empty() {
+                partitions.push(whole);
+            }
+        }
+
+        PhysicalDisk {
+            device: name.to_string(),
+            model: attr("device/model").or_else(|| udev.get("ID_MODEL").cloned()),
+            serial_number: attr("device/serial").or_else(|| udev.get("ID_SERIAL_SHORT").cloned()),
+            size_bytes: read_attr_u64(&dir.join("size")).unwrap_or(0) * SECTOR_SIZE,
+            kind: attr("queue/rotational")
+                .map(|r| if r == "1" { "HDD" } else { "SSD" }.to_string()),
+            removable: attr("removable").as_deref() == Some("1"),
+            read_only: attr("ro").as_deref() == Some("1"),
+            partition_table: udev.get("ID_PART_TABLE_TYPE").map(|t| match t.as_str() {
+                "dos" => "mbr".to_string(),
+                other => other.to_string(),
+            }),
+            partitions,
+        }
+    }
+
+    fn partition(&self, dir: &Path, name: &str) -> Partition {
+        let udev = self.udev(dir);
+        let (mount_points, fs_type, mut volumes) = self.mounts_of(&[name.to_string()], name);
+        self.holders(dir, name, &mut HashSet::new(), &mut volumes);
+        Partition {
+            device: name.to_string(),
+            size_bytes: read_attr_u64(&dir.join("size")).unwrap_or(0) * SECTOR_SIZE,
+            label: udev
+                .get("ID_PART_ENTRY_NAME")
+                .or_else(|| udev.get("ID_FS_LABEL"))
+                .cloned(),
+            file_system: udev.get("ID_FS_TYPE").cloned().or(fs_type),
+            mount_points,
+            volumes,
+        }
+    }
+
+    /// 叠在 `dir` 之上的 device-mapper 设备，递归收集到 `out`；
+    /// `visited` 记录已经展开过的设备，sysfs 出现环时不会无限递归
+    fn holders(
+        &self,
+        dir: &Path,
+        parent: &str,
+        visited: &mut HashSet<String>,
+        out: &mut Vec<Volume>,
+    ) {
+        let Ok(entries) = fs::read_dir(dir.join("holders")) else {
+            return;
+        };
+        let mut holders: Vec<String> = entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .collect();
+        holders.sort();
+
+        for device in holders {
+            if !visited.insert(device.clone()) {
+                log::warn!("cyclic holders at {} -> {}", parent, device);
+                continue;
+            }
+            let dm_dir = self.sys_block.join(&device);
+            let dm_name = read_attr(&dm_dir.join("dm/name")).unwrap_or_else(|| device.clone());
+            // dm uuid 的前缀标明用途：LVM-<vg/lv uuid>、CRYPT-LUKS2-<uuid>-<name>
+            let kind = match read_attr(&dm_dir.join("dm/uuid")) {
+                Some(uuid) if uuid.starts_with("LVM-") => "lvm",
+                Some(uuid) if uuid.starts_with("CRYPT-") => "crypt",
+                _ => "dm",
+            };
+            let udev = self.udev(&dm_dir);
+            let sources = [device.clone(), format!("mapper/{}", dm_name)];
+            let (mount_points, fs_type, subvolumes) = self.mounts_of(&sources, &dm_name);
+            out.push(Volume {
+                name: dm_name.clone(),
+                device: Some(device.clone()),
+                kind: kind.to_string(),
+                parent: parent.to_string(),
+                size_bytes: read_attr_u64(&dm_dir.join("size")).map(|s| s * SECTOR_SIZE),
+                file_system: udev.get("ID_FS_TYPE").cloned().or(fs_type),
+                mount_points,
+            });
+            out.extend(subvolumes);
+            self.holders(&dm_dir, &dm_name, visited, out);
+        }
+    }
+
+    /// 设备（按 /dev 下的任一名字匹配）的挂载点、文件系统，以及挂出来的 btrfs 子卷
+    fn mounts_of(
+        &self,
+        sources: &[String],
+        parent: &str,
+    ) -> (Vec<String>, Option<String>, Vec<Volume>) {
+        let mounts: Vec<&Mount> = self
+            .mounts
+            .iter()
+            .filter(|m| sources.contains(&m.source))
+            .collect();
+        let fs_type = mounts.first().map(|m| m.fs_type.clone());
+
+        let mut mount_points = Vec::new();
+        let mut subvolumes: Vec<Volume> = Vec::new();
+        for m in mounts {
+            match m.subvolume() {
+                Some(subvol) => match subvolumes.iter_mut().find(|v| v.name == subvol) {
+                    Some(v) => v.mount_points.push(m.target.clone()),
+                    None => subvolumes.push(Volume {
+                        name: subvol.to_string(),
+                        device: None,
+                        kind: "btrfs-subvolume".to_string(),
+                        parent: parent.to_string(),
+                        size_bytes: None,
+                        file_system: Some(m.fs_type.clone()),
+                        mount_points: vec![m.target.clone()],
+                    }),
+                },
+                None => mount_points.push(m.target.clone()),
+            }
+        }
+        (mount_points, fs_type, subvolumes)
+    }
+
+    /// udev 数据库中该设备的 `E:KEY=VALUE` 属性
+    fn udev(&self, dir: &Path) -> HashMap<String, String> {
+        read_attr(&dir.join("dev"))
+            .and_then(|dev| fs::read_to_string(self.udev_data.join(format!("b{}", dev))).ok())
+            .map(|content| {
+                content
+                    .lines()
+                    .filter_map(|line| line.strip_prefix("E:")?.split_once('='))
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::linux::fixture_root;
+
+    /// 在临时目录里写一个 sysfs / udev 文件，目录按需创建
+    fn write(root: &Path, path: &str, content: &str) {
+        let path = root.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn holder(root: &Path, device: &str, holder: &str) {
+        fs::create_dir_all(root.join(format!("block/{}/holders/{}", device, holder))).unwrap();
+    }
+
+    /// (name, device, kind, parent, file_system, mount_points)
+    type VolumeSummary<'a> = (
+        &'a str,
+        Option<&'a str>,
+        &'a str,
+        &'a str,
+        Option<&'a str>,
+        Vec<&'a str>,
+    );
+
+    fn summary(volumes: &[Volume]) -> Vec<VolumeSummary<'_>> {
+        volumes
+            .iter()
+            .map(|v| {
+                (
+                    v.name.as_str(),
+                    v.device.as_deref(),
+                    v.kind.as_str(),
+                    v.parent.as_str(),
+                    v.file_system.as_deref(),
+                    v.mount_points.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn intel_laptop() {
+        let root = fixture_root("intel-laptop");
+        let disks = read_storage_devices(
+            &root.join("block"),
+            &root.join("udev/data"),
+            &root.join("mounts"),
+        )
+        .unwrap();
+        // loop0 与 dm-0 不是物理磁盘
+        assert_eq!(disks.len(), 1);
+        let nvme = &disks[0];
+        assert_eq!(nvme.device, "nvme0n1");
+        assert_eq!(nvme.model.as_deref(), Some("SAMSUNG MZVL2512HCJQ-00BL7"));
+        assert_eq!(nvme.serial_number.as_deref(), Some("S64KNX0T512345"));
+        assert_eq!(nvme.size_bytes, 1000215216 * 512);
+        assert_eq!(nvme.kind.as_deref(), Some("SSD"));
+        assert!(!nvme.removable);
+        assert!(!nvme.read_only);
+        assert_eq!(nvme.partition_table.as_deref(), Some("gpt"));
+
+        let parts: Vec<&str> = nvme.partitions.iter().map(|p| p.device.as_str()).collect();
+        assert_eq!(parts, ["nvme0n1p1", "nvme0n1p2", "nvme0n1p3"]);
+
+        let efi = &nvme.partitions[0];
+        assert_eq!(efi.label.as_deref(), Some("EFI system partition"));
+        assert_eq!(efi.file_system.as_deref(), Some("vfat"));
+        assert_eq!(efi.mount_points, ["/boot/efi"]);
+        assert!(efi.volumes.is_empty());
+
+        // btrfs 顶层子卷算作分区自身的挂载点，其余子卷作为卷列出
+        let root_part = &nvme.partitions[1];
+        assert_eq!(root_part.label.as_deref(), Some("fedora"));
+        assert_eq!(root_part.mount_points, ["/mnt/btrfs-top"]);
+        assert_eq!(
+            summary(&root_part.volumes),
+            [
+                (
+                    "/root",
+                    None,
+                    "btrfs-subvolume",
+                    "nvme0n1p2",
+                    Some("btrfs"),
+                    vec!["/"]
+                ),
+                (
+                    "/snapshots",
+                    None,
+                    "btrfs-subvolume",
+                    "nvme0n1p2",
+                    Some("btrfs"),
+                    vec!["/.snapshots"]
+                ),
+            ]
+        );
+
+        let luks = &nvme.partitions[2];
+        assert_eq!(luks.file_system.as_deref(), Some("crypto_LUKS"));
+        assert!(luks.mount_points.is_empty());
+        assert_eq!(
+            summary(&luks.volumes),
+            [(
+                "luks-home",
+                Some("dm-0"),
+                "crypt",
+                "nvme0n1p3",
+                Some("ext4"),
+                vec!["/home", "/var/lib/My Data"]
+            )]
+        );
+        assert_eq!(luks.volumes[0].size_bytes, Some(580216832 * 512));
+    }
+
+    /// sda2 -> LUKS -> LVM 物理卷 -> 两个逻辑卷，其中 root 是带子卷的 btrfs
+    fn nested_tree() -> (tempfile::TempDir, PathBuf) {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path().to_path_buf();
+        for (path, content) in [
+            ("block/sda/dev", "8:0"),
+            ("block/sda/size", "1000000"),
+            ("block/sda/queue/rotational", "1"),
+            ("block/sda/removable", "0"),
+            ("block/sda/ro", "0"),
+            ("block/sda/sda1/dev", "8:1"),
+            ("block/sda/sda1/size", "2048"),
+            ("block/sda/sda1/partition", "1"),
+            ("block/sda/sda2/dev", "8:2"),
+            ("block/sda/sda2/size", "997952"),
+            ("block/sda/sda2/partition", "2"),
+            ("block/dm-0/dev", "253:0"),
+            ("block/dm-0/size", "997920"),
+            ("block/dm-0/dm/name", "luks-root"),
+            ("block/dm-0/dm/uuid", "CRYPT-LUKS2-0d1e2f3a4b5c-luks-root"),
+            ("block/dm-1/dev", "253:1"),
+            ("block/dm-1/size", "800000"),
+            ("block/dm-1/dm/name", "vg0-root"),
+            ("block/dm-1/dm/uuid", "LVM-Qm1xK2wz4aBcDeFgHiJkLmNoPqRsTuVw"),
+            ("block/dm-2/dev", "253:2"),
+            ("block/dm-2/size", "16000"),
+            ("block/dm-2/dm/name", "vg0-swap"),
+            ("block/dm-2/dm/uuid", "LVM-Qm1xK2wz4aBcDeFgHiJkLmNoPqRsTuVx"),
+            (
+                "udev/b8:0",
+                "E:ID_MODEL=WDC_WD10EZEX\nE:ID_PART_TABLE_TYPE=dos\n",
+            ),
+            ("udev/b8:1", "E:ID_FS_TYPE=vfat\nE:ID_FS_LABEL=ESP\n"),
+            ("udev/b8:2", "E:ID_FS_TYPE=crypto_LUKS\n"),
+            ("udev/b253:0", "E:ID_FS_TYPE=LVM2_member\n"),
+            ("udev/b253:1", "E:ID_FS_TYPE=btrfs\n"),
+            ("udev/b253:2", "E:ID_FS_TYPE=swap\n"),
+            (
+                "mounts",
+                "/dev/sda1 /boot/efi vfat rw,relatime 0 0\n\
+                 /dev/mapper/vg0-root / btrfs rw,subvolid=256,subvol=/@ 0 0\n\
+                 /dev/mapper/vg0-root /home btrfs rw,subvolid=257,subvol=/@home 0 0\n\
+                 /dev/dm-1 /srv/home btrfs rw,subvolid=257,subvol=/@home 0 0\n\
+                 /dev/mapper/vg0-root /mnt/pool btrfs rw,subvolid=5,subvol=/ 0 0\n",
+            ),
+        ] {
+            write(&root, path, content);
+        }
+        holder(&root, "sda/sda2", "dm-0");
+        holder(&root, "dm-0", "dm-1");
+        holder(&root, "dm-0", "dm-2");
+        (tmp, root)
+    }
+
+    #[test]
+    fn luks_lvm_btrfs() {
+        let (_tmp, root) = nested_tree();
+        let disks = read_storage_devices(
+            &root.join("block"),
+            &root.join("udev"),
+            &root.join("mounts"),
+        )
+        .unwrap();
+
+        // dm-* 只作为卷出现
+        assert_eq!(disks.len(), 1);
+        let sda = &disks[0];
+        assert_eq!(sda.model.as_deref(), Some("WDC_WD10EZEX"));
+        assert_eq!(sda.kind.as_deref(), Some("HDD"));
+        assert_eq!(sda.partition_table.as_deref(), Some("mbr"));
+        assert_eq!(sda.partitions.len(), 2);
+        assert_eq!(sda.partitions[0].label.as_deref(), Some("ESP"));
+        assert_eq!(sda.partitions[0].mount_points, ["/boot/efi"]);
+
+        let sda2 = &sda.partitions[1];
+        assert_eq!(sda2.file_system.as_deref(), Some("crypto_LUKS"));
+        assert!(sda2.mount_points.is_empty());
+        // /@home 以 mapper 名和 dm-1 两种名字挂载，合并为一个子卷
+        assert_eq!(
+            summary(&sda2.volumes),
+            [
+                (
+                    "luks-root",
+                    Some("dm-0"),
+                    "crypt",
+                    "sda2",
+                    Some("LVM2_member"),
+                    vec![]
+                ),
+                (
+                    "vg0-root",
+                    Some("dm-1"),
+                    "lvm",
+                    "luks-root",
+                    Some("btrfs"),
+                    vec!["/mnt/pool"]
+                ),
+                (
+                    "/@",
+                    None,
+                    "btrfs-subvolume",
+                    "vg0-root",
+                    Some("btrfs"),
+                    vec!["/"]
+                ),
+                (
+                    "/@home",
+                    None,
+                    "btrfs-subvolume",
+                    "vg0-root",
+                    Some("btrfs"),
+                    vec!["/home", "/srv/home"]
+                ),
+                (
+                    "vg0-swap",
+                    Some("dm-2"),
+                    "lvm",
+                    "luks-root",
+                    Some("swap"),
+                    vec![]
+                ),
+            ]
+        );
+        assert_eq!(sda2.volumes[0].size_bytes, Some(997920 * 512));
+        assert_eq!(sda2.volumes[2].size_bytes, None);
+    }
+
+    #[test]
+    fn cyclic_holders() {
+        let (_tmp, root) = nested_tree();
+        holder(&root, "dm-1", "dm-0");
+
+        let disks = read_storage_devices(
+            &root.join("block"),
+            &root.join("udev"),
+            &root.join("mounts"),
+        )
+        .unwrap();
+        let names: Vec<&str> = disks[0].partitions[1]
+            .volumes
+            .iter()
+            .map(|v| v.name.as_str())
+            .collect();
+        assert_eq!(names, ["luks-root", "vg0-root", "/@", "/@home", "vg0-swap"]);
+    }
+
+    #[test]
+    fn whole_disk_file_system() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = tmp.path();
+        write(root, "block/sdb/dev", "8:16");
+        write(root, "block/sdb/size", "2048");
+        write(root, "block/sdb/removable", "1");
+        write(root, "block/sdc/dev", "8:32");
+        write(
+            root,
+            "udev/b8:16",
+            "E:ID_FS_TYPE=exfat\nE:ID_FS_LABEL=USB\n",
+        );
+        write(root, "mounts", "/dev/sdb /media/USB exfat rw 0 0\n");
+
+        let disks = read_storage_devices(
+            &root.join("block"),
+            &root.join("udev"),
+            &root.join("mounts"),
+        )
+        .unwrap();
+        let sdb = &disks[0];
+        assert!(sdb.removable);
+        assert_eq!(sdb.kind, None);
+        assert_eq!(sdb.partitions.len(), 1);
+        assert_eq!(sdb.partitions[0].device, "sdb");
+        assert_eq!(sdb.partitions[0].label.as_deref(), Some("USB"));
+        assert_eq!(sdb.partitions[0].mount_points, ["/media/USB"]);
+
+        // 没有分区表也没有文件系统的空盘
+        assert_eq!(disks[1].device, "sdc");
+        assert!(disks[1].partitions.is_empty());
+
+        assert!(read_storage_devices(
+            &root.join("missing"),
+            &root.join("udev"),
+            &root.join("mounts")
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,237 @@
+//! 通过 /sys/class/hwmon 与 /sys/class/thermal 读取温度传感器
+//!
+//! - hwmon：每个设备一个 `hwmonN` 目录，`name` 为驱动名，
+//!   `tempK_input`（毫摄氏度）配合可选的 `tempK_label`、`tempK_max`、`tempK_crit`
+//! - thermal_zone：`type` 为区域名，`temp` 为当前温度，`trip_point_K_type/temp` 给出阈值
+//!
+//! 很多 thermal_zone 同时以 hwmon 设备的形式出现（同名），这类区域只从 hwmon 读取一次。
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use super::{hwmon_indices, numbered_entries, read_attr, read_attr_i64, unique_name};
+use crate::{TempSensor, Temps};
+
+/// 读取 `class_root`（通常为 /sys/class）下的 hwmon 与 thermal 温度，没有任何传感器时返回 None
+pub fn read_temps(class_root: &Path) -> Option<Temps> {
+    let mut sensors = Vec::new();
+    let mut cpu = None;
+    let mut gpu = None;
+    let mut chips = HashSet::new();
+    let mut names = HashSet::new();
+    // 已作为 cpu / gpu 上报的传感器不再放进 others
+    let mut assigned = HashSet::new();
+
+    for dir in numbered_entries(&class_root.join("hwmon"), "hwmon") {
+        let Some(chip) = read_attr(&dir.join("name")) else {
+            continue;
+        };
+        chips.insert(chip.clone());
+
+        for index in hwmon_indices(&dir, "temp", "_input") {
+            let attr =
+                |suffix: &str| read_millidegrees(&dir.join(format!("temp{}_{}", index, suffix)));
+            let Some(celsius) = attr("input") else {
+                continue;
+            };
+            let label = read_attr(&dir.join(format!("temp{}_label", index)));
+
+            match classify(&chip, label.as_deref()) {
+                Some(Kind::Cpu) if cpu.is_none() => {
+                    cpu = Some(celsius);
+                    assigned.insert(sensors.len());
+                }
+                Some(Kind::Gpu) if gpu.is_none() => {
+                    gpu = Some(celsius);
+                    assigned.insert(sensors.len());
+                }
+                _ => {}
+            }
+            sensors.push(TempSensor {
+                name: unique_name(
+                    &mut names,
+                    format!(
+                        "{} {}",
+                        chip,
+                        label.unwrap_or_else(|| format!("temp{}", index))
+                    ),
+                ),
+                celsius,
+                max: attr("max"),
+                critical: attr("crit"),
+            });
+        }
+    }
+
+    for dir in numbered_entries(&class_root.join("thermal"), "thermal_zone") {
+        let Some(kind) = read_attr(&dir.join("type")) else {
+            continue;
+        };
+        if chips.contains(&kind) {
+            continue;
+        }
+        let Some(celsius) = read_millidegrees(&dir.join("temp")) else {
+            continue;
+        };
+
+        // trip point 类型：critical / hot / passive / active，取 hot 作为上限、critical 作为临界值
+        let mut max = None;
+        let mut critical = None;
+        for k in 0.. {
+            let Some(trip) = read_attr(&dir.join(format!("trip_point_{}_type", k))) else {
+                break;
+            };
+            let temp = read_millidegrees(&dir.join(format!("trip_point_{}_temp", k)));
+            match trip.as_str() {
+                "critical" => critical = critical.or(temp),
+                "hot" => max = max.or(temp),
+                _ => {}
+            }
+        }
+
+        if kind == "x86_pkg_temp" && cpu.is_none() {
+            cpu = Some(celsius);
+            assigned.insert(sensors.len());
+        }
+        sensors.push(TempSensor {
+            name: unique_name(&mut names, kind),
+            celsius,
+            max,
+            critical,
+        });
+    }
+
+    if sensors.is_empty() {
+        return None;
+    }
+    Some(Temps {
+        cpu,
+        gpu,
+        others: sensors
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !assigned.contains(i))
+            .map(|(_, s)| (s.name.clone(), s.celsius))
+            .collect(),
+        sensors,
+    })
+}
+
+enum Kind {
+    Cpu,
+    Gpu,
+}
+
+/// 按驱动名和标签判断传感器是否代表 CPU 封装 / GPU 核心温度
+fn classify(chip: &str, label: Option<&str>) -> Option<Kind> {
+    match (chip, label) {
+        // AMD：Tctl 为控制温度，老内核只有 Tdie 或无标签
+        ("k10temp" | "zenpower", Some("Tctl" | "Tdie") | None) => Some(Kind::Cpu),
+        ("coretemp", Some(l)) if l.starts_with("Package id") => Some(Kind::Cpu),
+        // nouveau 通常只有一个无标签的 temp1
+        ("amdgpu" | "nouveau" | "radeon", Some("edge") | None) => Some(Kind::Gpu),
+        _ => None,
+    }
+}
+
+/// 毫摄氏度 -> 摄氏度
+fn read_millidegrees(path: &Path) -> Option<f32> {
+    read_attr_i64(path).map(|v| v as f32 / 1000.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::linux::fixture_root;
+
+    fn sensor<'a>(temps: &'a Temps, name: &str) -> &'a TempSensor {
+        temps
+            .sensors
+            .iter()
+            .find(|s| s.name == name)
+            .unwrap_or_else(|| panic!("no sensor {name}"))
+    }
+
+    #[test]
+    fn intel_laptop() {
+        let temps = read_temps(&fixture_root("intel-laptop")).unwrap();
+        assert_eq!(temps.cpu, Some(61.0));
+        assert_eq!(temps.gpu, Some(45.0));
+
+        let package = sensor(&temps, "coretemp Package id 0");
+        assert_eq!(package.max, Some(100.0));
+        assert_eq!(package.critical, Some(100.0));
+        let gpu = sensor(&temps, "nouveau temp1");
+        assert_eq!(gpu.max, Some(95.0));
+        assert_eq!(gpu.critical, Some(105.0));
+
+        // thermal_zone 的 hot / critical trip point 分别作为上限与临界值，passive 不算
+        let sen1 = sensor(&temps, "SEN1");
+        assert_eq!(sen1.celsius, 43.05);
+        assert_eq!(sen1.max, Some(90.05));
+        assert_eq!(sen1.critical, Some(99.05));
+        let pkg_zone = sensor(&temps, "x86_pkg_temp");
+        assert_eq!(pkg_zone.max, None);
+        assert_eq!(pkg_zone.critical, None);
+
+        // cpu / gpu 已单独上报，不再出现在 others 里
+        let others: Vec<&str> = temps.others.iter().map(|(n, _)| n.as_str()).collect();
+        assert_eq!(
+            others,
+            [
+                "coretemp Core 0",
+                "coretemp Core 1",
+                "x86_pkg_temp",
+                "INT3400 Thermal",
+                "SEN1"
+            ]
+        );
+    }
+
+    #[test]
+    fn ryzen_desktop() {
+        let temps = read_temps(&fixture_root("ryzen-desktop")).unwrap();
+        assert_eq!(temps.cpu, Some(52.125));
+        assert_eq!(temps.gpu, Some(47.0));
+
+        // hwmon10 排在 hwmon4 之后，第二块 nvme 的同名传感器追加序号
+        let names: Vec<&str> = temps.sensors.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "nvme Composite",
+                "nvme Sensor 1",
+                "acpitz temp1",
+                "k10temp Tctl",
+                "k10temp Tccd1",
+                "amdgpu edge",
+                "amdgpu junction",
+                "amdgpu mem",
+                "nct6798 SYSTIN",
+                "nvme Composite #2",
+            ]
+        );
+        let second = sensor(&temps, "nvme Composite #2");
+        assert_eq!(second.celsius, 35.85);
+        assert_eq!(second.max, Some(82.85));
+        assert_eq!(second.critical, Some(84.85));
+        assert_eq!(sensor(&temps, "k10temp Tctl").critical, None);
+        assert_eq!(sensor(&temps, "amdgpu junction").critical, Some(110.0));
+
+        // acpitz 同时是 hwmon 设备，thermal_zone 那份不重复上报
+        assert_eq!(names.iter().filter(|n| n.starts_with("acpitz")).count(), 1);
+    }
+
+    #[test]
+    fn no_sensors_is_none() {
+        let root = tempfile::tempdir().unwrap();
+        assert!(read_temps(root.path()).is_none());
+
+        // 只有非温度的 hwmon 设备（如电池）也没有温度
+        let hwmon = root.path().join("hwmon/hwmon0");
+        std::fs::create_dir_all(&hwmon).unwrap();
+        std::fs::write(hwmon.join("name"), "BAT0\n").unwrap();
+        std::fs::write(hwmon.join("in0_input"), "12500\n").unwrap();
+        assert!(read_temps(root.path()).is_none());
+    }
+}
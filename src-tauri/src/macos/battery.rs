@@ -0,0 +1,249 @@
+//! macOS 电池信息：pmset -g batt + ioreg AppleSmartBattery
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use regex::Regex;
+
+use crate::command::CommandRunner;
+use crate::{BatteryInfo, SingleBattery};
+
+pub fn fetch_battery(runner: &dyn CommandRunner) -> anyhow::Result<BatteryInfo> {
+    // 1) 尝试用 pmset -g batt 获取 percentage 与 charging state（文本）
+    let pm = runner
+        .run("pmset", &["-g", "batt"])
+        .context("failed to run pmset")?;
+    log::debug!("pmset output: {}", pm.stdout);
+
+    // 2) 用 ioreg 获取更多字段（CycleCount, DesignCapacity, MaxCapacity/CurrentCapacity, Temperature）
+    let ioreg = runner
+        .run("ioreg", &["-rn", "AppleSmartBattery"])
+        .context("failed to run ioreg")?;
+
+    // 时间估算（pmset 可能给出 estimate, 但解析 pmset 复杂；这里不做复杂估计）
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    Ok(parse_battery(&pm.stdout, &ioreg.stdout, now))
+}
+
+/// 解析 `pmset -g batt` 与 `ioreg -rn AppleSmartBattery` 的输出
+pub fn parse_battery(pm_stdout: &str, ioreg_out: &str, now: u64) -> BatteryInfo {
+    // pmset 输出示例行可能包含 "93%; discharging;" 或 "Now drawing from 'AC Power'"
+    let mut percentage: Option<f32> = None;
+    let mut state: String = "Unknown".to_string();
+    let ac_online = Some(
+        pm_stdout
+            .to_lowercase()
+            .contains("now drawing from 'ac power'"),
+    );
+    // 匹配 0-100%
+    if let Some(cap) = Regex::new(r"(\d{1,3})%")
+        .unwrap()
+        .captures(pm_stdout)
+        .and_then(|c| c.get(1))
+    {
+        if let Ok(v) = cap.as_str().parse::<f32>() {
+            percentage = Some(v);
+        }
+    }
+
+    if pm_stdout
+        .to_lowercase()
+        .contains("now drawing from 'ac power'")
+    {
+        state = "Charging".to_string();
+    } else if pm_stdout.to_lowercase().contains("discharging")
+        || pm_stdout.to_lowercase().contains("battery power")
+    {
+        state = "Discharging".to_string();
+    } else if pm_stdout.to_lowercase().contains("charged")
+        || pm_stdout.to_lowercase().contains("full")
+    {
+        state = "Full".to_string();
+    }
+
+    // ioreg 输出行形如: | | "CycleCount" = 123
+    let kv_re = Regex::new(r#"\"(?P<k>[A-Za-z0-9_]+)\"\s+=\s+(?P<v>.+)"#).unwrap();
+
+    let mut cycle_count: Option<u32> = None;
+    let mut design_capacity: Option<u32> = None;
+    let mut current_capacity: Option<u32> = None;
+    let mut max_capacity: Option<u32> = None;
+    let mut temp_c: Option<f32> = None;
+    let mut voltage: Option<f32> = None;
+    let mut serial: Option<String> = None;
+    let mut model: Option<String> = None;
+    let mut vendor: Option<String> = None;
+    let mut apple_raw_current_capacity: Option<u64> = None;
+    let mut apple_raw_max_capacity: Option<u64> = None;
+    let mut avg_time_to_full: Option<u32> = None;
+    let mut remain_time: Option<u32> = None;
+    let mut is_charging: bool = false;
+
+    for cap in kv_re.captures_iter(ioreg_out) {
+        let key = cap.name("k").unwrap().as_str();
+        let val_raw = cap.name("v").unwrap().as_str().trim();
+
+        match key {
+            "CycleCount" => {
+                if let Ok(n) = val_raw.parse::<u32>() {
+                    cycle_count = Some(n);
+                }
+            }
+            "AvgTimeToFull" => {
+                if let Ok(n) = val_raw.parse::<u32>() {
+                    if is_charging {
+                        avg_time_to_full = Some(n * 60); // minutes to seconds
+                    } else {
+                        avg_time_to_full = None;
+                    }
+                }
+            }
+            "TimeRemaining" => {
+                if let Ok(n) = val_raw.parse::<u32>() {
+                    remain_time = Some(n * 60); // minutes to seconds
+                }
+            }
+            "AppleRawCurrentCapacity" => {
+                if let Ok(n) = val_raw.parse::<u64>() {
+                    apple_raw_current_capacity = Some(n);
+                }
+            }
+            "AppleRawMaxCapacity" => {
+                if let Ok(n) = val_raw.parse::<u64>() {
+                    apple_raw_max_capacity = Some(n);
+                }
+            }
+            // 单位mAh/mWh，非 Wh
+            "DesignCapacity" => {
+                if let Ok(n) = val_raw.parse::<u32>() {
+                    design_capacity = Some(n);
+                } // ioreg often in mAh/mWh — best-effort convert
+            }
+            "MaxCapacity" | "MaxCapacityOperation" => {
+                if let Ok(n) = val_raw.parse::<u32>() {
+                    max_capacity = Some(n);
+                }
+            }
+            "CurrentCapacity" | "CurrentCapacityOperation" => {
+                if let Ok(n) = val_raw.parse::<u32>() {
+                    current_capacity = Some(n);
+                }
+            }
+            "IsCharging" => {
+                if let Ok(n) = val_raw.parse::<bool>() {
+                    is_charging = n;
+                }
+            }
+            "Temperature" => {
+                // ioreg temperature sometimes in 0.1 C units
+                if let Ok(n) = val_raw.parse::<f32>() {
+                    // Heuristic: if > 1000 likely 0.1C format; else assume C
+                    temp_c = Some(if n > 1000.0 { n / 100.0 } else { n / 1.0 });
+                }
+            }
+            "Voltage" => {
+                if let Ok(n) = val_raw.parse::<f32>() {
+                    // mV
+                    voltage = Some(n);
+                }
+            }
+            "Serial" | "BatterySerial" => {
+                // remove quotes if present
+                let cleaned = val_raw.trim_matches('"').to_string();
+                if !cleaned.is_empty() {
+                    serial = Some(cleaned);
+                }
+            }
+            "DeviceName" | "Model" => {
+                let cleaned = val_raw.trim_matches('"').to_string();
+                if !cleaned.is_empty() {
+                    model = Some(cleaned);
+                }
+            }
+            "Manufacturer" | "BatteryManufacturer" => {
+                let cleaned = val_raw.trim_matches('"').to_string();
+                if !cleaned.is_empty() {
+                    vendor = Some(cleaned);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 构造 SingleBattery（mac 一般只有一块 internal battery）
+    let b = SingleBattery {
+        vendor,
+        model,
+        serial_number: serial,
+        state,
+        apple_raw_current_capacity,
+        apple_raw_max_capacity,
+        percentage,
+        current_capacity,
+        max_capacity,
+        design_capacity,
+        voltage,
+        temperature_c: temp_c,
+        cycle_count,
+        time_to_full_seconds: avg_time_to_full,
+        time_to_empty_seconds: remain_time,
+    };
+
+    BatteryInfo {
+        batteries: vec![b],
+        ac_online,
+        timestamp_unix: now,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::macos_fixtures;
+
+    #[test]
+    fn discharging_from_fixtures() {
+        let runner = macos_fixtures();
+        let pm = runner.run("pmset", &["-g", "batt"]).unwrap().stdout;
+        let ioreg = runner
+            .run("ioreg", &["-rn", "AppleSmartBattery"])
+            .unwrap()
+            .stdout;
+
+        let info = parse_battery(&pm, &ioreg, 1759371164);
+        assert_eq!(info.timestamp_unix, 1759371164);
+        assert_eq!(info.ac_online, Some(false));
+        assert_eq!(info.batteries.len(), 1);
+        let bat = &info.batteries[0];
+        assert_eq!(bat.state, "Discharging");
+        assert_eq!(bat.percentage, Some(87.0));
+        assert_eq!(bat.vendor.as_deref(), Some("SMP"));
+        assert_eq!(bat.model.as_deref(), Some("bq40z651"));
+        assert_eq!(bat.serial_number.as_deref(), Some("F8Y04530ABCDEFGAX"));
+        assert_eq!(bat.cycle_count, Some(312));
+        assert_eq!(bat.design_capacity, Some(6075));
+        assert_eq!(bat.current_capacity, Some(87));
+        assert_eq!(bat.max_capacity, Some(100));
+        assert_eq!(bat.apple_raw_current_capacity, Some(4502));
+        assert_eq!(bat.apple_raw_max_capacity, Some(5187));
+        assert_eq!(bat.voltage, Some(12541.0));
+        assert_eq!(bat.temperature_c, Some(30.51));
+        // TimeRemaining 以分钟计
+        assert_eq!(bat.time_to_empty_seconds, Some(641 * 60));
+        assert_eq!(bat.time_to_full_seconds, None);
+    }
+
+    #[test]
+    fn charging_on_ac() {
+        let pm = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=1)\t42%; charging; 1:10 remaining present: true\n";
+        let info = parse_battery(pm, "", 0);
+        assert_eq!(info.ac_online, Some(true));
+        assert_eq!(info.batteries[0].state, "Charging");
+        assert_eq!(info.batteries[0].percentage, Some(42.0));
+        assert_eq!(info.batteries[0].cycle_count, None);
+    }
+}
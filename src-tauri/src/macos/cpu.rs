@@ -0,0 +1,408 @@
+//! 通过 `sysctl hw machdep.cpu` 读取 CPU 型号、拓扑、频率范围和缓存层级
+//!
+//! Apple Silicon 通过 `hw.perflevelN.*` 描述各级核心（perflevel0 为性能核），
+//! 逻辑 CPU 编号从能效核开始（M1 上 CPU0-3 为能效核、CPU4-7 为性能核），
+//! 同一 L2 的核心组成一个 cluster。Intel 机型没有 perflevel，但有 machdep.cpu.family 等字段
+//! 和 `hw.cpufrequency_min/max`；超线程的兄弟线程编号相邻。
+//!
+//! 缓存：Apple Silicon 每级核心有自己的 `hw.perflevelN.l1icachesize/l1dcachesize/l2cachesize`，
+//! L1 为各核心私有、L2 按 `cpusperl2` 共享（系统级缓存 SLC 不在 sysctl 中）；
+//! Intel 机型用 `hw.l1icachesize` 等全局值，`hw.cacheconfig` 的第 N 项为共享第 N 级缓存的逻辑 CPU 数。
+//!
+//! 指令集扩展来自 `sysctl hw.optional`，值为 1 表示系统报告支持；Apple Silicon 上有 `hw.optional.arm64`。
+
+use std::collections::HashMap;
+
+use anyhow::Context;
+
+use crate::command::CommandRunner;
+use crate::features;
+use crate::{CacheInfo, CpuCore, CpuDetails, CpuFeatures};
+
+pub fn get_cpu_details(runner: &dyn CommandRunner) -> anyhow::Result<CpuDetails> {
+    Ok(parse_cpu_details(&run_sysctl(runner)?))
+}
+
+pub fn get_caches(runner: &dyn CommandRunner) -> anyhow::Result<Vec<CacheInfo>> {
+    Ok(parse_caches(&run_sysctl(runner)?))
+}
+
+pub fn get_cpu_features(runner: &dyn CommandRunner) -> anyhow::Result<CpuFeatures> {
+    let out = runner
+        .run("sysctl", &["hw.optional"])
+        .context("failed to run sysctl")?;
+    if !out.success {
+        anyhow::bail!("sysctl failed: {}", out.stderr.trim());
+    }
+    let kv = parse_sysctl(&out.stdout);
+    let arch = if kv.get("hw.optional.arm64").is_some_and(|v| v == "1") {
+        "aarch64"
+    } else {
+        "x86_64"
+    };
+    // 只有在同一架构上运行时 CPUID 才描述的是这台机器（回放录制数据时可能不是）
+    let cpuid = (arch == std::env::consts::ARCH)
+        .then(features::read_cpuid)
+        .flatten();
+    Ok(features::detect(
+        arch,
+        &|f| {
+            (!f.macos.is_empty()).then(|| {
+                kv.get(&format!("hw.optional.{}", f.macos))
+                    .is_some_and(|v| v == "1")
+            })
+        },
+        cpuid.as_ref(),
+    ))
+}
+
+fn run_sysctl(runner: &dyn CommandRunner) -> anyhow::Result<String> {
+    let out = runner
+        .run("sysctl", &["hw", "machdep.cpu"])
+        .context("failed to run sysctl")?;
+    if !out.success {
+        anyhow::bail!("sysctl failed: {}", out.stderr.trim());
+    }
+    Ok(out.stdout)
+}
+
+/// 解析 `key: value` 形式的 sysctl 输出
+pub fn parse_sysctl(output: &str) -> HashMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_string(), v.trim().to_string()))
+        .collect()
+}
+
+pub fn parse_cpu_details(output: &str) -> CpuDetails {
+    let kv = parse_sysctl(output);
+    let get = |key: &str| kv.get(key).filter(|v| !v.is_empty()).cloned();
+    let num = |key: &str| get(key).and_then(|v| v.parse::<u64>().ok());
+
+    let logical = num("hw.logicalcpu").or_else(|| num("hw.ncpu")).unwrap_or(0) as u32;
+    let physical = num("hw.physicalcpu").map(|v| v as u32);
+    // Hz -> MHz，仅 Intel 机型提供
+    let min_mhz = num("hw.cpufrequency_min").map(|v| v / 1_000_000);
+    let max_mhz = num("hw.cpufrequency_max").map(|v| v / 1_000_000);
+
+    let mut details = CpuDetails {
+        vendor: get("machdep.cpu.vendor").or_else(|| {
+            get("machdep.cpu.brand_string")
+                .filter(|b| b.starts_with("Apple"))
+                .map(|_| "Apple".to_string())
+        }),
+        brand: get("machdep.cpu.brand_string"),
+        family: num("machdep.cpu.family").map(|v| v as u32),
+        model: num("machdep.cpu.model").map(|v| v as u32),
+        stepping: num("machdep.cpu.stepping").map(|v| v as u32),
+        packages: num("hw.packages").map(|v| v as u32),
+        physical_cores: physical,
+        logical_cores: logical,
+        ..Default::default()
+    };
+
+    let levels = num("hw.nperflevels").unwrap_or(0);
+    if levels > 1 {
+        // 从能效最高（编号最大）的一级开始分配逻辑 CPU 编号
+        let (mut next_id, mut cluster) = (0u32, 0u32);
+        for level in (0..levels).rev() {
+            let key = |name: &str| format!("hw.perflevel{}.{}", level, name);
+            let count = num(&key("logicalcpu")).unwrap_or(0) as u32;
+            let per_l2 = num(&key("cpusperl2")).unwrap_or(count as u64).max(1) as u32;
+            let kind = match get(&key("name")).as_deref() {
+                Some("Efficiency") => "efficiency",
+                _ if level == 0 => "performance",
+                _ => "efficiency",
+            };
+            for i in 0..count {
+                let id = next_id + i;
+                details.cores.push(CpuCore {
+                    id,
+                    package_id: Some(0),
+                    cluster_id: Some(cluster + i / per_l2),
+                    core_id: Some(id),
+                    smt_siblings: vec![id],
+                    kind: Some(kind.to_string()),
+                    ..Default::default()
+                });
+            }
+            next_id += count;
+            cluster += count.div_ceil(per_l2);
+
+            let physical = num(&key("physicalcpu")).map(|v| v as u32);
+            match kind {
+                "performance" => details.performance_cores = physical,
+                _ => {
+                    details.efficiency_cores =
+                        Some(details.efficiency_cores.unwrap_or(0) + physical.unwrap_or(0))
+                }
+            }
+        }
+    } else {
+        let threads_per_core = match physical {
+            Some(p) if p > 0 && logical >= p => logical / p,
+            _ => 1,
+        };
+        for id in 0..logical {
+            let core = id / threads_per_core;
+            let first = core * threads_per_core;
+            details.cores.push(CpuCore {
+                id,
+                package_id: Some(0),
+                core_id: Some(core),
+                smt_siblings: (first..first + threads_per_core).collect(),
+                min_mhz,
+                max_mhz,
+                ..Default::default()
+            });
+        }
+    }
+
+    details
+}
+
+pub fn parse_caches(output: &str) -> Vec<CacheInfo> {
+    let kv = parse_sysctl(output);
+    let num = |key: &str| {
+        kv.get(key)
+            .and_then(|v| v.parse::<u64>().ok())
+            .filter(|v| *v > 0)
+    };
+    let line_size = num("hw.cachelinesize").map(|v| v as u32);
+    let mut caches = Vec::new();
+
+    let levels = num("hw.nperflevels").unwrap_or(0);
+    if levels > 1 {
+        // 与 parse_cpu_details 相同，逻辑 CPU 编号从能效最高的一级开始
+        let mut next_id = 0u32;
+        for level in (0..levels).rev() {
+            let key = |name: &str| format!("hw.perflevel{}.{}", level, name);
+            let count = num(&key("logicalcpu")).unwrap_or(0) as u32;
+            let ids: Vec<u32> = (next_id..next_id + count).collect();
+            next_id += count;
+
+            let per_l2 = num(&key("cpusperl2")).unwrap_or(count as u64) as usize;
+            let mut push = |lvl, kind, size: Option<u64>, per: usize| {
+                if let Some(size) = size {
+                    caches.extend(instances(lvl, kind, size, None, line_size, &ids, per));
+                }
+            };
+            push(1, "instruction", num(&key("l1icachesize")), 1);
+            push(1, "data", num(&key("l1dcachesize")), 1);
+            push(2, "unified", num(&key("l2cachesize")), per_l2);
+        }
+    } else {
+        let logical = num("hw.logicalcpu").or_else(|| num("hw.ncpu")).unwrap_or(0) as u32;
+        let ids: Vec<u32> = (0..logical).collect();
+        let config: Vec<usize> = kv
+            .get("hw.cacheconfig")
+            .map(|v| {
+                v.split_whitespace()
+                    .filter_map(|n| n.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let l2_ways = num("machdep.cpu.cache.L2_associativity").map(|v| v as u32);
+
+        for (lvl, kind, key, ways) in [
+            (1, "instruction", "hw.l1icachesize", None),
+            (1, "data", "hw.l1dcachesize", None),
+            (2, "unified", "hw.l2cachesize", l2_ways),
+            (3, "unified", "hw.l3cachesize", None),
+        ] {
+            let Some(size) = num(key) else { continue };
+            // 缺少 cacheconfig 时假定 L3 全部共享、L1/L2 每个逻辑 CPU 私有
+            let default = if lvl == 3 { ids.len() } else { 1 };
+            let per = config
+                .get(lvl as usize)
+                .copied()
+                .filter(|n| *n > 0)
+                .unwrap_or(default);
+            caches.extend(instances(lvl, kind, size, ways, line_size, &ids, per));
+        }
+    }
+
+    crate::cache::dedup(caches)
+}
+
+/// 把 `ids` 按每 `per` 个逻辑 CPU 一组切分，每组生成一个缓存实例
+fn instances(
+    level: u32,
+    kind: &str,
+    size_bytes: u64,
+    ways: Option<u32>,
+    line_size: Option<u32>,
+    ids: &[u32],
+    per: usize,
+) -> Vec<CacheInfo> {
+    ids.chunks(per.max(1))
+        .map(|group| CacheInfo {
+            level,
+            kind: kind.to_string(),
+            size_bytes,
+            ways,
+            line_size,
+            sets: match (ways, line_size) {
+                (Some(w), Some(l)) if w > 0 && l > 0 => {
+                    Some((size_bytes / (w as u64 * l as u64)) as u32)
+                }
+                _ => None,
+            },
+            shared_by: group.len() as u32,
+            shared_cpus: group.to_vec(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::macos_fixtures;
+
+    #[test]
+    fn apple_silicon_perflevels() {
+        let details = get_cpu_details(&macos_fixtures()).unwrap();
+        assert_eq!(details.vendor.as_deref(), Some("Apple"));
+        assert_eq!(details.brand.as_deref(), Some("Apple M1"));
+        assert_eq!(details.family, None);
+        assert_eq!(details.packages, Some(1));
+        assert_eq!(details.logical_cores, 8);
+        assert_eq!(details.physical_cores, Some(8));
+        assert_eq!(details.performance_cores, Some(4));
+        assert_eq!(details.efficiency_cores, Some(4));
+
+        // 能效核在前，每 4 个共享一个 L2 组成一个 cluster
+        let cores: Vec<(u32, &str, Option<u32>)> = details
+            .cores
+            .iter()
+            .map(|c| (c.id, c.kind.as_deref().unwrap(), c.cluster_id))
+            .collect();
+        assert_eq!(
+            cores,
+            [
+                (0, "efficiency", Some(0)),
+                (1, "efficiency", Some(0)),
+                (2, "efficiency", Some(0)),
+                (3, "efficiency", Some(0)),
+                (4, "performance", Some(1)),
+                (5, "performance", Some(1)),
+                (6, "performance", Some(1)),
+                (7, "performance", Some(1)),
+            ]
+        );
+        assert!(details.cores.iter().all(|c| c.max_mhz.is_none()));
+    }
+
+    #[test]
+    fn intel_hyperthreading() {
+        let details = parse_cpu_details(
+            "hw.physicalcpu: 4\n\
+             hw.logicalcpu: 8\n\
+             hw.packages: 1\n\
+             hw.cpufrequency_min: 2300000000\n\
+             hw.cpufrequency_max: 2300000000\n\
+             machdep.cpu.vendor: GenuineIntel\n\
+             machdep.cpu.brand_string: Intel(R) Core(TM) i7-1068NG7 CPU @ 2.30GHz\n\
+             machdep.cpu.family: 6\n\
+             machdep.cpu.model: 126\n\
+             machdep.cpu.stepping: 5\n",
+        );
+        assert_eq!(details.vendor.as_deref(), Some("GenuineIntel"));
+        assert_eq!(
+            (details.family, details.model, details.stepping),
+            (Some(6), Some(126), Some(5))
+        );
+        assert_eq!(details.performance_cores, None);
+        let siblings: Vec<&[u32]> = details
+            .cores
+            .iter()
+            .map(|c| c.smt_siblings.as_slice())
+            .collect();
+        assert_eq!(
+            siblings,
+            [
+                &[0, 1][..],
+                &[0, 1],
+                &[2, 3],
+                &[2, 3],
+                &[4, 5],
+                &[4, 5],
+                &[6, 7],
+                &[6, 7]
+            ]
+        );
+        assert_eq!(details.cores[7].core_id, Some(3));
+        assert_eq!(details.cores[0].min_mhz, Some(2300));
+        assert_eq!(details.cores[0].max_mhz, Some(2300));
+        assert_eq!(details.cores[0].kind, None);
+    }
+
+    #[test]
+    fn sysctl_failure() {
+        let tmp = tempfile::tempdir().unwrap();
+        assert!(get_cpu_details(&crate::command::ReplayRunner::new(tmp.path())).is_err());
+    }
+
+    #[test]
+    fn apple_silicon_caches() {
+        let caches = get_caches(&macos_fixtures()).unwrap();
+        let summary: Vec<(u32, &str, u64, u32)> = caches
+            .iter()
+            .map(|c| (c.level, c.kind.as_str(), c.size_bytes / 1024, c.shared_by))
+            .collect();
+        let mut expected = Vec::new();
+        // 能效核（CPU0-3）与性能核（CPU4-7）的 L1 大小不同
+        for size in [128, 128, 128, 128, 192, 192, 192, 192] {
+            expected.push((1, "instruction", size, 1));
+        }
+        for size in [64, 64, 64, 64, 128, 128, 128, 128] {
+            expected.push((1, "data", size, 1));
+        }
+        expected.push((2, "unified", 4096, 4));
+        expected.push((2, "unified", 12288, 4));
+        assert_eq!(summary, expected);
+        assert_eq!(caches[16].shared_cpus, [0, 1, 2, 3]);
+        assert_eq!(caches[17].shared_cpus, [4, 5, 6, 7]);
+        assert!(caches.iter().all(|c| c.line_size == Some(128)));
+        // 没有相联度就算不出组数
+        assert!(caches.iter().all(|c| c.sets.is_none()));
+    }
+
+    #[test]
+    fn intel_cache_keys() {
+        let caches = parse_caches(
+            "hw.logicalcpu: 8\n\
+             hw.cachelinesize: 64\n\
+             hw.cacheconfig: 8 2 2 8 0 0 0 0 0 0\n\
+             hw.l1icachesize: 32768\n\
+             hw.l1dcachesize: 49152\n\
+             hw.l2cachesize: 524288\n\
+             hw.l3cachesize: 8388608\n\
+             machdep.cpu.cache.L2_associativity: 8\n",
+        );
+        let count = |level: u32, kind: &str| {
+            caches
+                .iter()
+                .filter(|c| c.level == level && c.kind == kind)
+                .count()
+        };
+        // cacheconfig 的第 1、2 项：每 2 个逻辑 CPU（一对超线程）共享 L1、L2
+        assert_eq!(count(1, "instruction"), 4);
+        assert_eq!(count(1, "data"), 4);
+        assert_eq!(count(2, "unified"), 4);
+        assert_eq!(count(3, "unified"), 1);
+
+        let l2 = caches.iter().find(|c| c.level == 2).unwrap();
+        assert_eq!(l2.shared_cpus, [0, 1]);
+        assert_eq!((l2.ways, l2.sets), (Some(8), Some(1024)));
+        let l3 = caches.last().unwrap();
+        assert_eq!(l3.size_bytes, 8 * 1024 * 1024);
+        assert_eq!(l3.shared_by, 8);
+
+        // 没有 cacheconfig 时 L1/L2 按每个逻辑 CPU 私有、L3 全部共享
+        let caches = parse_caches("hw.ncpu: 2\nhw.l1dcachesize: 32768\nhw.l3cachesize: 4194304\n");
+        assert_eq!(caches.len(), 3);
+        assert_eq!(caches[2].shared_cpus, [0, 1]);
+    }
+}
@@ -0,0 +1,163 @@
+//! 块设备 I/O 计数：`ioreg -rlw0 -c IOBlockStorageDriver` 的 Statistics 字典
+//!
+//! 每个 IOBlockStorageDriver 下面挂着整盘的 IOMedia（第一个 "BSD Name"，如 disk0），
+//! 再往下是分区，APFS 容器合成出来的 disk3、disk3s1 等卷也在同一棵子树里，
+//! 所以子树里的所有 BSD Name 都归到这个设备。挂载点来自 `mount` 的输出。
+//! Statistics 中的 "Total Time" 单位为纳秒；没有 io_ticks 那样的忙碌时间。
+
+use std::collections::HashMap;
+
+use anyhow::Context;
+use regex::Regex;
+
+use crate::command::CommandRunner;
+use crate::disk::DiskCounters;
+
+pub fn get_disk_counters(runner: &dyn CommandRunner) -> anyhow::Result<Vec<DiskCounters>> {
+    let output = runner
+        .run("ioreg", &["-rlw0", "-c", "IOBlockStorageDriver"])
+        .context("failed to run ioreg")?;
+    if !output.success {
+        return Err(anyhow::anyhow!("ioreg failed: {}", output.stderr));
+    }
+    let mount = runner.stdout("mount", &[]).unwrap_or_default();
+    Ok(parse_disk_counters(&output.stdout, &mount))
+}
+
+pub fn parse_disk_counters(ioreg: &str, mount: &str) -> Vec<DiskCounters> {
+    let (Ok(bsd_re), Ok(stat_re)) = (
+        Regex::new(r#""BSD Name" = "([^"]+)""#),
+        Regex::new(r#""([A-Za-z ]+ \((?:Read|Write)\))"=(\d+)"#),
+    ) else {
+        return Vec::new();
+    };
+    let mounts = parse_mount(mount);
+
+    // 每个顶层的 "+-o" 是一个 IOBlockStorageDriver 子树
+    let mut sections: Vec<String> = Vec::new();
+    for line in ioreg.lines() {
+        if line.starts_with("+-o") || sections.is_empty() {
+            sections.push(String::new());
+        }
+        if let Some(section) = sections.last_mut() {
+            section.push_str(line);
+            section.push('\n');
+        }
+    }
+
+    sections
+        .iter()
+        .filter_map(|section| {
+            let members: Vec<&str> = bsd_re
+                .captures_iter(section)
+                .filter_map(|c| c.get(1))
+                .map(|m| m.as_str())
+                .collect();
+            let device = members.first()?.to_string();
+            let stats: HashMap<&str, u64> = stat_re
+                .captures_iter(section)
+                .filter_map(|c| Some((c.get(1)?.as_str(), c.get(2)?.as_str().parse().ok()?)))
+                .collect();
+            let stat = |key: &str| stats.get(key).copied();
+            let queue_ns = stat("Total Time (Read)")? + stat("Total Time (Write)")?;
+
+            let mut mount_points: Vec<String> = mounts
+                .iter()
+                .filter(|(source, _)| members.contains(&source.as_str()))
+                .map(|(_, target)| target.clone())
+                .collect();
+            mount_points.sort();
+            Some(DiskCounters {
+                device,
+                mount_points,
+                read_bytes: stat("Bytes (Read)")?,
+                write_bytes: stat("Bytes (Write)")?,
+                reads: stat("Operations (Read)")?,
+                writes: stat("Operations (Write)")?,
+                busy_ms: None,
+                queue_ms: Some(queue_ns / 1_000_000),
+            })
+        })
+        .collect()
+}
+
+/// `/dev/disk3s1s1 on / (apfs, sealed, local, read-only, journaled)`
+fn parse_mount(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (source, rest) = line.strip_prefix("/dev/")?.split_once(" on ")?;
+            let (target, _) = rest.rsplit_once(" (")?;
+            Some((source.to_string(), target.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::macos_fixtures;
+
+    #[test]
+    fn ioreg_statistics() {
+        let disks = get_disk_counters(&macos_fixtures()).unwrap();
+        assert_eq!(disks.len(), 2);
+
+        // APFS 容器 disk3 及其卷都在 disk0 的子树里
+        let disk0 = &disks[0];
+        assert_eq!(disk0.device, "disk0");
+        assert_eq!(
+            disk0.mount_points,
+            ["/", "/System/Volumes/Data", "/System/Volumes/VM"]
+        );
+        assert_eq!(disk0.read_bytes, 442_419_970_048);
+        assert_eq!(disk0.write_bytes, 449_216_385_024);
+        assert_eq!(disk0.reads, 21_773_152);
+        assert_eq!(disk0.writes, 24_571_863);
+        // 纳秒 -> 毫秒
+        assert_eq!(disk0.queue_ms, Some(471_644_591));
+        assert_eq!(disk0.busy_ms, None);
+
+        let disk4 = &disks[1];
+        assert_eq!(disk4.device, "disk4");
+        assert_eq!(disk4.mount_points, ["/Volumes/Samsung T7"]);
+        assert_eq!((disk4.reads, disk4.writes), (5471, 1022));
+        assert_eq!(disk4.queue_ms, Some(12_024));
+    }
+
+    #[test]
+    fn without_mount_output() {
+        let out = macos_fixtures()
+            .stdout("ioreg", &["-rlw0", "-c", "IOBlockStorageDriver"])
+            .unwrap();
+        let disks = parse_disk_counters(&out, "");
+        assert_eq!(disks.len(), 2);
+        assert!(disks.iter().all(|d| d.mount_points.is_empty()));
+    }
+
+    #[test]
+    fn incomplete_statistics_skipped() {
+        let ioreg = "+-o IOBlockStorageDriver  <class IOBlockStorageDriver>\n\
+                     \x20 |   \"Statistics\" = {\"Bytes (Read)\"=1,\"Bytes (Write)\"=2}\n\
+                     \x20 +-o Media  <class IOMedia>\n\
+                     \x20   |   \"BSD Name\" = \"disk5\"\n";
+        assert!(parse_disk_counters(ioreg, "").is_empty());
+        assert!(parse_disk_counters("", "").is_empty());
+    }
+
+    #[test]
+    fn mount_lines() {
+        let mounts = parse_mount(
+            "/dev/disk3s1s1 on / (apfs, sealed, local, read-only, journaled)\n\
+             devfs on /dev (devfs, local, nobrowse)\n\
+             /dev/disk4s2 on /Volumes/My (Backup) (exfat, local)\n",
+        );
+        assert_eq!(
+            mounts,
+            [
+                ("disk3s1s1".to_string(), "/".to_string()),
+                ("disk4s2".to_string(), "/Volumes/My (Backup)".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,431 @@
+//! 通过 `system_profiler -json SPMemoryDataType` 读取内存信息
+//!
+//! Intel 机型在 `_items` 下逐个列出插槽（`_name` 形如 "BANK 0/ChannelA-DIMM0"），
+//! 厂商与料号是十六进制编码；Apple Silicon 为封装内的统一内存，只有总容量、类型和厂商。
+//!
+//! 内存细分来自 `vm_stat`（页数，首行给出页大小）、`sysctl vm.swapusage` 与
+//! `kern.memorystatus_vm_pressure_level`（1 / 2 / 4 对应 normal / warning / critical），
+//! 以及 `memory_pressure` 输出末尾的 free percentage。
+
+use std::collections::HashMap;
+
+use anyhow::Context;
+use regex::Regex;
+use serde_json::Value;
+
+use super::cpu::parse_sysctl;
+use crate::command::CommandRunner;
+use crate::memory::PagingCounters;
+use crate::{MemoryBreakdown, MemoryLayout, MemoryModule};
+
+const SYSCTL_MEMORY_ARGS: &[&str] = &[
+    "hw.memsize",
+    "vm.swapusage",
+    "kern.memorystatus_vm_pressure_level",
+];
+
+pub fn get_memory_modules(runner: &dyn CommandRunner) -> anyhow::Result<MemoryLayout> {
+    let output = runner
+        .run("system_profiler", &["-json", "SPMemoryDataType"])
+        .context("failed to run system_profiler")?;
+
+    if !output.success {
+        return Err(anyhow::anyhow!("system_profiler failed: {}", output.stderr));
+    }
+
+    parse_memory(&output.stdout)
+}
+
+pub fn parse_memory(stdout: &str) -> anyhow::Result<MemoryLayout> {
+    let v: Value =
+        serde_json::from_str(stdout).context("failed to parse JSON from system_profiler")?;
+    let entry = v
+        .get("SPMemoryDataType")
+        .and_then(|arr| arr.get(0))
+        .ok_or_else(|| anyhow::anyhow!("unexpected JSON structure"))?;
+
+    match entry.get("_items").and_then(|i| i.as_array()) {
+        Some(items) => {
+            let modules: Vec<MemoryModule> = items.iter().map(parse_slot).collect();
+            let slots = modules.len() as u32;
+            Ok(crate::memory::layout(modules, Some(slots), None))
+        }
+        None => {
+            let get = |key: &str| entry.get(key).and_then(|s| s.as_str());
+            let module = MemoryModule {
+                size_bytes: get("SPMemoryDataType").and_then(parse_size),
+                memory_type: get("dimm_type").map(str::to_string),
+                manufacturer: get("dimm_manufacturer").map(decode_manufacturer),
+                ..Default::default()
+            };
+            Ok(crate::memory::layout(vec![module], None, None))
+        }
+    }
+}
+
+fn parse_slot(item: &Value) -> MemoryModule {
+    let get = |key: &str| item.get(key).and_then(|s| s.as_str());
+    let (bank, slot) = match get("_name").map(|n| n.split_once('/')) {
+        Some(Some((bank, slot))) => (Some(bank.to_string()), Some(slot.to_string())),
+        _ => (None, get("_name").map(str::to_string)),
+    };
+    let size_bytes = get("dimm_size").and_then(parse_size);
+    let installed = |key: &str| get(key).filter(|_| size_bytes.is_some());
+
+    MemoryModule {
+        slot,
+        bank,
+        size_bytes,
+        memory_type: installed("dimm_type").map(str::to_string),
+        // system_profiler 把 MT/s 标成 MHz
+        speed_mts: installed("dimm_speed")
+            .and_then(|s| s.split_whitespace().next())
+            .and_then(|n| n.parse().ok()),
+        manufacturer: installed("dimm_manufacturer").map(decode_manufacturer),
+        part_number: installed("dimm_part_number").map(decode_hex_string),
+        serial_number: installed("dimm_serial_number")
+            .filter(|s| s.trim_start_matches("0x").chars().any(|c| c != '0'))
+            .map(str::to_string),
+        ..Default::default()
+    }
+}
+
+/// "16 GB" / "512 MB"；空插槽为 "empty"
+fn parse_size(s: &str) -> Option<u64> {
+    let mut parts = s.split_whitespace();
+    let n: u64 = parts.next()?.parse().ok()?;
+    let mult = match parts.next()? {
+        "TB" => 1 << 40,
+        "GB" => 1 << 30,
+        "MB" => 1 << 20,
+        _ => return None,
+    };
+    Some(n * mult)
+}
+
+/// 厂商有时是 JEDEC 编号（"0x80CE"），常见的换成名字，其余保留原样
+fn decode_manufacturer(s: &str) -> String {
+    match s {
+        "0x80CE" => "Samsung",
+        "0x80AD" => "SK Hynix",
+        "0x802C" => "Micron",
+        "0x0198" => "Kingston",
+        other => other,
+    }
+    .to_string()
+}
+
+/// "0x4D3437..." 形式的 ASCII 十六进制串解码为文本，解不开时保留原样
+fn decode_hex_string(s: &str) -> String {
+    let decoded = s.strip_prefix("0x").and_then(|hex| {
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+            .collect::<Option<Vec<u8>>>()?;
+        String::from_utf8(bytes).ok()
+    });
+    match decoded {
+        Some(text) if !text.trim_matches(['\0', ' ']).is_empty() => {
+            text.trim_matches(['\0', ' ']).to_string()
+        }
+        _ => s.to_string(),
+    }
+}
+
+/// 内存细分与累计分页计数
+pub fn get_memory_breakdown(
+    runner: &dyn CommandRunner,
+) -> anyhow::Result<(MemoryBreakdown, PagingCounters)> {
+    let vm_stat = runner
+        .run("vm_stat", &[])
+        .context("failed to run vm_stat")?;
+    if !vm_stat.success {
+        return Err(anyhow::anyhow!("vm_stat failed: {}", vm_stat.stderr));
+    }
+    let sysctl = runner
+        .run("sysctl", SYSCTL_MEMORY_ARGS)
+        .context("failed to run sysctl")?;
+    // memory_pressure 只用来取 free percentage，失败时忽略
+    let pressure = runner
+        .run("memory_pressure", &[])
+        .ok()
+        .filter(|o| o.success)
+        .map(|o| o.stdout);
+
+    Ok(parse_memory_breakdown(
+        &vm_stat.stdout,
+        &sysctl.stdout,
+        pressure.as_deref(),
+    ))
+}
+
+pub fn parse_memory_breakdown(
+    vm_stat: &str,
+    sysctl: &str,
+    memory_pressure: Option<&str>,
+) -> (MemoryBreakdown, PagingCounters) {
+    let (page_size, pages) = parse_vm_stat(vm_stat);
+    let bytes = |key: &str| pages.get(key).map(|p| p * page_size);
+    let kv = parse_sysctl(sysctl);
+
+    let total = kv
+        .get("hw.memsize")
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(0);
+    let (swap_total, swap_used) = kv
+        .get("vm.swapusage")
+        .map(|v| parse_swapusage(v))
+        .unwrap_or_default();
+    let wired = bytes("Pages wired down");
+    let compressed = bytes("Pages occupied by compressor");
+    let purgeable = bytes("Pages purgeable");
+    // 与活动监视器一致：已用 = App 内存（匿名页减去可清除页）+ 联动内存 + 被压缩内存
+    let app = bytes("Anonymous pages")
+        .unwrap_or(0)
+        .saturating_sub(purgeable.unwrap_or(0));
+    let used = app + wired.unwrap_or(0) + compressed.unwrap_or(0);
+
+    let breakdown = MemoryBreakdown {
+        total_bytes: total,
+        used_bytes: if total > 0 { used.min(total) } else { used },
+        free_bytes: bytes("Pages free").unwrap_or(0),
+        swap_total_bytes: swap_total,
+        swap_used_bytes: swap_used,
+        cached_bytes: bytes("File-backed pages"),
+        compressed_bytes: compressed,
+        wired_bytes: wired,
+        purgeable_bytes: purgeable,
+        pressure_level: kv
+            .get("kern.memorystatus_vm_pressure_level")
+            .and_then(|v| match v.as_str() {
+                "1" => Some("normal"),
+                "2" => Some("warning"),
+                "4" => Some("critical"),
+                _ => None,
+            })
+            .map(str::to_string),
+        free_percent: memory_pressure.and_then(parse_free_percent),
+        ..Default::default()
+    };
+    let counters = PagingCounters {
+        page_in: bytes("Pageins").unwrap_or(0),
+        page_out: bytes("Pageouts").unwrap_or(0),
+        swap_in: bytes("Swapins").unwrap_or(0),
+        swap_out: bytes("Swapouts").unwrap_or(0),
+    };
+    (breakdown, counters)
+}
+
+/// 页大小与各项页数；键名去掉引号，值去掉末尾的句点
+fn parse_vm_stat(output: &str) -> (u64, HashMap<String, u64>) {
+    let page_size = Regex::new(r"page size of (\d+) bytes")
+        .ok()
+        .and_then(|re| re.captures(output)?.get(1)?.as_str().parse().ok())
+        .unwrap_or(4096);
+    let pages = output
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            let n = value.trim().trim_end_matches('.').parse().ok()?;
+            Some((key.trim().trim_matches('"').to_string(), n))
+        })
+        .collect();
+    (page_size, pages)
+}
+
+/// `total = 2048.00M  used = 1058.25M  free = 989.75M  (encrypted)`
+fn parse_swapusage(value: &str) -> (u64, u64) {
+    let Ok(re) = Regex::new(r"(total|used) = ([\d.]+)([KMGT])") else {
+        return (0, 0);
+    };
+    let mut total = 0;
+    let mut used = 0;
+    for cap in re.captures_iter(value) {
+        let n: f64 = cap[2].parse().unwrap_or(0.0);
+        let mult = match &cap[3] {
+            "K" => 1u64 << 10,
+            "M" => 1 << 20,
+            "G" => 1 << 30,
+            _ => 1 << 40,
+        };
+        let bytes = (n * mult as f64) as u64;
+        match &cap[1] {
+            "total" => total = bytes,
+            _ => used = bytes,
+        }
+    }
+    (total, used)
+}
+
+/// `System-wide memory free percentage: 42%`
+fn parse_free_percent(output: &str) -> Option<f32> {
+    output.lines().find_map(|line| {
+        line.strip_prefix("System-wide memory free percentage:")?
+            .trim()
+            .trim_end_matches('%')
+            .parse()
+            .ok()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::macos_fixtures;
+
+    const GIB: u64 = 1 << 30;
+
+    /// Intel 机型的插槽列表：一条已安装（JEDEC 厂商编号、十六进制料号），一条空插槽
+    const INTEL_SLOTS: &str = r#"{
+  "SPMemoryDataType" : [
+    {
+      "_items" : [
+        {
+          "_name" : "BANK 0/ChannelA-DIMM0",
+          "dimm_manufacturer" : "0x80CE",
+          "dimm_part_number" : "0x4D34373141314B34334442312D4357452020",
+          "dimm_serial_number" : "0x36A1C2D3",
+          "dimm_size" : "8 GB",
+          "dimm_speed" : "2667 MHz",
+          "dimm_status" : "ok",
+          "dimm_type" : "DDR4"
+        },
+        {
+          "_name" : "BANK 2/ChannelB-DIMM0",
+          "dimm_manufacturer" : "Empty",
+          "dimm_part_number" : "Empty",
+          "dimm_serial_number" : "Empty",
+          "dimm_size" : "empty",
+          "dimm_speed" : "Empty",
+          "dimm_status" : "empty",
+          "dimm_type" : "Empty"
+        }
+      ],
+      "global_ecc_state" : "ecc_disabled",
+      "is_memory_upgradeable" : "Yes"
+    }
+  ]
+}"#;
+
+    #[test]
+    fn apple_silicon_unified_memory() {
+        let layout = get_memory_modules(&macos_fixtures()).unwrap();
+        assert_eq!(layout.total_slots, None);
+        assert_eq!(layout.populated_slots, 1);
+        assert_eq!(layout.channels_populated, None);
+        assert_eq!(layout.installed_bytes, 16 * GIB);
+        assert_eq!(layout.modules.len(), 1);
+
+        let m = &layout.modules[0];
+        assert_eq!(m.slot, None);
+        assert_eq!(m.size_bytes, Some(16 * GIB));
+        assert_eq!(m.memory_type.as_deref(), Some("LPDDR4"));
+        assert_eq!(m.manufacturer.as_deref(), Some("Hynix"));
+    }
+
+    #[test]
+    fn intel_slots() {
+        let layout = parse_memory(INTEL_SLOTS).unwrap();
+        assert_eq!(layout.total_slots, Some(2));
+        assert_eq!(layout.populated_slots, 1);
+        assert_eq!(layout.channels_populated, Some(1));
+        assert_eq!(layout.installed_bytes, 8 * GIB);
+
+        let m = &layout.modules[0];
+        assert_eq!(m.bank.as_deref(), Some("BANK 0"));
+        assert_eq!(m.slot.as_deref(), Some("ChannelA-DIMM0"));
+        assert_eq!(m.channel.as_deref(), Some("A"));
+        assert_eq!(m.size_bytes, Some(8 * GIB));
+        assert_eq!(m.memory_type.as_deref(), Some("DDR4"));
+        assert_eq!(m.speed_mts, Some(2667));
+        assert_eq!(m.manufacturer.as_deref(), Some("Samsung"));
+        assert_eq!(m.part_number.as_deref(), Some("M471A1K43DB1-CWE"));
+        assert_eq!(m.serial_number.as_deref(), Some("0x36A1C2D3"));
+
+        // 空插槽只保留位置
+        let empty = &layout.modules[1];
+        assert_eq!(empty.bank.as_deref(), Some("BANK 2"));
+        assert_eq!(empty.channel.as_deref(), Some("B"));
+        assert_eq!(empty.size_bytes, None);
+        assert_eq!(empty.memory_type, None);
+        assert_eq!(empty.speed_mts, None);
+        assert_eq!(empty.manufacturer, None);
+        assert_eq!(empty.part_number, None);
+    }
+
+    #[test]
+    fn sizes_and_strings() {
+        assert_eq!(parse_size("512 MB"), Some(512 << 20));
+        assert_eq!(parse_size("1 TB"), Some(1 << 40));
+        assert_eq!(parse_size("empty"), None);
+        assert_eq!(decode_manufacturer("0x802C"), "Micron");
+        assert_eq!(decode_manufacturer("Hynix"), "Hynix");
+        // 解不开或全是填充时保留原样
+        assert_eq!(decode_hex_string("0x4D3437"), "M47");
+        assert_eq!(decode_hex_string("0x0000"), "0x0000");
+        assert_eq!(decode_hex_string("0xZZ"), "0xZZ");
+        assert_eq!(decode_hex_string("CT8G4SFRA32A"), "CT8G4SFRA32A");
+    }
+
+    #[test]
+    fn malformed_json() {
+        assert!(parse_memory("not json").is_err());
+        assert!(parse_memory(r#"{"SPMemoryDataType": []}"#).is_err());
+    }
+
+    #[test]
+    fn breakdown_from_fixtures() {
+        let (b, counters) = get_memory_breakdown(&macos_fixtures()).unwrap();
+        let page = 16384;
+
+        assert_eq!(b.total_bytes, 16 * GIB);
+        assert_eq!(b.free_bytes, 6312 * page);
+        assert_eq!(b.wired_bytes, Some(132716 * page));
+        assert_eq!(b.compressed_bytes, Some(238904 * page));
+        assert_eq!(b.purgeable_bytes, Some(11254 * page));
+        assert_eq!(b.cached_bytes, Some(214876 * page));
+        // App 内存（匿名页 - 可清除页）+ 联动内存 + 被压缩内存
+        assert_eq!(b.used_bytes, ((387561 - 11254) + 132716 + 238904) * page);
+        assert_eq!(b.swap_total_bytes, 3072 << 20);
+        assert_eq!(b.swap_used_bytes, (2181.5 * (1 << 20) as f64) as u64);
+        assert_eq!(b.pressure_level.as_deref(), Some("normal"));
+        assert_eq!(b.free_percent, Some(44.0));
+        assert!(b.psi.is_none());
+
+        assert_eq!(counters.page_in, 6620183 * page);
+        assert_eq!(counters.page_out, 72214 * page);
+        assert_eq!(counters.swap_in, 912004 * page);
+        assert_eq!(counters.swap_out, 1204381 * page);
+    }
+
+    #[test]
+    fn breakdown_without_memory_pressure() {
+        let vm_stat = "Mach Virtual Memory Statistics: (page size of 4096 bytes)\n\
+                       Pages free:                                   100.\n\
+                       Anonymous pages:                             5000.\n\
+                       Pages wired down:                            1000.\n";
+        let sysctl = "hw.memsize: 16384000\nkern.memorystatus_vm_pressure_level: 4\n";
+        let (b, counters) = parse_memory_breakdown(vm_stat, sysctl, None);
+        assert_eq!(b.free_bytes, 100 * 4096);
+        // 已用不超过总量
+        assert_eq!(b.used_bytes, 16384000);
+        assert_eq!(b.compressed_bytes, None);
+        assert_eq!(b.swap_total_bytes, 0);
+        assert_eq!(b.pressure_level.as_deref(), Some("critical"));
+        assert_eq!(b.free_percent, None);
+        assert_eq!(counters.page_in, 0);
+    }
+
+    #[test]
+    fn swapusage_units() {
+        assert_eq!(
+            parse_swapusage("total = 1.50G  used = 512.00K  free = 1.50G"),
+            (3 << 29, 512 << 10)
+        );
+        assert_eq!(
+            parse_swapusage("total = 0.00M  used = 0.00M  free = 0.00M"),
+            (0, 0)
+        );
+        assert_eq!(parse_free_percent("no percentage here"), None);
+    }
+}
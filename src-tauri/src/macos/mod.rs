@@ -0,0 +1,730 @@
+//! macOS 平台采集实现：数据来自 ioreg / sysctl / system_profiler / powermetrics 等命令
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use anyhow::Context;
+use regex::Regex;
+
+use crate::command::{CommandRunner, SharedRunner};
+use crate::disk::DiskCounters;
+use crate::memory::PagingRates;
+use crate::network::NetCounters;
+use crate::platform::Platform;
+use crate::process::sockets::SocketInfo;
+use crate::{
+    BaseboardInfo, BatteryInfo, BiosInfo, BoardInfo, CacheInfo, ChassisInfo, CpuDetails,
+    CpuFeatures, DeviceInfo, DiskHealth, ElectricalSensor, FanSensor, HardwareData, InterfaceInfo,
+    MemoryBreakdown, MemoryLayout, PhysicalDisk, Power, ProductInfo, Sensors, Temps, WifiInfo,
+};
+
+pub mod battery;
+pub mod cpu;
+pub mod disk;
+pub mod memory;
+pub mod network;
+pub mod smart;
+pub mod sockets;
+pub mod storage;
+
+/// smc 提供温度与风扇，cpu_power 提供 CPU/GPU/ANE 功耗
+const POWERMETRICS_ARGS: &[&str] = &["--samplers", "smc,cpu_power", "-i1", "-n1"];
+
+/// powermetrics 单次运行要采样 1 秒，同一轮采样里温度、功耗共用一次输出
+const POWERMETRICS_REUSE: Duration = Duration::from_millis(500);
+
+/// powermetrics 失败（通常是没有 root 权限）后隔这么久才重试，期间直接返回上次的错误
+const POWERMETRICS_RETRY: Duration = Duration::from_secs(60);
+
+/// 最近一次执行 powermetrics 的时间与结果，失败时保存错误信息
+type PowermetricsResult = Option<(Instant, Result<String, String>)>;
+
+pub struct MacosPlatform {
+    runner: SharedRunner,
+    powermetrics: Mutex<PowermetricsResult>,
+    /// 上一次 vm_stat 的分页计数，用于计算速率
+    paging: Mutex<PagingRates>,
+}
+
+impl MacosPlatform {
+    pub fn new(runner: SharedRunner) -> Self {
+        Self {
+            runner,
+            powermetrics: Mutex::new(None),
+            paging: Mutex::new(PagingRates::default()),
+        }
+    }
+
+    fn powermetrics(&self) -> anyhow::Result<String> {
+        self.powermetrics_at(Instant::now())
+    }
+
+    /// 最近一次 powermetrics 结果：成功的输出复用 `POWERMETRICS_REUSE`，失败复用 `POWERMETRICS_RETRY`
+    ///
+    /// 连续失败只在第一次记 warn，之后降为 debug，免得没有权限时每轮采样都刷日志
+    fn powermetrics_at(&self, now: Instant) -> anyhow::Result<String> {
+        let mut cached = self.powermetrics.lock().unwrap();
+        let mut failing = false;
+        if let Some((at, result)) = cached.as_ref() {
+            let reuse = match result {
+                Ok(_) => POWERMETRICS_REUSE,
+                Err(_) => POWERMETRICS_RETRY,
+            };
+            if now.saturating_duration_since(*at) < reuse {
+                return result.clone().map_err(anyhow::Error::msg);
+            }
+            failing = result.is_err();
+        }
+        let result = run_powermetrics(self.runner.as_ref()).map_err(|e| format!("{:#}", e));
+        if let Err(e) = &result {
+            if failing {
+                log::debug!("{}", e);
+            } else {
+                log::warn!("{}", e);
+            }
+        }
+        *cached = Some((now, result.clone()));
+        result.map_err(anyhow::Error::msg)
+    }
+}
+
+impl Platform for MacosPlatform {
+    fn name(&self) -> &'static str {
+        "macos"
+    }
+
+    fn device_info(&self) -> anyhow::Result<DeviceInfo> {
+        Ok(get_device_info(self.runner.as_ref()))
+    }
+
+    fn hardware_data(&self) -> anyhow::Result<HardwareData> {
+        get_hardware_data(self.runner.as_ref())
+    }
+
+    fn board_info(&self) -> anyhow::Result<BoardInfo> {
+        get_board_info(self.runner.as_ref())
+    }
+
+    fn memory_modules(&self) -> anyhow::Result<MemoryLayout> {
+        memory::get_memory_modules(self.runner.as_ref())
+    }
+
+    fn memory_breakdown(&self) -> anyhow::Result<MemoryBreakdown> {
+        let (mut breakdown, counters) = memory::get_memory_breakdown(self.runner.as_ref())?;
+        self.paging.lock().unwrap().apply(&mut breakdown, counters);
+        Ok(breakdown)
+    }
+
+    fn cpu_details(&self) -> anyhow::Result<CpuDetails> {
+        cpu::get_cpu_details(self.runner.as_ref())
+    }
+
+    fn caches(&self) -> anyhow::Result<Vec<CacheInfo>> {
+        cpu::get_caches(self.runner.as_ref())
+    }
+
+    fn cpu_features(&self) -> anyhow::Result<CpuFeatures> {
+        cpu::get_cpu_features(self.runner.as_ref())
+    }
+
+    fn temps(&self) -> Option<Temps> {
+        // powermetrics 通常需要 root 权限，失败时返回 None（前端可提示用户权限或安装 helper）
+        let output = self.powermetrics().ok()?;
+        Some(parse_powermetrics_temps(&output))
+    }
+
+    fn power(&self) -> Option<Power> {
+        parse_powermetrics_power(&self.powermetrics().ok()?)
+    }
+
+    fn disk_counters(&self) -> anyhow::Result<Vec<DiskCounters>> {
+        disk::get_disk_counters(self.runner.as_ref())
+    }
+
+    fn net_counters(&self) -> anyhow::Result<Vec<NetCounters>> {
+        network::get_net_counters(self.runner.as_ref())
+    }
+
+    fn storage_devices(&self) -> anyhow::Result<Vec<PhysicalDisk>> {
+        storage::get_storage_devices(self.runner.as_ref())
+    }
+
+    fn disk_health(&self) -> anyhow::Result<Vec<DiskHealth>> {
+        smart::get_disk_health(self.runner.as_ref())
+    }
+
+    fn sockets(&self) -> anyhow::Result<Vec<SocketInfo>> {
+        sockets::get_sockets(self.runner.as_ref())
+    }
+
+    fn battery(&self) -> anyhow::Result<BatteryInfo> {
+        battery::fetch_battery(self.runner.as_ref())
+    }
+
+    fn sensors(&self) -> anyhow::Result<Sensors> {
+        Ok(parse_powermetrics_sensors(&self.powermetrics()?))
+    }
+
+    fn interfaces(&self) -> anyhow::Result<Vec<InterfaceInfo>> {
+        network::gather_interfaces_via_ifconfig(self.runner.as_ref())
+    }
+
+    fn wifi(&self) -> anyhow::Result<Option<WifiInfo>> {
+        network::get_wifi_info(self.runner.as_ref())
+    }
+
+    fn default_gateway(&self) -> anyhow::Result<Option<String>> {
+        network::get_default_gateway(self.runner.as_ref())
+    }
+
+    fn dns_servers(&self) -> Option<Vec<String>> {
+        network::get_dns_servers(self.runner.as_ref())
+    }
+}
+
+/// 从 `ioreg -rd1 -c IOPlatformExpertDevice` 的输出中读取属性
+pub fn parse_ioreg_property(out: &str, key: &str) -> Option<String> {
+    // 支持 "key" = "value" 或 "key" = <"value">
+    let pattern = format!(
+        r#""{}"\s*=\s*(?:"([^"]+)"|<"([^"]+)">|<([0-9a-fA-F ]+)>)"#,
+        regex::escape(key)
+    );
+    let re = Regex::new(&pattern).ok()?;
+    re.captures(out).and_then(|cap| {
+        // 优先匹配字符串
+        cap.get(1)
+            .or_else(|| cap.get(2))
+            .map(|m| m.as_str().to_string())
+            // 如果是16进制字节，尝试转成字符串
+            .or_else(|| {
+                cap.get(3).and_then(|m| {
+                    let bytes: Vec<u8> = m
+                        .as_str()
+                        .split_whitespace()
+                        .filter_map(|b| u8::from_str_radix(b, 16).ok())
+                        .collect();
+                    String::from_utf8(bytes).ok()
+                })
+            })
+    })
+}
+
+/// 解析 `sysctl -n kern.boottime`，如 "{ sec = 1727658764, usec = 0 } Mon Sep 30 ..."
+pub fn parse_boot_time(s: &str) -> Option<String> {
+    let re = Regex::new(r#"\{ sec = (\d+),"#).ok()?;
+    re.captures(s)
+        .and_then(|cap| cap.get(1).map(|m| m.as_str().to_string()))
+        .and_then(|sec_str| sec_str.parse::<i64>().ok())
+        .and_then(crate::platform::format_utc)
+}
+
+pub fn get_device_info(runner: &dyn CommandRunner) -> DeviceInfo {
+    let ioreg = runner
+        .stdout("ioreg", &["-rd1", "-c", "IOPlatformExpertDevice"])
+        .unwrap_or_default();
+    let serial_number = parse_ioreg_property(&ioreg, "IOPlatformSerialNumber");
+    let hardware_uuid = parse_ioreg_property(&ioreg, "IOPlatformUUID");
+    let model_identifier = parse_ioreg_property(&ioreg, "model");
+    let manufacturer = parse_ioreg_property(&ioreg, "manufacturer");
+    let boot_time_utc = runner
+        .stdout("sysctl", &["-n", "kern.boottime"])
+        .and_then(|s| parse_boot_time(&s));
+
+    let uptime_seconds = sysinfo::System::uptime();
+
+    DeviceInfo {
+        serial_number,
+        hardware_uuid,
+        model_identifier,
+        boot_time_utc,
+        uptime_seconds,
+        manufacturer,
+        product_name: None,
+        board_vendor: None,
+        board_name: None,
+        board_serial: None,
+        machine_id: None,
+        restricted: Vec::new(),
+    }
+}
+
+pub fn get_hardware_data(runner: &dyn CommandRunner) -> anyhow::Result<HardwareData> {
+    let output = runner
+        .run("system_profiler", &["-json", "SPHardwareDataType"])
+        .context("failed to run system_profiler")?;
+
+    if !output.success {
+        return Err(anyhow::anyhow!("system_profiler failed: {}", output.stderr));
+    }
+
+    parse_hardware_data(&output.stdout)
+}
+
+pub fn parse_hardware_data(stdout: &str) -> anyhow::Result<HardwareData> {
+    // system_profiler -json SPHardwareDataType 的输出是 { "SPHardwareDataType": [ { ... } ] }
+    let v: serde_json::Value =
+        serde_json::from_str(stdout).context("failed to parse JSON from system_profiler")?;
+
+    // 保险地从 JSON 中提取字段
+    let hw = v
+        .get("SPHardwareDataType")
+        .and_then(|arr| arr.get(0))
+        .ok_or_else(|| anyhow::anyhow!("unexpected JSON structure"))?;
+
+    let get_str = |key: &str| hw.get(key).and_then(|s| s.as_str()).map(|s| s.to_string());
+
+    Ok(HardwareData {
+        model_name: get_str("machine_name").or_else(|| get_str("Model Name")), // 兼容性尝试
+        model_identifier: get_str("machine_model").or_else(|| get_str("Model Identifier")),
+        model_number: get_str("model_number").or_else(|| get_str("Model Number")),
+        chip: get_str("chip_type").or_else(|| get_str("Chip")),
+        total_number_of_cores: get_str("number_processors")
+            .or_else(|| get_str("Total Number of Cores"))
+            .or_else(|| hw.get("Total Number of Cores").map(|x| x.to_string())),
+        memory: get_str("physical_memory").or_else(|| get_str("Memory")),
+        system_firmware_version: get_str("system_firmware_version")
+            .or_else(|| get_str("boot_rom_version"))
+            .or_else(|| get_str("System Firmware Version")),
+        os_loader_version: get_str("os_loader_version").or_else(|| get_str("OS Loader Version")),
+        serial_number_system: get_str("serial_number")
+            .or_else(|| get_str("Serial Number (system)")),
+        hardware_uuid: get_str("hardware_uuid")
+            .or_else(|| get_str("platform_UUID"))
+            .or_else(|| get_str("Hardware UUID")),
+        provisioning_udid: get_str("provisioning_UDID")
+            .or_else(|| get_str("provisioning_udid"))
+            .or_else(|| get_str("Provisioning UDID")),
+        activation_lock_status: get_str("activation_lock_status")
+            .or_else(|| get_str("Activation Lock Status")),
+    })
+}
+
+/// Mac 没有可读的 SMBIOS，主板页用 system_profiler 的硬件概览拼出同样的结构
+pub fn get_board_info(runner: &dyn CommandRunner) -> anyhow::Result<BoardInfo> {
+    let output = runner
+        .run("system_profiler", &["-json", "SPHardwareDataType"])
+        .context("failed to run system_profiler")?;
+
+    if !output.success {
+        return Err(anyhow::anyhow!("system_profiler failed: {}", output.stderr));
+    }
+
+    parse_board_info(&output.stdout)
+}
+
+pub fn parse_board_info(stdout: &str) -> anyhow::Result<BoardInfo> {
+    let hw = parse_hardware_data(stdout)?;
+    let apple = || Some("Apple Inc.".to_string());
+    // 机型名到 SMBIOS 机箱类型的粗略对应
+    let chassis_type = hw.model_name.as_deref().map(|name| {
+        if name.contains("Book") {
+            "Notebook"
+        } else if name.starts_with("iMac") {
+            "All in One"
+        } else if name.starts_with("Mac mini") {
+            "Mini PC"
+        } else if name.starts_with("Mac Pro") {
+            "Tower"
+        } else {
+            "Desktop"
+        }
+        .to_string()
+    });
+
+    Ok(BoardInfo {
+        bios: Some(BiosInfo {
+            vendor: apple(),
+            version: hw.system_firmware_version.clone(),
+            ..Default::default()
+        }),
+        system: Some(ProductInfo {
+            manufacturer: apple(),
+            product_name: hw.model_name,
+            version: hw.model_identifier.clone(),
+            serial_number: hw.serial_number_system.clone(),
+            uuid: hw.hardware_uuid,
+            sku: hw.model_number,
+            family: None,
+        }),
+        baseboard: Some(BaseboardInfo {
+            manufacturer: apple(),
+            product: hw.model_identifier,
+            ..Default::default()
+        }),
+        chassis: Some(ChassisInfo {
+            manufacturer: apple(),
+            chassis_type,
+            serial_number: hw.serial_number_system,
+            ..Default::default()
+        }),
+    })
+}
+
+// 辅助：从字符串中提取第一个浮点数（如 "CPU die temperature: 69.54 C" -> 69.54）
+fn extract_first_float(s: &str) -> Option<f32> {
+    let mut num = String::new();
+    let mut in_num = false;
+    for ch in s.chars() {
+        if ch.is_ascii_digit() || ch == '.' || ch == '-' {
+            num.push(ch);
+            in_num = true;
+        } else if in_num {
+            break;
+        }
+    }
+    if num.is_empty() {
+        None
+    } else {
+        num.parse::<f32>().ok()
+    }
+}
+
+/// 执行 powermetrics 并返回 stdout，输出中包含 "CPU die temperature"、"CPU Power" 等行
+pub fn run_powermetrics(runner: &dyn CommandRunner) -> anyhow::Result<String> {
+    let out = runner
+        .run("powermetrics", POWERMETRICS_ARGS)
+        .context("failed to run powermetrics")?;
+    if !out.success {
+        // 可能是没有权限或者 powermetrics 不支持所请求的 sampler
+        anyhow::bail!("powermetrics failed: {}", out.stderr.trim());
+    }
+    Ok(out.stdout)
+}
+
+/// 解析 `powermetrics --samplers smc` 输出中的温度行
+pub fn parse_powermetrics_temps(output: &str) -> Temps {
+    // 解析文本，找 "CPU die temperature: 69.54 C" 之类
+    let mut cpu_temp: Option<f32> = None;
+    let mut gpu_temp: Option<f32> = None;
+    let mut others = Vec::new();
+
+    for line in output.lines() {
+        let l = line.trim();
+        if l.to_lowercase().contains("cpu die temperature")
+            || l.to_lowercase().contains("cpu temperature")
+        {
+            if let Some(num) = extract_first_float(l) {
+                cpu_temp = Some(num);
+            }
+        } else if l.to_lowercase().contains("gpu die temperature")
+            || l.to_lowercase().contains("gpu temperature")
+        {
+            if let Some(num) = extract_first_float(l) {
+                gpu_temp = Some(num);
+            }
+        } else if l.to_lowercase().contains("temperature") {
+            if let Some(num) = extract_first_float(l) {
+                others.push((temp_sensor_name(l), num));
+            }
+        }
+    }
+
+    Temps {
+        cpu: cpu_temp,
+        gpu: gpu_temp,
+        others,
+        sensors: Vec::new(),
+    }
+}
+
+/// `Battery temperature: 30.51 C` -> `Battery`：名称会成为历史指标名，不能带读数
+fn temp_sensor_name(line: &str) -> String {
+    let name = line.split_once(':').map_or(line, |(name, _)| name).trim();
+    let stripped = name
+        .len()
+        .checked_sub("temperature".len())
+        .filter(|&i| name.is_char_boundary(i) && name[i..].eq_ignore_ascii_case("temperature"))
+        .map(|i| name[..i].trim_end())
+        .filter(|n| !n.is_empty());
+    stripped.unwrap_or(name).to_string()
+}
+
+/// 按单位识别 `名称: 数值 单位` 形式的行：
+/// `Fan: 1212.53 rpm` 为风扇，`CPU Power: 1234 mW` 为功率，`mV`/`V`、`mA`/`A` 为电压/电流
+pub fn parse_powermetrics_sensors(output: &str) -> Sensors {
+    let mut sensors = Sensors::default();
+
+    for line in output.lines() {
+        let Some((name, rest)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let Some(value) = extract_first_float(rest) else {
+            continue;
+        };
+        let name = name.trim().to_string();
+        let reading = |value: f32| ElectricalSensor {
+            name: name.clone(),
+            value,
+            min: None,
+            max: None,
+            critical: None,
+        };
+
+        match rest.split_whitespace().last().unwrap_or("") {
+            "rpm" => sensors.fans.push(FanSensor {
+                name,
+                rpm: value,
+                min_rpm: None,
+                max_rpm: None,
+                pwm_percent: None,
+            }),
+            "mW" => sensors.power.push(reading(value / 1000.0)),
+            "W" => sensors.power.push(reading(value)),
+            "mV" => sensors.voltages.push(reading(value / 1000.0)),
+            "V" => sensors.voltages.push(reading(value)),
+            "mA" => sensors.currents.push(reading(value / 1000.0)),
+            "A" => sensors.currents.push(reading(value)),
+            _ => {}
+        }
+    }
+
+    sensors
+}
+
+/// 解析 cpu_power sampler 的功耗行，例如 `CPU Power: 187 mW`、
+/// `Combined Power (CPU + GPU + ANE): 198 mW`，Intel 机型为
+/// `Intel energy model derived package power (CPUs+GT+SA): 3.52W`；没有任何功耗行时返回 None
+pub fn parse_powermetrics_power(output: &str) -> Option<Power> {
+    let mut power = Power::default();
+    let mut found = false;
+
+    for line in output.lines() {
+        let Some((name, rest)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let rest = rest.trim();
+        let Some(value) = extract_first_float(rest) else {
+            continue;
+        };
+        let watts = if rest.ends_with("mW") {
+            value / 1000.0
+        } else if rest.ends_with('W') {
+            value
+        } else {
+            continue;
+        };
+
+        let name = name.trim();
+        let lower = name.to_lowercase();
+        let slot = match lower.as_str() {
+            "cpu power" => &mut power.cpu,
+            "gpu power" => &mut power.gpu,
+            "ane power" => &mut power.ane,
+            l if l.starts_with("combined power") => &mut power.system,
+            l if l.contains("package power") => &mut power.package,
+            l if l.ends_with(" power") => {
+                power.others.push((name.to_string(), watts));
+                found = true;
+                continue;
+            }
+            _ => continue,
+        };
+        *slot = Some(watts);
+        found = true;
+    }
+
+    found.then_some(power)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::io;
+    use std::path::Path;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::command::{fixture_name, macos_fixtures, CommandOutput, ReplayRunner};
+
+    /// 记录执行次数的回放
+    struct CountingRunner {
+        inner: ReplayRunner,
+        calls: AtomicUsize,
+    }
+
+    impl CommandRunner for CountingRunner {
+        fn run(&self, program: &str, args: &[&str]) -> io::Result<CommandOutput> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            self.inner.run(program, args)
+        }
+    }
+
+    #[test]
+    fn ioreg_platform_properties() {
+        let out = macos_fixtures()
+            .stdout("ioreg", &["-rd1", "-c", "IOPlatformExpertDevice"])
+            .unwrap();
+        // "key" = "value"
+        assert_eq!(
+            parse_ioreg_property(&out, "IOPlatformSerialNumber").as_deref(),
+            Some("C02XK1ABCDEF")
+        );
+        assert_eq!(
+            parse_ioreg_property(&out, "IOPlatformUUID").as_deref(),
+            Some("6F1C2B9E-3A41-5D8E-9B7A-0C4E2F1A7D33")
+        );
+        // "key" = <"value">
+        assert_eq!(
+            parse_ioreg_property(&out, "model").as_deref(),
+            Some("MacBookPro17,1")
+        );
+        assert_eq!(
+            parse_ioreg_property(&out, "manufacturer").as_deref(),
+            Some("Apple Inc.")
+        );
+        assert_eq!(parse_ioreg_property(&out, "missing-key"), None);
+    }
+
+    #[test]
+    fn boot_time() {
+        let out = macos_fixtures()
+            .stdout("sysctl", &["-n", "kern.boottime"])
+            .unwrap();
+        assert_eq!(
+            parse_boot_time(&out).as_deref(),
+            Some("2025-09-30T01:12:44Z")
+        );
+        assert_eq!(parse_boot_time("kern.boottime: unavailable"), None);
+    }
+
+    #[test]
+    fn hardware_data() {
+        let out = macos_fixtures()
+            .stdout("system_profiler", &["-json", "SPHardwareDataType"])
+            .unwrap();
+        let hw = parse_hardware_data(&out).unwrap();
+        assert_eq!(hw.model_name.as_deref(), Some("MacBook Pro"));
+        assert_eq!(hw.model_identifier.as_deref(), Some("MacBookPro17,1"));
+        assert_eq!(hw.model_number.as_deref(), Some("MYD82CH/A"));
+        assert_eq!(hw.chip.as_deref(), Some("Apple M1"));
+        assert_eq!(hw.total_number_of_cores.as_deref(), Some("proc 8:4:4"));
+        assert_eq!(hw.memory.as_deref(), Some("16 GB"));
+        assert_eq!(hw.system_firmware_version.as_deref(), Some("11881.1.1"));
+        assert_eq!(hw.os_loader_version.as_deref(), Some("11881.1.1"));
+        assert_eq!(hw.serial_number_system.as_deref(), Some("C02XK1ABCDEF"));
+        assert_eq!(
+            hw.hardware_uuid.as_deref(),
+            Some("6F1C2B9E-3A41-5D8E-9B7A-0C4E2F1A7D33")
+        );
+        assert_eq!(
+            hw.provisioning_udid.as_deref(),
+            Some("00008103-001A2B3C4D5E601E")
+        );
+        assert_eq!(
+            hw.activation_lock_status.as_deref(),
+            Some("activation_lock_disabled")
+        );
+
+        assert!(parse_hardware_data("{}").is_err());
+        assert!(parse_hardware_data("not json").is_err());
+    }
+
+    #[test]
+    fn powermetrics_sensors() {
+        let out = run_powermetrics(&macos_fixtures()).unwrap();
+        let sensors = parse_powermetrics_sensors(&out);
+        assert_eq!(sensors.fans.len(), 1);
+        assert_eq!(sensors.fans[0].name, "Fan");
+        assert_eq!(sensors.fans[0].rpm, 1212.53);
+        // 温度与频率行不是电气量，不应被收进来
+        assert!(sensors.voltages.is_empty());
+        assert!(sensors.currents.is_empty());
+        let power: Vec<(&str, f32)> = sensors
+            .power
+            .iter()
+            .map(|p| (p.name.as_str(), p.value))
+            .collect();
+        assert_eq!(
+            power,
+            [
+                ("ANE Power", 0.0),
+                ("CPU Power", 0.187),
+                ("GPU Power", 0.011),
+                ("Combined Power (CPU + GPU + ANE)", 0.198)
+            ]
+        );
+    }
+
+    #[test]
+    fn powermetrics_temps_and_power() {
+        let out = run_powermetrics(&macos_fixtures()).unwrap();
+
+        let temps = parse_powermetrics_temps(&out);
+        assert_eq!(temps.cpu, Some(48.53));
+        assert_eq!(temps.gpu, Some(41.2));
+        assert_eq!(temps.others, [("Battery".to_string(), 30.51)]);
+        assert_eq!(temp_sensor_name("NAND Temperature: 40 C"), "NAND");
+        assert_eq!(temp_sensor_name("temperature: 40 C"), "temperature");
+        assert_eq!(
+            temp_sensor_name("Ambient temp sensor"),
+            "Ambient temp sensor"
+        );
+
+        let power = parse_powermetrics_power(&out).unwrap();
+        assert_eq!(power.cpu, Some(0.187));
+        assert_eq!(power.gpu, Some(0.011));
+        assert_eq!(power.ane, Some(0.0));
+        assert_eq!(power.system, Some(0.198));
+        assert_eq!(power.package, None);
+
+        // Intel 机型只有 package 功耗
+        let intel = parse_powermetrics_power(
+            "Intel energy model derived package power (CPUs+GT+SA): 3.52W\n",
+        )
+        .unwrap();
+        assert_eq!(intel.package, Some(3.52));
+        assert!(parse_powermetrics_power("CPU die temperature: 48.53 C").is_none());
+    }
+
+    #[test]
+    fn powermetrics_result_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let stdout = dir
+            .path()
+            .join(fixture_name("powermetrics", POWERMETRICS_ARGS));
+        let stderr = stdout.with_extension("stderr");
+        fs::write(&stdout, "").unwrap();
+        fs::write(&stderr, "powermetrics must be invoked as the superuser\n").unwrap();
+
+        let runner = Arc::new(CountingRunner {
+            inner: ReplayRunner::new(dir.path()),
+            calls: AtomicUsize::new(0),
+        });
+        let platform = MacosPlatform::new(runner.clone());
+        let calls = || runner.calls.load(Ordering::SeqCst);
+
+        // 同一轮里温度、功耗、传感器共用一次失败结果
+        assert!(platform.temps().is_none());
+        assert!(platform.power().is_none());
+        let err = platform.sensors().unwrap_err();
+        assert!(err.to_string().contains("superuser"));
+        assert_eq!(calls(), 1);
+
+        // 失败要等 POWERMETRICS_RETRY 才重试
+        let t0 = Instant::now();
+        assert!(platform
+            .powermetrics_at(t0 + Duration::from_secs(5))
+            .is_err());
+        assert_eq!(calls(), 1);
+        assert!(platform.powermetrics_at(t0 + POWERMETRICS_RETRY).is_err());
+        assert_eq!(calls(), 2);
+
+        // 拿到权限之后，成功的输出只复用 POWERMETRICS_REUSE
+        fs::remove_file(&stderr).unwrap();
+        let recorded = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures/commands/macos")
+            .join(fixture_name("powermetrics", POWERMETRICS_ARGS));
+        fs::copy(recorded, &stdout).unwrap();
+        let t1 = t0 + POWERMETRICS_RETRY * 2;
+        let out = platform.powermetrics_at(t1).unwrap();
+        assert!(out.contains("CPU die temperature"));
+        assert_eq!(calls(), 3);
+        assert!(platform
+            .powermetrics_at(t1 + Duration::from_millis(100))
+            .is_ok());
+        assert_eq!(calls(), 3);
+        assert!(platform.powermetrics_at(t1 + POWERMETRICS_REUSE).is_ok());
+        assert_eq!(calls(), 4);
+    }
+}
@@ -0,0 +1,602 @@
+//! macOS 网络信息：ifconfig / airport / route / scutil / netstat
+
+use std::collections::HashMap;
+use std::net::IpAddr;
+
+use anyhow::Context;
+use regex::Regex;
+
+use crate::command::CommandRunner;
+use crate::network::{self, NetCounters};
+use crate::{InterfaceInfo, IpAddress, WifiInfo};
+
+// ---------- 新实现：用 ifconfig -a 解析接口 ----------
+pub fn gather_interfaces_via_ifconfig(
+    runner: &dyn CommandRunner,
+) -> anyhow::Result<Vec<InterfaceInfo>> {
+    // run `ifconfig -a` and parse blocks per interface
+    let out = runner
+        .run("ifconfig", &["-a"])
+        .context("running ifconfig -a")?;
+    if !out.success {
+        return Err(anyhow::anyhow!("ifconfig failed"));
+    }
+    let mut interfaces = parse_ifconfig(&out.stdout);
+
+    // en* 既可能是有线也可能是 Wi-Fi，只有 networksetup 的硬件端口名能区分
+    if let Some(ports) = runner.stdout("networksetup", &["-listallhardwareports"]) {
+        let ports = parse_hardware_ports(&ports);
+        for iface in &mut interfaces {
+            if let Some(kind) = ports.get(&iface.name).and_then(|p| kind_from_port(p)) {
+                iface.kind = Some(kind.to_string());
+            }
+        }
+    }
+    Ok(interfaces)
+}
+
+pub fn parse_ifconfig(txt: &str) -> Vec<InterfaceInfo> {
+    // Split into interface blocks. On macOS, interface header looks like:
+    // en0: flags=... mtu 1500
+    //     inet 192.168.1.10 netmask 0xffffff00 broadcast 192.168.1.255
+    //     inet6 ...
+    //     ether aa:bb:cc:dd:ee:ff
+    //
+    let header_re = Regex::new(r"(?m)^([0-9A-Za-z._-]+):\s+flags=.*?mtu\s+(\d+)").unwrap();
+    let ether_re = Regex::new(r"(?m)^\s+ether\s+([0-9a-fA-F:]{17})").unwrap();
+    let status_active_re = Regex::new(r"(?mi)status:\s*active").unwrap();
+    let up_flag_re = Regex::new(r"(?m)flags=[0-9]+<([^>]+)>").unwrap();
+
+    // We'll iterate through header matches, take substring from header.start to next header.start
+    let mut interfaces = Vec::new();
+    let mut positions: Vec<(usize, String, u32)> = Vec::new(); // (start_idx,name,mtu)
+
+    for cap in header_re.captures_iter(txt) {
+        if let (Some(m0), Some(m1)) = (cap.get(0), cap.get(1)) {
+            let start = m0.start();
+            let name = m1.as_str().to_string();
+            let mtu: u32 = cap
+                .get(2)
+                .and_then(|m| m.as_str().parse::<u32>().ok())
+                .unwrap_or(0);
+            positions.push((start, name, mtu));
+        }
+    }
+
+    // sort by start just in case
+    positions.sort_by_key(|p| p.0);
+
+    for (i, (start, name, mtu_val)) in positions.iter().enumerate() {
+        let end = if i + 1 < positions.len() {
+            positions[i + 1].0
+        } else {
+            txt.len()
+        };
+        let block = &txt[*start..end];
+
+        let addresses = parse_addresses(block);
+        let ips = addresses.iter().map(|a| a.address.to_string()).collect();
+
+        // mac
+        let mac = ether_re
+            .captures(block)
+            .and_then(|c| c.get(1).map(|m| m.as_str().to_ascii_lowercase()));
+
+        // is_up: check status: active OR flags contain "UP" or "RUNNING"
+        let is_up = status_active_re.is_match(block)
+            || up_flag_re
+                .captures(block)
+                .and_then(|c| c.get(1).map(|m| m.as_str().to_string()))
+                .map(|s| {
+                    s.split(',')
+                        .any(|f| f.eq_ignore_ascii_case("UP") || f.eq_ignore_ascii_case("RUNNING"))
+                })
+                .unwrap_or(false);
+
+        // is_loopback heuristics: name starts with lo or block contains "LOOPBACK"
+        let is_loopback = name.starts_with("lo") || block.to_lowercase().contains("loopback");
+
+        let mtu = if *mtu_val > 0 { Some(*mtu_val) } else { None };
+
+        interfaces.push(InterfaceInfo {
+            name: name.clone(),
+            mac,
+            ips,
+            addresses,
+            kind: kind_from_name(name, block).map(str::to_string),
+            is_up,
+            is_loopback,
+            mtu,
+        });
+    }
+
+    // sort for determinism
+    interfaces.sort_by(|a, b| a.name.cmp(&b.name));
+    interfaces
+}
+
+/// 解析一个接口块里的 `inet` / `inet6` 行：
+/// `inet 192.168.1.23 netmask 0xffffff00 broadcast 192.168.1.255`、
+/// `inet 10.8.0.6 --> 10.8.0.5 netmask 0xffffffff`、
+/// `inet6 fe80::1%en0 prefixlen 64 secured scopeid 0xb`、`inet6 ... prefixlen 64 autoconf temporary`
+pub fn parse_addresses(block: &str) -> Vec<IpAddress> {
+    let mut addresses: Vec<IpAddress> = block
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            let family = words.next()?;
+            if family != "inet" && family != "inet6" {
+                return None;
+            }
+            // 链路本地地址带 `%接口名` 区域，地址本身不含区域
+            let address: IpAddr = words.next()?.split('%').next()?.parse().ok()?;
+            let rest: Vec<&str> = words.collect();
+            let value_of = |key: &str| {
+                rest.iter()
+                    .position(|w| *w == key)
+                    .and_then(|i| rest.get(i + 1))
+                    .copied()
+            };
+            let prefix_len = match address {
+                IpAddr::V4(_) => value_of("netmask")
+                    .and_then(|m| u32::from_str_radix(m.trim_start_matches("0x"), 16).ok())
+                    .map(|m| m.count_ones() as u8),
+                IpAddr::V6(_) => value_of("prefixlen").and_then(|p| p.parse().ok()),
+            };
+            let mut a = IpAddress::new(address, prefix_len);
+            a.broadcast = value_of("broadcast").and_then(|b| b.parse().ok());
+            a.peer = value_of("-->").and_then(|p| p.parse().ok());
+            a.temporary = rest.contains(&"temporary");
+            a.deprecated = rest.contains(&"deprecated");
+            Some(a)
+        })
+        .collect();
+    network::mark_secondary(&mut addresses);
+    addresses
+}
+
+/// 只凭接口名与 ifconfig 块判断类型；en* 默认当作有线，再由硬件端口修正
+fn kind_from_name(name: &str, block: &str) -> Option<&'static str> {
+    const TUNNEL: &[&str] = &["utun", "ipsec", "gif", "stf", "ppp", "tun", "tap", "wg"];
+    const VIRTUAL: &[&str] = &[
+        "awdl", "llw", "anpi", "ap", "vmenet", "vnic", "feth", "vlan",
+    ];
+    let kind = if name.starts_with("lo") {
+        "loopback"
+    } else if name.starts_with("bridge") || block.contains("\tmember: ") {
+        "bridge"
+    } else if TUNNEL.iter().any(|p| name.starts_with(p)) {
+        "tunnel"
+    } else if VIRTUAL.iter().any(|p| name.starts_with(p)) {
+        "virtual"
+    } else if name.starts_with("en") {
+        "ethernet"
+    } else {
+        return None;
+    };
+    Some(kind)
+}
+
+/// 硬件端口名到类型："Wi-Fi"、"Ethernet"、"USB 10/100/1000 LAN"、"Thunderbolt Bridge"、"Thunderbolt 1"
+fn kind_from_port(port: &str) -> Option<&'static str> {
+    if port == "Wi-Fi" || port == "AirPort" {
+        Some("wifi")
+    } else if port.contains("Bridge") {
+        Some("bridge")
+    } else if port.contains("Ethernet") || port.contains("LAN") || port.starts_with("Thunderbolt") {
+        Some("ethernet")
+    } else {
+        None
+    }
+}
+
+/// `networksetup -listallhardwareports`：每段 `Hardware Port: <名称>` 后跟 `Device: <接口名>`
+pub fn parse_hardware_ports(s: &str) -> HashMap<String, String> {
+    let mut ports = HashMap::new();
+    let mut port = None;
+    for line in s.lines() {
+        if let Some(name) = line.strip_prefix("Hardware Port: ") {
+            port = Some(name.trim().to_string());
+        } else if let Some(device) = line.strip_prefix("Device: ") {
+            if let Some(port) = port.take() {
+                ports.insert(device.trim().to_string(), port);
+            }
+        }
+    }
+    ports
+}
+
+// ---------- wifi (airport -I) ----------
+pub fn get_wifi_info(runner: &dyn CommandRunner) -> anyhow::Result<Option<WifiInfo>> {
+    let airport_path =
+        "/System/Library/PrivateFrameworks/Apple80211.framework/Versions/Current/Resources/airport";
+
+    // 新系统已移除 airport，命令不存在时视为无 Wi-Fi 信息
+    let out = match runner.run(airport_path, &["-I"]) {
+        Ok(out) => out,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e).context("running airport"),
+    };
+    if !out.success {
+        return Ok(None);
+    }
+    Ok(Some(parse_airport(&out.stdout)))
+}
+
+/// 解析 `airport -I` 输出
+pub fn parse_airport(s: &str) -> WifiInfo {
+    let ssid = Regex::new(r"(?m)^\s*SSID:\s*(.+)$")
+        .unwrap()
+        .captures_iter(s)
+        .next()
+        .and_then(|c| c.get(1).map(|m| m.as_str().trim().to_string()));
+    let bssid = Regex::new(r"(?m)^\s*BSSID:\s*([0-9a-fA-F:]{17})")
+        .unwrap()
+        .captures_iter(s)
+        .next()
+        .and_then(|c| c.get(1).map(|m| m.as_str().to_string()));
+    let signal = Regex::new(r"(?m)^\s*agrCtlRSSI:\s*(-?\d+)")
+        .unwrap()
+        .captures_iter(s)
+        .next()
+        .and_then(|c| c.get(1).and_then(|m| m.as_str().parse::<i32>().ok()));
+    let iface = Regex::new(r"(?m)^\s*interface:\s*(\w+)")
+        .unwrap()
+        .captures_iter(s)
+        .next()
+        .and_then(|c| c.get(1).map(|m| m.as_str().to_string()));
+
+    WifiInfo {
+        ssid,
+        bssid,
+        signal_dbm: signal,
+        frequency_mhz: None,
+        iface,
+    }
+}
+
+// ---------- default gateway ----------
+pub fn get_default_gateway(runner: &dyn CommandRunner) -> anyhow::Result<Option<String>> {
+    let out = runner
+        .run("route", &["-n", "get", "default"])
+        .context("route get default")?;
+    if !out.success {
+        return Ok(None);
+    }
+    Ok(parse_route_gateway(&out.stdout))
+}
+
+/// 从 `route -n get default` 输出中取出 IPv4 网关
+pub fn parse_route_gateway(s: &str) -> Option<String> {
+    let re = Regex::new(r"gateway:\s*([0-9]+\.[0-9]+\.[0-9]+\.[0-9]+)").unwrap();
+    re.captures(s)
+        .map(|cap| cap.get(1).unwrap().as_str().to_string())
+}
+
+// ---------- dns servers ----------
+pub fn get_dns_servers(runner: &dyn CommandRunner) -> Option<Vec<String>> {
+    let out = runner.run("scutil", &["--dns"]).ok()?;
+    if !out.success {
+        return None;
+    }
+    Some(parse_scutil_dns(&out.stdout))
+}
+
+/// 从 `scutil --dns` 输出中收集去重后的 IPv4 nameserver
+pub fn parse_scutil_dns(s: &str) -> Vec<String> {
+    let re = Regex::new(r"nameserver\[[0-9]+\]\s*:\s*([0-9]+\.[0-9]+\.[0-9]+\.[0-9]+)").unwrap();
+    let mut v = Vec::new();
+    for cap in re.captures_iter(s) {
+        v.push(cap.get(1).unwrap().as_str().to_string());
+    }
+    v.sort();
+    v.dedup();
+    v
+}
+
+// ---------- traffic counters (netstat -ibdn) ----------
+pub fn get_net_counters(runner: &dyn CommandRunner) -> anyhow::Result<Vec<NetCounters>> {
+    let out = runner
+        .run("netstat", &["-ibdn"])
+        .context("running netstat -ibdn")?;
+    if !out.success {
+        return Err(anyhow::anyhow!("netstat failed: {}", out.stderr));
+    }
+    Ok(parse_netstat(&out.stdout))
+}
+
+/// 解析 `netstat -ibdn`：每个接口有一行 `<Link#N>`（链路层计数）和若干地址行，只取前者。
+/// 回环等没有 MAC 的接口缺少 Address 列，所以计数从行尾往前取：
+/// `Ipkts Ierrs Ibytes Opkts Oerrs Obytes Coll Drop`
+pub fn parse_netstat(s: &str) -> Vec<NetCounters> {
+    let mut counters: Vec<NetCounters> = s
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let f: Vec<&str> = line.split_whitespace().collect();
+            if f.len() < 11 || !f[2].starts_with("<Link#") {
+                return None;
+            }
+            let n: Vec<u64> = f[f.len() - 8..]
+                .iter()
+                .map(|v| v.parse().ok())
+                .collect::<Option<_>>()?;
+            Some(NetCounters {
+                // 名字带 `*` 表示接口已关闭
+                interface: f[0].trim_end_matches('*').to_string(),
+                rx_packets: n[0],
+                rx_errors: n[1],
+                rx_bytes: n[2],
+                tx_packets: n[3],
+                tx_errors: n[4],
+                tx_bytes: n[5],
+                rx_dropped: None,
+                tx_dropped: Some(n[7]),
+                multicast: None,
+            })
+        })
+        .collect();
+    counters.sort_by(|a, b| a.interface.cmp(&b.interface));
+    counters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::macos_fixtures;
+
+    #[test]
+    fn ifconfig_interfaces() {
+        let out = macos_fixtures().stdout("ifconfig", &["-a"]).unwrap();
+        let interfaces = parse_ifconfig(&out);
+        let names: Vec<&str> = interfaces.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["anpi0", "awdl0", "bridge0", "en0", "gif0", "lo0", "stf0", "utun0", "utun3"]
+        );
+
+        let en0 = interfaces.iter().find(|i| i.name == "en0").unwrap();
+        assert_eq!(en0.mac.as_deref(), Some("a4:83:e7:12:34:56"));
+        assert_eq!(en0.mtu, Some(1500));
+        assert!(en0.is_up);
+        assert!(!en0.is_loopback);
+        assert!(en0.ips.contains(&"192.168.1.23".to_string()));
+        assert!(en0.ips.contains(&"fe80::1c2a:3bff:fe4d:5e6f".to_string()));
+
+        let lo0 = interfaces.iter().find(|i| i.name == "lo0").unwrap();
+        assert!(lo0.is_loopback);
+        assert_eq!(lo0.mac, None);
+        assert_eq!(lo0.mtu, Some(16384));
+        assert_eq!(lo0.ips, ["127.0.0.1", "::1", "fe80::1"]);
+
+        // flags=8010<POINTOPOINT,MULTICAST>：既没有 UP 也没有 status: active
+        let gif0 = interfaces.iter().find(|i| i.name == "gif0").unwrap();
+        assert!(!gif0.is_up);
+        assert!(gif0.ips.is_empty());
+    }
+
+    #[test]
+    fn airport() {
+        let out = macos_fixtures()
+            .stdout(
+                "/System/Library/PrivateFrameworks/Apple80211.framework/Versions/Current/Resources/airport",
+                &["-I"],
+            )
+            .unwrap();
+        let wifi = parse_airport(&out);
+        assert_eq!(wifi.ssid.as_deref(), Some("office-5G"));
+        assert_eq!(wifi.bssid.as_deref(), Some("70:3a:0e:aa:bb:cc"));
+        assert_eq!(wifi.signal_dbm, Some(-54));
+        assert_eq!(wifi.iface, None);
+    }
+
+    #[test]
+    fn airport_missing_is_no_wifi() {
+        // 录制目录里没有对应文件时与命令不存在一样，视为没有 Wi-Fi 信息
+        let dir = tempfile::tempdir().unwrap();
+        let runner = crate::command::ReplayRunner::new(dir.path());
+        assert!(get_wifi_info(&runner).unwrap().is_none());
+    }
+
+    #[test]
+    fn route_gateway() {
+        let out = macos_fixtures()
+            .stdout("route", &["-n", "get", "default"])
+            .unwrap();
+        assert_eq!(parse_route_gateway(&out).as_deref(), Some("192.168.1.1"));
+        assert_eq!(
+            parse_route_gateway("route: writing to routing socket: not in table"),
+            None
+        );
+    }
+
+    #[test]
+    fn scutil_dns() {
+        let out = macos_fixtures().stdout("scutil", &["--dns"]).unwrap();
+        // 多个 resolver 重复的地址只保留一次
+        assert_eq!(parse_scutil_dns(&out), ["192.168.1.1", "223.5.5.5"]);
+        assert!(parse_scutil_dns("No DNS configuration available").is_empty());
+    }
+
+    #[test]
+    fn netstat_counters() {
+        let counters = get_net_counters(&macos_fixtures()).unwrap();
+        // 只取 <Link#N> 行，按名字排序，关闭的接口去掉 `*`
+        let names: Vec<&str> = counters.iter().map(|c| c.interface.as_str()).collect();
+        assert_eq!(
+            names,
+            ["anpi0", "awdl0", "en0", "en3", "gif0", "lo0", "stf0", "utun0"]
+        );
+
+        let en0 = counters.iter().find(|c| c.interface == "en0").unwrap();
+        assert_eq!(en0.rx_packets, 14829337);
+        assert_eq!(en0.rx_errors, 0);
+        assert_eq!(en0.rx_bytes, 18734501928);
+        assert_eq!(en0.tx_packets, 6021943);
+        assert_eq!(en0.tx_bytes, 1029384756);
+        assert_eq!(en0.tx_dropped, Some(17));
+        assert_eq!(en0.rx_dropped, None);
+        assert_eq!(en0.multicast, None);
+
+        // 没有 MAC 的接口缺少 Address 列
+        let utun0 = counters.iter().find(|c| c.interface == "utun0").unwrap();
+        assert_eq!(utun0.rx_packets, 0);
+        assert_eq!(utun0.tx_packets, 12);
+        assert_eq!(utun0.tx_bytes, 1304);
+        let lo0 = counters.iter().find(|c| c.interface == "lo0").unwrap();
+        assert_eq!(lo0.rx_bytes, 612389120);
+
+        assert!(parse_netstat("Name Mtu Network Address Ipkts\n").is_empty());
+    }
+
+    #[test]
+    fn interface_kinds_from_hardware_ports() {
+        let interfaces = gather_interfaces_via_ifconfig(&macos_fixtures()).unwrap();
+        let kinds: Vec<(&str, Option<&str>)> = interfaces
+            .iter()
+            .map(|i| (i.name.as_str(), i.kind.as_deref()))
+            .collect();
+        // en0 的硬件端口是 Wi-Fi，bridge0 是雷雳网桥
+        assert_eq!(
+            kinds,
+            [
+                ("anpi0", Some("virtual")),
+                ("awdl0", Some("virtual")),
+                ("bridge0", Some("bridge")),
+                ("en0", Some("wifi")),
+                ("gif0", Some("tunnel")),
+                ("lo0", Some("loopback")),
+                ("stf0", Some("tunnel")),
+                ("utun0", Some("tunnel")),
+                ("utun3", Some("tunnel")),
+            ]
+        );
+
+        // 没有 networksetup 时 en* 按有线处理
+        let en0 = parse_ifconfig(&macos_fixtures().stdout("ifconfig", &["-a"]).unwrap())
+            .into_iter()
+            .find(|i| i.name == "en0")
+            .unwrap();
+        assert_eq!(en0.kind.as_deref(), Some("ethernet"));
+    }
+
+    #[test]
+    fn hardware_ports() {
+        let out = macos_fixtures()
+            .stdout("networksetup", &["-listallhardwareports"])
+            .unwrap();
+        let ports = parse_hardware_ports(&out);
+        assert_eq!(ports.len(), 4);
+        assert_eq!(ports["en0"], "Wi-Fi");
+        assert_eq!(ports["en1"], "Thunderbolt 1");
+        assert_eq!(ports["en3"], "Ethernet Adapter (en3)");
+        assert_eq!(ports["bridge0"], "Thunderbolt Bridge");
+
+        assert_eq!(kind_from_port("Wi-Fi"), Some("wifi"));
+        assert_eq!(kind_from_port("Thunderbolt Bridge"), Some("bridge"));
+        assert_eq!(kind_from_port("Thunderbolt 1"), Some("ethernet"));
+        assert_eq!(kind_from_port("USB 10/100/1000 LAN"), Some("ethernet"));
+        assert_eq!(kind_from_port("iPhone USB"), None);
+    }
+
+    /// (地址, 作用域, 前缀长度, temporary, deprecated, secondary)
+    type AddressSummary<'a> = (String, &'a str, Option<u8>, bool, bool, bool);
+
+    #[test]
+    fn en0_addresses() {
+        let out = macos_fixtures().stdout("ifconfig", &["-a"]).unwrap();
+        let en0 = parse_ifconfig(&out)
+            .into_iter()
+            .find(|i| i.name == "en0")
+            .unwrap();
+        let summary: Vec<AddressSummary> = en0
+            .addresses
+            .iter()
+            .map(|a| {
+                (
+                    a.address.to_string(),
+                    a.scope.as_str(),
+                    a.prefix_len,
+                    a.temporary,
+                    a.deprecated,
+                    a.secondary,
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (
+                    "fe80::1c2a:3bff:fe4d:5e6f".to_string(),
+                    "link-local",
+                    Some(64),
+                    false,
+                    false,
+                    false
+                ),
+                (
+                    "192.168.1.23".to_string(),
+                    "global",
+                    Some(24),
+                    false,
+                    false,
+                    false
+                ),
+                (
+                    "240e:3b7:3272:d8d0:14a8:6c2e:9d1f:4b27".to_string(),
+                    "global",
+                    Some(64),
+                    false,
+                    false,
+                    false
+                ),
+                (
+                    "240e:3b7:3272:d8d0:58e1:2f0a:c3b4:11d6".to_string(),
+                    "global",
+                    Some(64),
+                    true,
+                    false,
+                    false
+                ),
+                (
+                    "240e:3b7:3272:d8d0:9c07:5be1:e2a4:7f10".to_string(),
+                    "global",
+                    Some(64),
+                    true,
+                    true,
+                    false
+                ),
+                (
+                    "192.168.1.24".to_string(),
+                    "global",
+                    Some(24),
+                    false,
+                    false,
+                    true
+                ),
+            ]
+        );
+        assert_eq!(
+            en0.addresses[1].broadcast,
+            Some("192.168.1.255".parse().unwrap())
+        );
+    }
+
+    #[test]
+    fn point_to_point_address() {
+        let addresses = parse_addresses(
+            "utun3: flags=8051<UP,POINTOPOINT,RUNNING,MULTICAST> mtu 1400\n\
+             \tinet 10.8.0.6 --> 10.8.0.5 netmask 0xffffffff\n\
+             \tinet6 fe80::1%utun3 prefixlen 64 scopeid 0x10\n\
+             \tinet bogus netmask 0xffffff00\n",
+        );
+        assert_eq!(addresses.len(), 2);
+        assert_eq!(addresses[0].address.to_string(), "10.8.0.6");
+        assert_eq!(addresses[0].peer, Some("10.8.0.5".parse().unwrap()));
+        assert_eq!(addresses[0].prefix_len, Some(32));
+        assert_eq!(addresses[0].broadcast, None);
+        // 区域 `%utun3` 不属于地址本身
+        assert_eq!(addresses[1].address.to_string(), "fe80::1");
+        assert_eq!(addresses[1].family, "ipv6");
+    }
+}
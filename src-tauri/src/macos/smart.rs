@@ -0,0 +1,135 @@
+//! 磁盘健康：优先使用 `smartctl --json -a`（需自行安装 smartmontools），
+//! 没有时退回 `system_profiler -json SPNVMeDataType` 中的 `smart_status`。
+//!
+//! 从 Finder 启动的应用 PATH 里没有 Homebrew 目录，因此按常见安装位置逐个尝试。
+//! `smart_status` 只有 "Verified" / "Failing" 两种结论，没有任何计数器。
+
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+use super::storage;
+use crate::command::CommandRunner;
+use crate::smart;
+use crate::DiskHealth;
+
+const SMARTCTL_PATHS: &[&str] = &[
+    "smartctl",
+    "/opt/homebrew/bin/smartctl",
+    "/usr/local/bin/smartctl",
+];
+
+pub fn get_disk_health(runner: &dyn CommandRunner) -> anyhow::Result<Vec<DiskHealth>> {
+    let disks = storage::get_storage_devices(runner)?;
+    let statuses = runner
+        .stdout("system_profiler", &["-json", "SPNVMeDataType"])
+        .map(|json| parse_smart_status(&json))
+        .unwrap_or_default();
+
+    Ok(disks
+        .into_iter()
+        .map(|disk| {
+            let mut health = DiskHealth::new(&disk.device);
+            health.model = disk.model;
+            health.serial_number = disk.serial_number;
+
+            // smartctl 的退出码是位掩码，这里只看 JSON 内容
+            let smartctl = SMARTCTL_PATHS
+                .iter()
+                .find_map(|program| runner.run(program, &["--json", "-a", &disk.device]).ok());
+            let err = match smartctl {
+                Some(output) => match smart::parse_smartctl(&mut health, &output.stdout) {
+                    Ok(()) => return health,
+                    Err(e) => e.to_string(),
+                },
+                None => "smartctl not installed".to_string(),
+            };
+            match statuses.get(&disk.device).map(String::as_str) {
+                Some(status) => apply_smart_status(&mut health, status),
+                None => health.error = Some(err),
+            }
+            health
+        })
+        .collect())
+}
+
+/// 把 system_profiler 的 `smart_status` 换算成判定
+pub fn apply_smart_status(health: &mut DiskHealth, status: &str) {
+    health.verdict = match status {
+        "Verified" => "good",
+        "Failing" => "critical",
+        _ => "unknown",
+    }
+    .to_string();
+    if health.verdict == "critical" {
+        health.reasons = vec!["SMART status: Failing".to_string()];
+    }
+    health.source = Some("system_profiler".to_string());
+}
+
+/// bsd_name -> smart_status
+pub fn parse_smart_status(json: &str) -> HashMap<String, String> {
+    let Ok(v) = serde_json::from_str::<Value>(json) else {
+        return HashMap::new();
+    };
+    v.get("SPNVMeDataType")
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|controller| controller.get("_items")?.as_array())
+        .flatten()
+        .filter_map(|item| {
+            Some((
+                item.get("bsd_name")?.as_str()?.to_string(),
+                item.get("smart_status")?.as_str()?.to_string(),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::macos_fixtures;
+
+    #[test]
+    fn smartctl_from_fixtures() {
+        let disks = get_disk_health(&macos_fixtures()).unwrap();
+        let disk0 = disks.iter().find(|d| d.device == "disk0").unwrap();
+        assert_eq!(disk0.source.as_deref(), Some("smartctl"));
+        assert_eq!(disk0.model.as_deref(), Some("APPLE SSD AP0512Q"));
+        assert_eq!(disk0.protocol.as_deref(), Some("nvme"));
+        assert_eq!(disk0.verdict, "good");
+        assert_eq!(disk0.temperature_c, Some(31.0));
+        assert_eq!(disk0.percentage_used, Some(2));
+        assert_eq!(disk0.power_on_hours, Some(2874));
+        assert_eq!(disk0.error, None);
+    }
+
+    #[test]
+    fn system_profiler_status() {
+        let runner = macos_fixtures();
+        let json = runner
+            .stdout("system_profiler", &["-json", "SPNVMeDataType"])
+            .unwrap();
+        let statuses = parse_smart_status(&json);
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses["disk0"], "Verified");
+        assert!(parse_smart_status("not json").is_empty());
+
+        let mut health = DiskHealth::new("disk0");
+        apply_smart_status(&mut health, &statuses["disk0"]);
+        assert_eq!(health.verdict, "good");
+        assert!(health.reasons.is_empty());
+        assert_eq!(health.source.as_deref(), Some("system_profiler"));
+
+        let mut health = DiskHealth::new("disk2");
+        apply_smart_status(&mut health, "Failing");
+        assert_eq!(health.verdict, "critical");
+        assert_eq!(health.reasons, ["SMART status: Failing"]);
+
+        let mut health = DiskHealth::new("disk3");
+        apply_smart_status(&mut health, "Not Supported");
+        assert_eq!(health.verdict, "unknown");
+    }
+}
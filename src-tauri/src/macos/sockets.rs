@@ -0,0 +1,245 @@
+//! 套接字表：`lsof -nP -iTCP -iUDP -FpcPtnT`
+//!
+//! `-F` 输出每行一个字段，首字符为字段名：`p` 开始一个进程（pid），`c` 为进程名，
+//! `f` 开始该进程的一个文件，之后的 `t`（IPv4 / IPv6）、`P`（TCP / UDP）、
+//! `n`（`本地->远端` 地址）与 `TST=`（TCP 状态）都属于这个文件。
+//! 非 root 运行时只能看到当前用户进程的套接字。
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use anyhow::Context;
+
+use crate::command::CommandRunner;
+use crate::process::sockets::SocketInfo;
+
+pub fn get_sockets(runner: &dyn CommandRunner) -> anyhow::Result<Vec<SocketInfo>> {
+    let output = runner
+        .run("lsof", &["-nP", "-iTCP", "-iUDP", "-FpcPtnT"])
+        .context("failed to run lsof")?;
+    // lsof 无法 stat 某些文件时返回 1，但可读的套接字都已打印，照常解析；
+    // 没有任何匹配的套接字时同样返回 1，stdout 与 stderr 都为空
+    if !output.success && output.stdout.is_empty() && !output.stderr.trim().is_empty() {
+        return Err(anyhow::anyhow!("lsof failed: {}", output.stderr.trim()));
+    }
+    Ok(parse_lsof(&output.stdout))
+}
+
+pub fn parse_lsof(s: &str) -> Vec<SocketInfo> {
+    let mut sockets = Vec::new();
+    let mut pid = None;
+    let mut command = None;
+    let mut file: Option<FileFields> = None;
+
+    for line in s.lines() {
+        let Some(field) = line.chars().next() else {
+            continue;
+        };
+        let value = &line[field.len_utf8()..];
+        match field {
+            'p' => {
+                sockets.extend(file.take().and_then(|f| f.finish(pid, &command)));
+                pid = value.parse().ok();
+                command = None;
+            }
+            'c' => command = Some(value.to_string()),
+            'f' => {
+                sockets.extend(file.take().and_then(|f| f.finish(pid, &command)));
+                file = Some(FileFields::default());
+            }
+            't' | 'P' | 'n' | 'T' => {
+                if let Some(f) = file.as_mut() {
+                    match field {
+                        't' => f.family = Some(value.to_string()),
+                        'P' => f.protocol = Some(value.to_string()),
+                        'n' => f.name = Some(value.to_string()),
+                        _ => {
+                            if let Some(state) = value.strip_prefix("ST=") {
+                                f.state = Some(state.to_string());
+                            }
+                        }
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    sockets.extend(file.take().and_then(|f| f.finish(pid, &command)));
+    sockets
+}
+
+#[derive(Default)]
+struct FileFields {
+    family: Option<String>,
+    protocol: Option<String>,
+    name: Option<String>,
+    state: Option<String>,
+}
+
+impl FileFields {
+    fn finish(self, pid: Option<u32>, command: &Option<String>) -> Option<SocketInfo> {
+        let ipv6 = match self.family.as_deref()? {
+            "IPv4" => false,
+            "IPv6" => true,
+            _ => return None,
+        };
+        let protocol = self.protocol?.to_lowercase();
+        let name = self.name?;
+        let (local, remote) = match name.split_once("->") {
+            Some((l, r)) => (l, Some(r)),
+            None => (name.as_str(), None),
+        };
+        let (local_address, local_port) = parse_endpoint(local, ipv6)?;
+        let remote = remote.and_then(|r| parse_endpoint(r, ipv6));
+        let state = match (protocol.as_str(), self.state) {
+            ("tcp", Some(state)) => state,
+            (_, _) if remote.is_some() => "ESTABLISHED".to_string(),
+            _ => "UNCONN".to_string(),
+        };
+        Some(SocketInfo {
+            protocol,
+            family: if ipv6 { "ipv6" } else { "ipv4" }.to_string(),
+            local_address,
+            local_port,
+            remote_address: remote.map(|(a, _)| a),
+            remote_port: remote.map(|(_, p)| p),
+            state,
+            pid,
+            process_name: command.clone(),
+        })
+    }
+}
+
+/// `127.0.0.1:8080`、`*:5353`、`[::1]:8080`、`[fe80::1%lo0]:123`；端口为 `*` 时记为 0
+fn parse_endpoint(s: &str, ipv6: bool) -> Option<(IpAddr, u16)> {
+    let (addr, port) = s.rsplit_once(':')?;
+    let port = if port == "*" { 0 } else { port.parse().ok()? };
+    let addr = addr.trim_start_matches('[').trim_end_matches(']');
+    let addr = addr.split('%').next()?;
+    let addr = match addr {
+        "*" if ipv6 => IpAddr::V6(Ipv6Addr::UNSPECIFIED),
+        "*" => IpAddr::V4(Ipv4Addr::UNSPECIFIED),
+        _ => addr.parse().ok()?,
+    };
+    Some((addr, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::{fixture_name, macos_fixtures, ReplayRunner};
+
+    fn row(s: &SocketInfo) -> String {
+        let remote = match (s.remote_address, s.remote_port) {
+            (Some(a), Some(p)) => format!("{a}:{p}"),
+            _ => "*".to_string(),
+        };
+        format!(
+            "{} {} {}:{} {} {} {:?} {}",
+            s.protocol,
+            s.family,
+            s.local_address,
+            s.local_port,
+            remote,
+            s.state,
+            s.pid,
+            s.process_name.as_deref().unwrap_or("-"),
+        )
+    }
+
+    #[test]
+    fn lsof_sockets() {
+        let sockets = get_sockets(&macos_fixtures()).unwrap();
+        let rows: Vec<String> = sockets.iter().map(row).collect();
+        assert_eq!(
+            rows,
+            [
+                // `*:*`：未绑定端口的 UDP
+                "udp ipv4 0.0.0.0:0 * UNCONN Some(412) rapportd",
+                "tcp ipv4 0.0.0.0:7000 * LISTEN Some(498) ControlCenter",
+                "tcp ipv6 :::7000 * LISTEN Some(498) ControlCenter",
+                "udp ipv4 0.0.0.0:5353 * UNCONN Some(498) ControlCenter",
+                "tcp ipv4 127.0.0.1:8080 * LISTEN Some(1523) node",
+                "tcp ipv4 127.0.0.1:8080 127.0.0.1:53122 ESTABLISHED Some(1523) node",
+                "tcp ipv6 ::1:9229 * LISTEN Some(1523) node",
+                "tcp ipv4 192.168.1.23:53170 142.250.66.78:443 ESTABLISHED Some(2210) Google Chrome Helper",
+                // 已连接的 UDP 没有 TST= 字段
+                "udp ipv6 240e:3b7:3272:d8d0:58e1:2f0a:c3b4:11d6:61012 2404:6800:4005:81b::200e:443 ESTABLISHED Some(2210) Google Chrome Helper",
+                "tcp ipv4 192.168.1.23:53188 17.57.146.20:5223 CLOSE_WAIT Some(2210) Google Chrome Helper",
+                "tcp ipv4 127.0.0.1:53122 127.0.0.1:8080 ESTABLISHED Some(3001) curl",
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_incomplete_files() {
+        let sockets = parse_lsof(
+            "n127.0.0.1:80\n\
+             p77\n\
+             csomething\n\
+             f3\n\
+             tunix\n\
+             PTCP\n\
+             n/var/run/sock\n\
+             f4\n\
+             PTCP\n\
+             n127.0.0.1:80\n\
+             f5\n\
+             tIPv4\n\
+             PTCP\n\
+             nnot-an-address\n\
+             f6\n\
+             tIPv6\n\
+             PUDP\n\
+             n[fe80::1%lo0]:123\n",
+        );
+        assert_eq!(sockets.len(), 1);
+        assert_eq!(
+            row(&sockets[0]),
+            "udp ipv6 fe80::1:123 * UNCONN Some(77) something"
+        );
+        assert!(parse_lsof("").is_empty());
+    }
+
+    /// 在临时目录里录制一次 lsof 输出；`stderr` 为 Some 时表示非零退出
+    fn replay(stdout: &str, stderr: Option<&str>) -> (tempfile::TempDir, ReplayRunner) {
+        let tmp = tempfile::tempdir().unwrap();
+        let path = tmp
+            .path()
+            .join(fixture_name("lsof", &["-nP", "-iTCP", "-iUDP", "-FpcPtnT"]));
+        std::fs::write(&path, stdout).unwrap();
+        if let Some(stderr) = stderr {
+            std::fs::write(path.with_extension("stderr"), stderr).unwrap();
+        }
+        let runner = ReplayRunner::new(tmp.path());
+        (tmp, runner)
+    }
+
+    #[test]
+    fn partial_output_kept() {
+        let (_tmp, runner) = replay(
+            "p3001\nccurl\nf5\ntIPv4\nPTCP\nn127.0.0.1:53122->127.0.0.1:8080\nTST=ESTABLISHED\n",
+            Some("lsof: WARNING: can't stat() nfs file system /Volumes/share\n"),
+        );
+        let sockets = get_sockets(&runner).unwrap();
+        assert_eq!(sockets.len(), 1);
+        assert_eq!(sockets[0].pid, Some(3001));
+    }
+
+    #[test]
+    fn no_matches_is_empty() {
+        let (_tmp, runner) = replay("", Some(""));
+        assert!(get_sockets(&runner).unwrap().is_empty());
+    }
+
+    #[test]
+    fn failure_without_output() {
+        let (_tmp, runner) = replay("", Some("lsof: unsupported option: -T\n"));
+        let err = get_sockets(&runner).unwrap_err().to_string();
+        assert_eq!(err, "lsof failed: lsof: unsupported option: -T");
+
+        // 命令不存在
+        let tmp = tempfile::tempdir().unwrap();
+        let err = get_sockets(&ReplayRunner::new(tmp.path())).unwrap_err();
+        assert!(err.to_string().contains("failed to run lsof"));
+    }
+}
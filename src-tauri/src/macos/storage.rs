@@ -0,0 +1,272 @@
+//! 物理磁盘与分区：`diskutil info -all`，序列号来自 `system_profiler -json SPNVMeDataType`
+//!
+//! `diskutil info -all` 按 `**********` 分隔，每段是一个磁盘、分区或卷的 "Key: Value" 列表。
+//! APFS 容器被合成为一块虚拟整盘（如 disk3，"Virtual: Yes"），其中的卷带有
+//! "APFS Physical Store"，指向真正承载它的分区（如 disk0s2），据此把卷挂到分区下面。
+
+use std::collections::HashMap;
+
+use anyhow::Context;
+use serde_json::Value;
+
+use crate::command::CommandRunner;
+use crate::{Partition, PhysicalDisk, Volume};
+
+pub fn get_storage_devices(runner: &dyn CommandRunner) -> anyhow::Result<Vec<PhysicalDisk>> {
+    let output = runner
+        .run("diskutil", &["info", "-all"])
+        .context("failed to run diskutil")?;
+    if !output.success {
+        return Err(anyhow::anyhow!("diskutil failed: {}", output.stderr));
+    }
+    // 序列号只是补充信息，非 NVMe 机型上为空
+    let nvme = runner
+        .stdout("system_profiler", &["-json", "SPNVMeDataType"])
+        .unwrap_or_default();
+    Ok(parse_storage_devices(&output.stdout, &nvme))
+}
+
+pub fn parse_storage_devices(diskutil: &str, nvme_json: &str) -> Vec<PhysicalDisk> {
+    let entries: Vec<HashMap<&str, &str>> = diskutil
+        .split("**********")
+        .map(|block| {
+            block
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .map(|(k, v)| (k.trim(), v.trim()))
+                .collect::<HashMap<_, _>>()
+        })
+        .filter(|e| e.contains_key("Device Identifier"))
+        .collect();
+    let serials = parse_nvme_serials(nvme_json);
+
+    entries
+        .iter()
+        .filter(|e| flag(e, "Whole") && !flag(e, "Virtual"))
+        .map(|disk| {
+            let id = disk["Device Identifier"];
+            let mut partitions: Vec<Partition> = entries
+                .iter()
+                .filter(|e| !flag(e, "Whole") && e.get("Part of Whole") == Some(&id))
+                .map(|part| {
+                    let part_id = part["Device Identifier"];
+                    let volumes = entries
+                        .iter()
+                        .filter(|e| e.get("APFS Physical Store") == Some(&part_id))
+                        .map(|vol| Volume {
+                            name: volume_name(vol)
+                                .unwrap_or(vol["Device Identifier"])
+                                .to_string(),
+                            device: Some(vol["Device Identifier"].to_string()),
+                            kind: "apfs".to_string(),
+                            parent: vol.get("Part of Whole").unwrap_or(&part_id).to_string(),
+                            size_bytes: vol
+                                .get("Volume Used Space")
+                                .or_else(|| vol.get("Disk Size"))
+                                .and_then(|v| bytes(v)),
+                            file_system: file_system(vol),
+                            mount_points: mount_point(vol).into_iter().collect(),
+                        })
+                        .collect();
+                    Partition {
+                        device: part_id.to_string(),
+                        size_bytes: part.get("Disk Size").and_then(|v| bytes(v)).unwrap_or(0),
+                        label: volume_name(part).map(str::to_string),
+                        file_system: file_system(part),
+                        mount_points: mount_point(part).into_iter().collect(),
+                        volumes,
+                    }
+                })
+                .collect();
+            partitions.sort_by_key(|p| partition_number(&p.device));
+
+            PhysicalDisk {
+                device: id.to_string(),
+                model: disk.get("Device / Media Name").map(|s| s.to_string()),
+                serial_number: serials.get(id).cloned(),
+                size_bytes: disk.get("Disk Size").and_then(|v| bytes(v)).unwrap_or(0),
+                kind: disk
+                    .get("Solid State")
+                    .map(|s| if *s == "Yes" { "SSD" } else { "HDD" }.to_string()),
+                removable: disk
+                    .get("Removable Media")
+                    .is_some_and(|v| *v == "Removable"),
+                read_only: flag(disk, "Media Read-Only"),
+                partition_table: disk
+                    .get("Content (IOContent)")
+                    .and_then(|c| match *c {
+                        "GUID_partition_scheme" => Some("gpt"),
+                        "FDisk_partition_scheme" => Some("mbr"),
+                        "Apple_partition_scheme" => Some("apm"),
+                        _ => None,
+                    })
+                    .map(str::to_string),
+                partitions,
+            }
+        })
+        .collect()
+}
+
+fn flag(entry: &HashMap<&str, &str>, key: &str) -> bool {
+    entry.get(key) == Some(&"Yes")
+}
+
+/// 没有文件系统的分区显示为 "Not applicable (no file system)"
+fn volume_name<'a>(entry: &HashMap<&str, &'a str>) -> Option<&'a str> {
+    entry
+        .get("Volume Name")
+        .copied()
+        .filter(|v| !v.is_empty() && !v.starts_with("Not applicable"))
+}
+
+fn file_system(entry: &HashMap<&str, &str>) -> Option<String> {
+    entry.get("File System Personality").map(|s| s.to_string())
+}
+
+fn mount_point(entry: &HashMap<&str, &str>) -> Option<String> {
+    entry
+        .get("Mount Point")
+        .filter(|m| !m.is_empty() && !m.starts_with("Not applicable"))
+        .map(|s| s.to_string())
+}
+
+/// `500.3 GB (500277792768 Bytes) (exactly 977105064 512-Byte-Units)` 中括号里的字节数
+fn bytes(value: &str) -> Option<u64> {
+    let (_, rest) = value.split_once('(')?;
+    rest.split_whitespace().next()?.parse().ok()
+}
+
+/// disk0s10 排在 disk0s2 之后
+fn partition_number(device: &str) -> u32 {
+    device
+        .rsplit_once('s')
+        .and_then(|(_, n)| n.parse().ok())
+        .unwrap_or(0)
+}
+
+/// bsd_name -> device_serial
+fn parse_nvme_serials(json: &str) -> HashMap<String, String> {
+    let Ok(v) = serde_json::from_str::<Value>(json) else {
+        return HashMap::new();
+    };
+    v.get("SPNVMeDataType")
+        .and_then(|a| a.as_array())
+        .into_iter()
+        .flatten()
+        .filter_map(|controller| controller.get("_items")?.as_array())
+        .flatten()
+        .filter_map(|item| {
+            Some((
+                item.get("bsd_name")?.as_str()?.to_string(),
+                item.get("device_serial")?.as_str()?.trim().to_string(),
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command::macos_fixtures;
+
+    #[test]
+    fn diskutil_with_apfs_container() {
+        let disks = get_storage_devices(&macos_fixtures()).unwrap();
+        // 虚拟的 APFS 容器 disk3 不是物理磁盘
+        let ids: Vec<&str> = disks.iter().map(|d| d.device.as_str()).collect();
+        assert_eq!(ids, ["disk0", "disk4"]);
+
+        let disk0 = &disks[0];
+        assert_eq!(disk0.model.as_deref(), Some("APPLE SSD AP0512Q"));
+        assert_eq!(disk0.serial_number.as_deref(), Some("0ba01e4a12c83f27"));
+        assert_eq!(disk0.size_bytes, 500_277_792_768);
+        assert_eq!(disk0.kind.as_deref(), Some("SSD"));
+        assert!(!disk0.removable);
+        assert!(!disk0.read_only);
+        assert_eq!(disk0.partition_table.as_deref(), Some("gpt"));
+
+        let parts: Vec<(&str, usize)> = disk0
+            .partitions
+            .iter()
+            .map(|p| (p.device.as_str(), p.volumes.len()))
+            .collect();
+        assert_eq!(parts, [("disk0s1", 0), ("disk0s2", 4), ("disk0s3", 0)]);
+
+        // 容器里的卷经 "APFS Physical Store" 挂到 disk0s2 下
+        let store = &disk0.partitions[1];
+        assert_eq!(store.label, None);
+        assert_eq!(store.size_bytes, 494_384_795_648);
+        let volumes: Vec<(&str, &str, Option<&str>, Option<u64>)> = store
+            .volumes
+            .iter()
+            .map(|v| {
+                (
+                    v.device.as_deref().unwrap(),
+                    v.name.as_str(),
+                    v.mount_points.first().map(String::as_str),
+                    v.size_bytes,
+                )
+            })
+            .collect();
+        assert_eq!(
+            volumes,
+            [
+                ("disk3s1", "Macintosh HD", None, Some(9_912_016_896)),
+                ("disk3s1s1", "Macintosh HD", Some("/"), Some(9_912_016_896)),
+                (
+                    "disk3s5",
+                    "Macintosh HD - Data",
+                    Some("/System/Volumes/Data"),
+                    Some(303_116_779_520)
+                ),
+                (
+                    "disk3s6",
+                    "VM",
+                    Some("/System/Volumes/VM"),
+                    Some(3_221_266_432)
+                ),
+            ]
+        );
+        assert!(store
+            .volumes
+            .iter()
+            .all(|v| v.kind == "apfs" && v.parent == "disk3"));
+        assert_eq!(store.volumes[0].file_system.as_deref(), Some("APFS"));
+
+        // 外置 exFAT 盘没有 NVMe 序列号
+        let disk4 = &disks[1];
+        assert_eq!(disk4.model.as_deref(), Some("PSSD T7"));
+        assert_eq!(disk4.serial_number, None);
+        assert!(disk4.removable);
+        let labels: Vec<Option<&str>> = disk4
+            .partitions
+            .iter()
+            .map(|p| p.label.as_deref())
+            .collect();
+        assert_eq!(labels, [Some("EFI"), Some("Samsung T7")]);
+        assert_eq!(disk4.partitions[1].file_system.as_deref(), Some("ExFAT"));
+        assert_eq!(disk4.partitions[1].mount_points, ["/Volumes/Samsung T7"]);
+    }
+
+    #[test]
+    fn without_nvme_serials() {
+        let out = macos_fixtures()
+            .stdout("diskutil", &["info", "-all"])
+            .unwrap();
+        let disks = parse_storage_devices(&out, "");
+        assert_eq!(disks.len(), 2);
+        assert!(disks.iter().all(|d| d.serial_number.is_none()));
+    }
+
+    #[test]
+    fn helpers() {
+        assert_eq!(
+            bytes("500.3 GB (500277792768 Bytes) (exactly 977105064 512-Byte-Units)"),
+            Some(500_277_792_768)
+        );
+        assert_eq!(bytes("0 B"), None);
+        assert_eq!(partition_number("disk0s10"), 10);
+        assert_eq!(partition_number("disk0"), 0);
+        assert!(parse_nvme_serials("not json").is_empty());
+    }
+}
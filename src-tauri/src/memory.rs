@@ -0,0 +1,186 @@
+//! 内存插槽汇总：平台实现只需给出每个插槽的模组信息，通道与统计在这里统一推断。
+//!
+//! 分页速率同样在这里统一计算：平台实现给出累计计数，[`PagingRates`] 保存上一次读数。
+
+use std::collections::HashSet;
+use std::time::Instant;
+
+use regex::Regex;
+
+use crate::{MemoryBreakdown, MemoryLayout, MemoryModule};
+
+/// 由插槽列表汇总
+pub fn layout(
+    mut modules: Vec<MemoryModule>,
+    total_slots: Option<u32>,
+    max_capacity_bytes: Option<u64>,
+) -> MemoryLayout {
+    for m in &mut modules {
+        if m.channel.is_none() {
+            m.channel = infer_channel(m.bank.as_deref(), m.slot.as_deref());
+        }
+    }
+
+    let populated: Vec<&MemoryModule> = modules.iter().filter(|m| m.size_bytes.is_some()).collect();
+    let channels: HashSet<&str> = populated
+        .iter()
+        .filter_map(|m| m.channel.as_deref())
+        .collect();
+
+    MemoryLayout {
+        total_slots,
+        populated_slots: populated.len() as u32,
+        channels_populated: (!channels.is_empty()).then_some(channels.len() as u32),
+        max_capacity_bytes,
+        installed_bytes: populated.iter().filter_map(|m| m.size_bytes).sum(),
+        modules,
+    }
+}
+
+/// 从 bank / 插槽位置里找出通道，例如 "P0 CHANNEL A"、"ChannelA-DIMM0" 为 "A"；
+/// 带内存控制器编号（"Controller1-ChannelB"）时为 "1-B"，避免不同控制器的同名通道被合并
+pub fn infer_channel(bank: Option<&str>, slot: Option<&str>) -> Option<String> {
+    let re = Regex::new(r"(?i)(?:controller\s*(\d+)\W*)?channel\s*-?\s*([a-z0-9]+)").ok()?;
+    [bank, slot].into_iter().flatten().find_map(|s| {
+        let cap = re.captures(s)?;
+        let channel = cap.get(2)?.as_str().to_uppercase();
+        Some(match cap.get(1) {
+            Some(ctrl) => format!("{}-{}", ctrl.as_str(), channel),
+            None => channel,
+        })
+    })
+}
+
+/// 累计的分页与交换量（字节）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PagingCounters {
+    pub page_in: u64,
+    pub page_out: u64,
+    pub swap_in: u64,
+    pub swap_out: u64,
+}
+
+/// 跨调用保存上一次的分页计数
+#[derive(Default)]
+pub struct PagingRates {
+    last: Option<(Instant, PagingCounters)>,
+}
+
+impl PagingRates {
+    pub fn apply(&mut self, breakdown: &mut MemoryBreakdown, counters: PagingCounters) {
+        self.apply_at(breakdown, counters, Instant::now())
+    }
+
+    /// 以 `now` 作为本次读数的时间，把速率写入 `breakdown`；第一次调用只记录基准
+    pub fn apply_at(
+        &mut self,
+        breakdown: &mut MemoryBreakdown,
+        counters: PagingCounters,
+        now: Instant,
+    ) {
+        let Some((at, prev)) = self.last.replace((now, counters)) else {
+            return;
+        };
+        let elapsed = now.saturating_duration_since(at).as_secs_f64();
+        if elapsed <= 0.0 {
+            return;
+        }
+        // 计数器在重启或回绕后会变小，这一次按 0 处理
+        let rate = |cur: u64, prev: u64| Some((cur.saturating_sub(prev) as f64 / elapsed) as u64);
+        breakdown.page_in_bytes_per_sec = rate(counters.page_in, prev.page_in);
+        breakdown.page_out_bytes_per_sec = rate(counters.page_out, prev.page_out);
+        breakdown.swap_in_bytes_per_sec = rate(counters.swap_in, prev.swap_in);
+        breakdown.swap_out_bytes_per_sec = rate(counters.swap_out, prev.swap_out);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn channel_from_bank_or_slot() {
+        assert_eq!(
+            infer_channel(Some("P0 CHANNEL A"), Some("DIMM 0")).as_deref(),
+            Some("A")
+        );
+        assert_eq!(
+            infer_channel(Some("BANK 0"), Some("ChannelB-DIMM1")).as_deref(),
+            Some("B")
+        );
+        assert_eq!(
+            infer_channel(Some("BANK 0"), Some("Controller1-ChannelC")).as_deref(),
+            Some("1-C")
+        );
+        assert_eq!(infer_channel(Some("Channel-2"), None).as_deref(), Some("2"));
+        assert_eq!(infer_channel(Some("BANK 0"), Some("DIMM 0")), None);
+        assert_eq!(infer_channel(None, None), None);
+    }
+
+    #[test]
+    fn layout_counts_populated_slots() {
+        let module = |bank: &str, size: Option<u64>| MemoryModule {
+            bank: Some(bank.to_string()),
+            size_bytes: size,
+            ..Default::default()
+        };
+        let desktop = layout(
+            vec![
+                module("P0 CHANNEL A", Some(8 << 30)),
+                module("P0 CHANNEL A", None),
+                module("P0 CHANNEL B", Some(8 << 30)),
+                MemoryModule {
+                    channel: Some("X".to_string()),
+                    ..module("P0 CHANNEL B", None)
+                },
+            ],
+            Some(4),
+            Some(64 << 30),
+        );
+        assert_eq!(desktop.total_slots, Some(4));
+        assert_eq!(desktop.populated_slots, 2);
+        assert_eq!(desktop.channels_populated, Some(2));
+        assert_eq!(desktop.installed_bytes, 16 << 30);
+        // 已知的通道不会被覆盖
+        assert_eq!(desktop.modules[3].channel.as_deref(), Some("X"));
+
+        let empty = layout(Vec::new(), None, None);
+        assert_eq!(empty.populated_slots, 0);
+        assert_eq!(empty.channels_populated, None);
+        assert_eq!(empty.installed_bytes, 0);
+    }
+
+    #[test]
+    fn paging_rates() {
+        let counters = |n: u64| PagingCounters {
+            page_in: n,
+            page_out: 2 * n,
+            swap_in: 0,
+            swap_out: n,
+        };
+        let start = Instant::now();
+        let mut rates = PagingRates::default();
+        let mut breakdown = MemoryBreakdown::default();
+
+        // 第一次只记录基准
+        rates.apply_at(&mut breakdown, counters(1000), start);
+        assert_eq!(breakdown.page_in_bytes_per_sec, None);
+
+        rates.apply_at(
+            &mut breakdown,
+            counters(3000),
+            start + Duration::from_secs(2),
+        );
+        assert_eq!(breakdown.page_in_bytes_per_sec, Some(1000));
+        assert_eq!(breakdown.page_out_bytes_per_sec, Some(2000));
+        assert_eq!(breakdown.swap_in_bytes_per_sec, Some(0));
+        assert_eq!(breakdown.swap_out_bytes_per_sec, Some(1000));
+
+        // 计数器变小（重启后）按 0 处理
+        rates.apply_at(&mut breakdown, counters(10), start + Duration::from_secs(3));
+        assert_eq!(breakdown.page_in_bytes_per_sec, Some(0));
+        assert_eq!(breakdown.page_out_bytes_per_sec, Some(0));
+    }
+}
@@ -0,0 +1,235 @@
+//! 网络接口流量与地址的平台无关部分。
+//!
+//! 流量：平台实现给出累计计数，采样线程用相邻两次读数之差算出速率。
+//! 平台计数一般是开机（或接口创建）以来的累计值；应用启动后第一次读到的计数作为基准，
+//! 用来给出“本次运行以来”的流量。接口重建后计数归零，基准随之重置。
+//!
+//! 地址：作用域按地址本身判断，附加地址（secondary）按网段判断，两个平台共用。
+
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{IpAddress, NetIo};
+
+/// 一个网络接口的累计计数
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NetCounters {
+    pub interface: String,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    pub rx_packets: u64,
+    pub tx_packets: u64,
+    pub rx_errors: u64,
+    pub tx_errors: u64,
+    /// 丢包数；macOS 的 netstat 只给出发送方向，接收方向为 None
+    pub rx_dropped: Option<u64>,
+    pub tx_dropped: Option<u64>,
+    /// 收到的多播包（macOS 没有对应计数）
+    pub multicast: Option<u64>,
+}
+
+/// 由上一次与本次的累计计数算出各接口速率；`baseline` 记录每个接口首次出现时的计数，
+/// 首次出现的接口只写入基准，不出现在结果里
+pub fn rates(
+    previous: &HashMap<String, NetCounters>,
+    baseline: &mut HashMap<String, NetCounters>,
+    current: &[NetCounters],
+    elapsed_secs: f64,
+) -> Vec<NetIo> {
+    for cur in current {
+        let reset = baseline
+            .get(&cur.interface)
+            .is_none_or(|b| cur.rx_bytes < b.rx_bytes || cur.tx_bytes < b.tx_bytes);
+        if reset {
+            baseline.insert(cur.interface.clone(), cur.clone());
+        }
+    }
+    if elapsed_secs <= 0.0 {
+        return Vec::new();
+    }
+    current
+        .iter()
+        .filter_map(|cur| {
+            let prev = previous.get(&cur.interface)?;
+            let base = &baseline[&cur.interface];
+            let per_sec = |c: u64, p: u64| c.saturating_sub(p) as f64 / elapsed_secs;
+            Some(NetIo {
+                interface: cur.interface.clone(),
+                rx_bytes_per_sec: per_sec(cur.rx_bytes, prev.rx_bytes) as u64,
+                tx_bytes_per_sec: per_sec(cur.tx_bytes, prev.tx_bytes) as u64,
+                rx_packets_per_sec: per_sec(cur.rx_packets, prev.rx_packets) as f32,
+                tx_packets_per_sec: per_sec(cur.tx_packets, prev.tx_packets) as f32,
+                rx_errors: cur.rx_errors,
+                tx_errors: cur.tx_errors,
+                rx_dropped: cur.rx_dropped,
+                tx_dropped: cur.tx_dropped,
+                multicast: cur.multicast,
+                rx_bytes_total: cur.rx_bytes,
+                tx_bytes_total: cur.tx_bytes,
+                rx_bytes_session: cur.rx_bytes.saturating_sub(base.rx_bytes),
+                tx_bytes_session: cur.tx_bytes.saturating_sub(base.tx_bytes),
+            })
+        })
+        .collect()
+}
+
+impl IpAddress {
+    pub fn new(address: IpAddr, prefix_len: Option<u8>) -> Self {
+        Self {
+            family: if address.is_ipv4() { "ipv4" } else { "ipv6" }.to_string(),
+            address,
+            prefix_len,
+            broadcast: None,
+            peer: None,
+            scope: scope_of(&address).to_string(),
+            temporary: false,
+            deprecated: false,
+            secondary: false,
+        }
+    }
+}
+
+/// 地址作用域；IPv4 私有地址按 global 处理，与内核的 scope 一致
+pub fn scope_of(address: &IpAddr) -> &'static str {
+    match address {
+        IpAddr::V4(v4) if v4.is_loopback() => "loopback",
+        IpAddr::V4(v4) if v4.is_link_local() => "link-local",
+        IpAddr::V4(_) => "global",
+        IpAddr::V6(v6) if v6.is_loopback() => "loopback",
+        IpAddr::V6(v6) if v6.segments()[0] & 0xffc0 == 0xfe80 => "link-local",
+        IpAddr::V6(v6) if v6.segments()[0] & 0xfe00 == 0xfc00 => "ula",
+        IpAddr::V6(_) => "global",
+    }
+}
+
+/// IPv4 广播地址；/31、/32 没有广播地址
+pub fn ipv4_broadcast(address: Ipv4Addr, prefix_len: u8) -> Option<Ipv4Addr> {
+    (prefix_len < 31).then(|| Ipv4Addr::from(u32::from(address) | (u32::MAX >> prefix_len)))
+}
+
+/// 按内核的定义标记附加地址：与同一接口上更早的 IPv4 地址处于同一网段
+pub fn mark_secondary(addresses: &mut [IpAddress]) {
+    let network = |a: &IpAddress| match (a.address, a.prefix_len) {
+        (IpAddr::V4(v4), Some(len)) if len > 0 => {
+            Some((u32::from(v4) & (u32::MAX << (32 - len.min(32) as u32)), len))
+        }
+        _ => None,
+    };
+    for i in 1..addresses.len() {
+        let Some(net) = network(&addresses[i]) else {
+            continue;
+        };
+        addresses[i].secondary = addresses[..i].iter().any(|a| network(a) == Some(net));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn counters(interface: &str, rx_bytes: u64, tx_bytes: u64) -> NetCounters {
+        NetCounters {
+            interface: interface.to_string(),
+            rx_bytes,
+            tx_bytes,
+            rx_packets: rx_bytes / 1000,
+            tx_packets: tx_bytes / 1000,
+            ..Default::default()
+        }
+    }
+
+    fn by_name(counters: &[NetCounters]) -> HashMap<String, NetCounters> {
+        counters
+            .iter()
+            .map(|c| (c.interface.clone(), c.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn rates_and_session_totals() {
+        let mut baseline = HashMap::new();
+        let first = [counters("eth0", 1_000_000, 500_000)];
+        // 第一次只记录基准
+        assert!(rates(&HashMap::new(), &mut baseline, &first, 1.0).is_empty());
+        assert_eq!(baseline["eth0"].rx_bytes, 1_000_000);
+
+        let second = [
+            counters("eth0", 3_000_000, 600_000),
+            counters("wg0", 10_000, 20_000),
+        ];
+        let io = rates(&by_name(&first), &mut baseline, &second, 2.0);
+        // wg0 刚出现，没有上一次的读数
+        assert_eq!(io.len(), 1);
+        let eth0 = &io[0];
+        assert_eq!(eth0.rx_bytes_per_sec, 1_000_000);
+        assert_eq!(eth0.tx_bytes_per_sec, 50_000);
+        assert_eq!(eth0.rx_packets_per_sec, 1000.0);
+        assert_eq!(eth0.rx_bytes_total, 3_000_000);
+        assert_eq!(eth0.rx_bytes_session, 2_000_000);
+        assert_eq!(eth0.tx_bytes_session, 100_000);
+        assert_eq!(baseline["wg0"].rx_bytes, 10_000);
+
+        // 接口重建后计数归零：基准重置，速率按 0 处理
+        let third = [counters("eth0", 4_000, 1_000)];
+        let io = rates(&by_name(&second), &mut baseline, &third, 1.0);
+        assert_eq!(io[0].rx_bytes_per_sec, 0);
+        assert_eq!(io[0].rx_bytes_session, 0);
+        assert_eq!(baseline["eth0"].rx_bytes, 4_000);
+
+        // 时间没有前进时只更新基准
+        assert!(rates(&by_name(&third), &mut baseline, &third, 0.0).is_empty());
+    }
+
+    #[test]
+    fn address_scopes() {
+        let scope = |s: &str| scope_of(&s.parse().unwrap());
+        assert_eq!(scope("127.0.0.1"), "loopback");
+        assert_eq!(scope("169.254.10.2"), "link-local");
+        assert_eq!(scope("10.0.0.1"), "global");
+        assert_eq!(scope("::1"), "loopback");
+        assert_eq!(scope("fe80::1"), "link-local");
+        assert_eq!(scope("febf::1"), "link-local");
+        assert_eq!(scope("fd7a:115c:a1e0::1"), "ula");
+        assert_eq!(scope("fc00::1"), "ula");
+        assert_eq!(scope("2001:db8::1"), "global");
+    }
+
+    #[test]
+    fn broadcast_addresses() {
+        let ip = |s: &str| s.parse::<Ipv4Addr>().unwrap();
+        assert_eq!(
+            ipv4_broadcast(ip("192.168.1.23"), 24),
+            Some(ip("192.168.1.255"))
+        );
+        assert_eq!(
+            ipv4_broadcast(ip("172.17.0.1"), 16),
+            Some(ip("172.17.255.255"))
+        );
+        assert_eq!(
+            ipv4_broadcast(ip("10.1.2.3"), 0),
+            Some(ip("255.255.255.255"))
+        );
+        assert_eq!(ipv4_broadcast(ip("10.0.0.0"), 31), None);
+        assert_eq!(ipv4_broadcast(ip("10.0.0.1"), 32), None);
+    }
+
+    #[test]
+    fn secondary_addresses() {
+        let addr = |s: &str, len: Option<u8>| IpAddress::new(s.parse().unwrap(), len);
+        let mut addresses = vec![
+            addr("192.168.1.23", Some(24)),
+            addr("fe80::1", Some(64)),
+            addr("192.168.1.24", Some(24)),
+            addr("192.168.2.1", Some(24)),
+            addr("192.168.1.25", Some(32)),
+            addr("10.0.0.1", None),
+            addr("10.0.0.2", None),
+        ];
+        mark_secondary(&mut addresses);
+        let secondary: Vec<bool> = addresses.iter().map(|a| a.secondary).collect();
+        // 网段相同且前缀长度相同才算附加地址；没有前缀长度的无法判断
+        assert_eq!(secondary, [false, false, true, false, false, false, false]);
+    }
+}
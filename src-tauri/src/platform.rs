@@ -0,0 +1,456 @@
+//! 平台抽象：每个操作系统实现一个 `Platform`，命令层只依赖该 trait。
+//!
+//! 启动时通过 [`detect`] 选出实现并放进 Tauri 的托管状态；
+//! 设置 `CPU_LIGHT_FIXTURE_DIR` 环境变量时改用录制好的数据，方便在 CI / 无硬件环境下运行；
+//! 设置 `CPU_LIGHT_COMMAND_FIXTURES` 时在任意系统上用 macOS 实现回放录制的命令输出。
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Context;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::command;
+use crate::disk::DiskCounters;
+use crate::network::NetCounters;
+use crate::process::sockets::SocketInfo;
+use crate::{
+    BatteryInfo, BoardInfo, CacheInfo, CpuDetails, CpuFeatures, DeviceInfo, DiskHealth,
+    HardwareData, InterfaceInfo, MemoryBreakdown, MemoryLayout, PhysicalDisk, Power, Sensors,
+    Temps, WifiInfo,
+};
+
+/// 指向录制数据目录的环境变量
+pub const FIXTURE_DIR_ENV: &str = "CPU_LIGHT_FIXTURE_DIR";
+
+pub type SharedPlatform = Arc<dyn Platform>;
+
+/// 各平台需要提供的采集能力
+pub trait Platform: Send + Sync {
+    /// 实现名称，用于日志
+    fn name(&self) -> &'static str;
+
+    fn device_info(&self) -> anyhow::Result<DeviceInfo>;
+
+    fn hardware_data(&self) -> anyhow::Result<HardwareData>;
+
+    /// BIOS、主板与机箱信息
+    fn board_info(&self) -> anyhow::Result<BoardInfo>;
+
+    /// 内存插槽与模组
+    fn memory_modules(&self) -> anyhow::Result<MemoryLayout>;
+
+    /// 内存细分、交换与内存压力；分页速率基于两次调用之间的计数差
+    fn memory_breakdown(&self) -> anyhow::Result<MemoryBreakdown>;
+
+    /// CPU 型号、拓扑与频率范围；使用率由采样线程提供，不在这里填
+    fn cpu_details(&self) -> anyhow::Result<CpuDetails>;
+
+    /// 各级缓存，共享缓存只出现一次
+    fn caches(&self) -> anyhow::Result<Vec<CacheInfo>>;
+
+    /// 指令集扩展，包含 CPU 支持但被系统禁用的项
+    fn cpu_features(&self) -> anyhow::Result<CpuFeatures>;
+
+    /// 温度是 best-effort 的，拿不到时返回 None
+    fn temps(&self) -> Option<Temps>;
+
+    /// 功耗同样是 best-effort 的；基于能量计数器的实现第一次调用只建立基准，返回 None
+    fn power(&self) -> Option<Power>;
+
+    /// 各块设备的累计读写计数，速率由采样线程计算
+    fn disk_counters(&self) -> anyhow::Result<Vec<DiskCounters>>;
+
+    /// 各网络接口的累计收发计数，速率由采样线程计算
+    fn net_counters(&self) -> anyhow::Result<Vec<NetCounters>>;
+
+    /// 物理磁盘及其分区、逻辑卷
+    fn storage_devices(&self) -> anyhow::Result<Vec<PhysicalDisk>>;
+
+    /// 各物理磁盘的 SMART / NVMe 健康状态；单块磁盘读取失败记入其 `error`，不影响其它磁盘
+    fn disk_health(&self) -> anyhow::Result<Vec<DiskHealth>>;
+
+    /// 全部 TCP / UDP 套接字及其所属进程，过滤在命令层完成
+    fn sockets(&self) -> anyhow::Result<Vec<SocketInfo>>;
+
+    fn battery(&self) -> anyhow::Result<BatteryInfo>;
+
+    /// 风扇、电压、电流、功率
+    fn sensors(&self) -> anyhow::Result<Sensors>;
+
+    fn interfaces(&self) -> anyhow::Result<Vec<InterfaceInfo>>;
+
+    fn wifi(&self) -> anyhow::Result<Option<WifiInfo>>;
+
+    fn default_gateway(&self) -> anyhow::Result<Option<String>>;
+
+    fn dns_servers(&self) -> Option<Vec<String>>;
+}
+
+/// 按环境变量与编译目标选择平台实现
+pub fn detect() -> SharedPlatform {
+    if let Some(dir) = std::env::var_os(FIXTURE_DIR_ENV) {
+        log::info!("Using fixture platform from {:?}", dir);
+        return Arc::new(FixturePlatform::new(dir));
+    }
+    if std::env::var_os(command::REPLAY_DIR_ENV).is_some() {
+        return Arc::new(crate::macos::MacosPlatform::new(command::from_env()));
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        Arc::new(crate::macos::MacosPlatform::new(command::from_env()))
+    }
+    #[cfg(target_os = "linux")]
+    {
+        Arc::new(crate::linux::LinuxPlatform::new())
+    }
+    #[cfg(not(any(target_os = "macos", target_os = "linux")))]
+    {
+        Arc::new(UnsupportedPlatform)
+    }
+}
+
+/// Unix 时间戳（秒）格式化为 ISO8601 UTC 字符串
+pub fn format_utc(secs: i64) -> Option<String> {
+    chrono::DateTime::from_timestamp(secs, 0).map(|dt| dt.format("%Y-%m-%dT%H:%M:%SZ").to_string())
+}
+
+/// 网络相关的录制数据（network.json）
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NetworkFixture {
+    #[serde(default)]
+    pub interfaces: Vec<InterfaceInfo>,
+    pub wifi: Option<WifiInfo>,
+    pub default_gateway: Option<String>,
+    #[serde(default)]
+    pub dns_servers: Vec<String>,
+}
+
+/// 从目录中读取录制的 JSON：device_info.json、hardware_data.json、board_info.json、
+/// memory_modules.json、memory_breakdown.json、cpu_details.json、caches.json、cpu_features.json、
+/// temps.json、power.json、disk_counters.json、net_counters.json、storage_devices.json、
+/// disk_health.json、sockets.json、battery.json、sensors.json、network.json，
+/// 缺失的文件按“拿不到数据”处理
+pub struct FixturePlatform {
+    dir: PathBuf,
+}
+
+impl FixturePlatform {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn load<T: DeserializeOwned>(&self, file: &str) -> anyhow::Result<T> {
+        let path = self.dir.join(file);
+        let s = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read fixture {}", path.display()))?;
+        serde_json::from_str(&s).with_context(|| format!("invalid fixture {}", path.display()))
+    }
+
+    fn network(&self) -> NetworkFixture {
+        self.load("network.json").unwrap_or_default()
+    }
+}
+
+impl Platform for FixturePlatform {
+    fn name(&self) -> &'static str {
+        "fixture"
+    }
+
+    fn device_info(&self) -> anyhow::Result<DeviceInfo> {
+        self.load("device_info.json")
+    }
+
+    fn hardware_data(&self) -> anyhow::Result<HardwareData> {
+        self.load("hardware_data.json")
+    }
+
+    fn board_info(&self) -> anyhow::Result<BoardInfo> {
+        self.load("board_info.json")
+    }
+
+    fn memory_modules(&self) -> anyhow::Result<MemoryLayout> {
+        self.load("memory_modules.json")
+    }
+
+    fn memory_breakdown(&self) -> anyhow::Result<MemoryBreakdown> {
+        self.load("memory_breakdown.json")
+    }
+
+    fn cpu_details(&self) -> anyhow::Result<CpuDetails> {
+        self.load("cpu_details.json")
+    }
+
+    fn caches(&self) -> anyhow::Result<Vec<CacheInfo>> {
+        self.load("caches.json")
+    }
+
+    fn cpu_features(&self) -> anyhow::Result<CpuFeatures> {
+        self.load("cpu_features.json")
+    }
+
+    fn temps(&self) -> Option<Temps> {
+        self.load("temps.json").ok()
+    }
+
+    fn power(&self) -> Option<Power> {
+        self.load("power.json").ok()
+    }
+
+    fn disk_counters(&self) -> anyhow::Result<Vec<DiskCounters>> {
+        self.load("disk_counters.json")
+    }
+
+    fn net_counters(&self) -> anyhow::Result<Vec<NetCounters>> {
+        self.load("net_counters.json")
+    }
+
+    fn storage_devices(&self) -> anyhow::Result<Vec<PhysicalDisk>> {
+        self.load("storage_devices.json")
+    }
+
+    fn disk_health(&self) -> anyhow::Result<Vec<DiskHealth>> {
+        self.load("disk_health.json")
+    }
+
+    fn sockets(&self) -> anyhow::Result<Vec<SocketInfo>> {
+        self.load("sockets.json")
+    }
+
+    fn battery(&self) -> anyhow::Result<BatteryInfo> {
+        self.load("battery.json")
+    }
+
+    fn sensors(&self) -> anyhow::Result<Sensors> {
+        self.load("sensors.json")
+    }
+
+    fn interfaces(&self) -> anyhow::Result<Vec<InterfaceInfo>> {
+        Ok(self.network().interfaces)
+    }
+
+    fn wifi(&self) -> anyhow::Result<Option<WifiInfo>> {
+        Ok(self.network().wifi)
+    }
+
+    fn default_gateway(&self) -> anyhow::Result<Option<String>> {
+        Ok(self.network().default_gateway)
+    }
+
+    fn dns_servers(&self) -> Option<Vec<String>> {
+        Some(self.network().dns_servers)
+    }
+}
+
+/// 尚未适配的平台：除 x86 上通过 CPUID 读取的缓存与指令集信息外，所有采集都返回错误或空值
+#[cfg_attr(any(target_os = "macos", target_os = "linux"), allow(dead_code))]
+pub struct UnsupportedPlatform;
+
+impl Platform for UnsupportedPlatform {
+    fn name(&self) -> &'static str {
+        "unsupported"
+    }
+
+    fn device_info(&self) -> anyhow::Result<DeviceInfo> {
+        Err(anyhow::anyhow!(
+            "device info is not supported on this platform"
+        ))
+    }
+
+    fn hardware_data(&self) -> anyhow::Result<HardwareData> {
+        Err(anyhow::anyhow!(
+            "hardware data is not supported on this platform"
+        ))
+    }
+
+    fn board_info(&self) -> anyhow::Result<BoardInfo> {
+        Err(anyhow::anyhow!(
+            "board info is not supported on this platform"
+        ))
+    }
+
+    fn memory_modules(&self) -> anyhow::Result<MemoryLayout> {
+        Err(anyhow::anyhow!(
+            "memory modules are not supported on this platform"
+        ))
+    }
+
+    fn memory_breakdown(&self) -> anyhow::Result<MemoryBreakdown> {
+        Err(anyhow::anyhow!(
+            "memory breakdown is not supported on this platform"
+        ))
+    }
+
+    fn cpu_details(&self) -> anyhow::Result<CpuDetails> {
+        Err(anyhow::anyhow!(
+            "cpu details are not supported on this platform"
+        ))
+    }
+
+    fn caches(&self) -> anyhow::Result<Vec<CacheInfo>> {
+        crate::cache::read_cpuid()
+    }
+
+    fn cpu_features(&self) -> anyhow::Result<CpuFeatures> {
+        // 没有系统侧的信息，只能以 CPUID 为准
+        let cpuid = crate::features::read_cpuid();
+        Ok(crate::features::detect(
+            std::env::consts::ARCH,
+            &|_| None,
+            cpuid.as_ref(),
+        ))
+    }
+
+    fn temps(&self) -> Option<Temps> {
+        None
+    }
+
+    fn power(&self) -> Option<Power> {
+        None
+    }
+
+    fn disk_counters(&self) -> anyhow::Result<Vec<DiskCounters>> {
+        Err(anyhow::anyhow!(
+            "disk counters are not supported on this platform"
+        ))
+    }
+
+    fn net_counters(&self) -> anyhow::Result<Vec<NetCounters>> {
+        Err(anyhow::anyhow!(
+            "network counters are not supported on this platform"
+        ))
+    }
+
+    fn storage_devices(&self) -> anyhow::Result<Vec<PhysicalDisk>> {
+        Err(anyhow::anyhow!(
+            "storage devices are not supported on this platform"
+        ))
+    }
+
+    fn disk_health(&self) -> anyhow::Result<Vec<DiskHealth>> {
+        Err(anyhow::anyhow!(
+            "disk health is not supported on this platform"
+        ))
+    }
+
+    fn sockets(&self) -> anyhow::Result<Vec<SocketInfo>> {
+        Err(anyhow::anyhow!(
+            "socket table is not supported on this platform"
+        ))
+    }
+
+    fn battery(&self) -> anyhow::Result<BatteryInfo> {
+        Err(anyhow::anyhow!(
+            "battery info is not supported on this platform"
+        ))
+    }
+
+    fn sensors(&self) -> anyhow::Result<Sensors> {
+        Err(anyhow::anyhow!(
+            "sensors are not supported on this platform"
+        ))
+    }
+
+    fn interfaces(&self) -> anyhow::Result<Vec<InterfaceInfo>> {
+        Ok(Vec::new())
+    }
+
+    fn wifi(&self) -> anyhow::Result<Option<WifiInfo>> {
+        Ok(None)
+    }
+
+    fn default_gateway(&self) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn dns_servers(&self) -> Option<Vec<String>> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MACBOOK: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/fixtures/platform/macbook-pro-m1"
+    );
+
+    #[test]
+    fn fixture_platform_loads_every_method() {
+        let p = FixturePlatform::new(MACBOOK);
+        assert_eq!(p.name(), "fixture");
+
+        let device = p.device_info().unwrap();
+        assert_eq!(device.model_identifier.as_deref(), Some("MacBookPro17,1"));
+        assert_eq!(device.uptime_seconds, 183204);
+
+        let hardware = p.hardware_data().unwrap();
+        assert_eq!(hardware.chip.as_deref(), Some("Apple M1"));
+        assert_eq!(hardware.memory.as_deref(), Some("16 GB"));
+
+        let board = p.board_info().unwrap();
+        assert_eq!(board.bios.unwrap().vendor.as_deref(), Some("Apple Inc."));
+
+        let modules = p.memory_modules().unwrap();
+        assert_eq!(modules.installed_bytes, 16 << 30);
+        assert_eq!(modules.modules.len(), 1);
+
+        let breakdown = p.memory_breakdown().unwrap();
+        assert_eq!(breakdown.total_bytes, 16 << 30);
+
+        let cpu = p.cpu_details().unwrap();
+        assert_eq!(cpu.brand.as_deref(), Some("Apple M1"));
+        assert_eq!(cpu.cores.len(), 8);
+
+        assert_eq!(p.caches().unwrap().len(), 18);
+        assert_eq!(p.cpu_features().unwrap().arch, "aarch64");
+
+        let temps = p.temps().unwrap();
+        assert_eq!(temps.cpu, Some(48.53));
+        assert!(temps.sensors.is_empty());
+        assert_eq!(p.power().unwrap().cpu, Some(0.187));
+
+        let disks = p.disk_counters().unwrap();
+        assert_eq!(disks[0].device, "disk0");
+        assert_eq!(p.net_counters().unwrap().len(), 8);
+        assert_eq!(p.storage_devices().unwrap().len(), 2);
+        assert_eq!(p.disk_health().unwrap()[0].verdict, "good");
+        assert_eq!(p.sockets().unwrap().len(), 11);
+
+        let battery = p.battery().unwrap();
+        assert_eq!(battery.batteries[0].cycle_count, Some(312));
+        assert_eq!(battery.ac_online, Some(false));
+
+        assert_eq!(p.sensors().unwrap().fans.len(), 1);
+
+        let names: Vec<String> = p
+            .interfaces()
+            .unwrap()
+            .into_iter()
+            .map(|i| i.name)
+            .collect();
+        assert_eq!(names, ["en0", "lo0"]);
+        let wifi = p.wifi().unwrap().unwrap();
+        assert_eq!(wifi.ssid.as_deref(), Some("office-5G"));
+        assert_eq!(p.default_gateway().unwrap().as_deref(), Some("192.168.1.1"));
+        assert_eq!(p.dns_servers().unwrap(), ["192.168.1.1", "223.5.5.5"]);
+    }
+
+    #[test]
+    fn fixture_platform_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let p = FixturePlatform::new(dir.path());
+
+        let err = p.device_info().unwrap_err();
+        assert!(format!("{err:#}").contains("device_info.json"));
+        assert!(p.temps().is_none());
+        assert!(p.power().is_none());
+        assert!(p.interfaces().unwrap().is_empty());
+        assert!(p.default_gateway().unwrap().is_none());
+    }
+}
@@ -0,0 +1,286 @@
+//! 进程控制：发送信号与调整优先级（nice）。
+//!
+//! 为了避免前端误操作任意 pid，所有操作都要先通过 [`ActionGuard::issue`] 领取一次性令牌：
+//! 令牌绑定 pid 与进程启动时间（防止 pid 被复用），`TOKEN_TTL` 内有效，使用一次即作废。
+//! 前端应在确认对话框里展示令牌返回的进程名，用户确认后再带着令牌调用操作命令。
+
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::anyhow;
+use nix::errno::Errno;
+use nix::sys::signal::{self, Signal};
+use nix::unistd::Pid;
+use serde::{Deserialize, Serialize};
+
+use crate::sampler::Collectors;
+
+/// 令牌有效期
+pub const TOKEN_TTL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ProcessSignal {
+    Term,
+    Kill,
+    Stop,
+    Cont,
+}
+
+impl From<ProcessSignal> for Signal {
+    fn from(s: ProcessSignal) -> Self {
+        match s {
+            ProcessSignal::Term => Signal::SIGTERM,
+            ProcessSignal::Kill => Signal::SIGKILL,
+            ProcessSignal::Stop => Signal::SIGSTOP,
+            ProcessSignal::Cont => Signal::SIGCONT,
+        }
+    }
+}
+
+/// 发给前端用于确认的令牌
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ActionTicket {
+    pub token: String,
+    pub pid: u32,
+    pub name: String,
+    pub expires_in_ms: u64,
+}
+
+struct Pending {
+    pid: u32,
+    start_time: u64,
+    issued_at: Instant,
+}
+
+#[derive(Default)]
+pub struct ActionGuard {
+    pending: Mutex<HashMap<String, Pending>>,
+    /// 每个 ActionGuard 随机的哈希种子，令牌无法从 pid 推算
+    hasher: RandomState,
+    issued: AtomicU64,
+}
+
+impl ActionGuard {
+    /// 为 `pid` 签发令牌；拒绝 pid 0/1 与本进程
+    pub fn issue(&self, c: &Collectors, pid: u32) -> anyhow::Result<ActionTicket> {
+        if pid <= 1 || pid == std::process::id() {
+            return Err(anyhow!("refusing to control process {}", pid));
+        }
+        let p = c
+            .sys
+            .process(sysinfo::Pid::from_u32(pid))
+            .ok_or_else(|| anyhow!("process {} not found", pid))?;
+        Ok(self.issue_at(
+            pid,
+            p.start_time(),
+            p.name().to_string_lossy().into_owned(),
+            Instant::now(),
+        ))
+    }
+
+    /// 以 `now` 作为签发时间，为启动时间为 `start_time` 的进程签发令牌
+    pub fn issue_at(&self, pid: u32, start_time: u64, name: String, now: Instant) -> ActionTicket {
+        let mut hasher = self.hasher.build_hasher();
+        hasher.write_u32(pid);
+        hasher.write_u64(start_time);
+        hasher.write_u64(self.issued.fetch_add(1, Ordering::Relaxed));
+        hasher.write_u64(
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_nanos() as u64,
+        );
+        let token = format!("{:016x}", hasher.finish());
+
+        let mut pending = self.pending.lock().unwrap();
+        pending.retain(|_, p| now.saturating_duration_since(p.issued_at) < TOKEN_TTL);
+        pending.insert(
+            token.clone(),
+            Pending {
+                pid,
+                start_time,
+                issued_at: now,
+            },
+        );
+
+        ActionTicket {
+            token,
+            pid,
+            name,
+            expires_in_ms: TOKEN_TTL.as_millis() as u64,
+        }
+    }
+
+    /// 校验并作废令牌：必须是同一个 pid、未过期，且进程没有被替换
+    pub fn redeem(&self, c: &Collectors, pid: u32, token: &str) -> anyhow::Result<()> {
+        let start_time = c
+            .sys
+            .process(sysinfo::Pid::from_u32(pid))
+            .map(|p| p.start_time());
+        self.redeem_at(pid, token, start_time, Instant::now())
+    }
+
+    /// 以 `now` 作为当前时间校验令牌；`start_time` 为 pid 当前对应进程的启动时间，
+    /// 进程已退出时为 None
+    pub fn redeem_at(
+        &self,
+        pid: u32,
+        token: &str,
+        start_time: Option<u64>,
+        now: Instant,
+    ) -> anyhow::Result<()> {
+        let pending = self
+            .pending
+            .lock()
+            .unwrap()
+            .remove(token)
+            .ok_or_else(|| anyhow!("invalid or already used confirmation token"))?;
+        if pending.pid != pid {
+            return Err(anyhow!("confirmation token was issued for another process"));
+        }
+        if now.saturating_duration_since(pending.issued_at) >= TOKEN_TTL {
+            return Err(anyhow!("confirmation token expired"));
+        }
+        if start_time != Some(pending.start_time) {
+            return Err(anyhow!("process {} has exited or been replaced", pid));
+        }
+        Ok(())
+    }
+}
+
+pub fn send_signal(pid: u32, sig: ProcessSignal) -> anyhow::Result<()> {
+    signal::kill(Pid::from_raw(pid as i32), Signal::from(sig)).map_err(|e| describe(e, pid))
+}
+
+/// 设置 nice 值（-20..=19，越小优先级越高）；降低 nice 值通常需要管理员权限
+pub fn renice(pid: u32, nice: i32) -> anyhow::Result<()> {
+    if !(-20..=19).contains(&nice) {
+        return Err(anyhow!("nice value {} out of range -20..=19", nice));
+    }
+    // nix 没有封装 setpriority，直接调用 libc
+    // SAFETY: setpriority 只接收整数参数，不涉及内存；pid 不存在或无权限时通过 errno 报错
+    let ret = unsafe { nix::libc::setpriority(nix::libc::PRIO_PROCESS, pid as _, nice) };
+    Errno::result(ret).map(drop).map_err(|e| describe(e, pid))
+}
+
+fn describe(e: Errno, pid: u32) -> anyhow::Error {
+    match e {
+        Errno::EPERM | Errno::EACCES => anyhow!(
+            "permission denied for process {}: it belongs to another user or the operation requires administrator privileges",
+            pid
+        ),
+        Errno::ESRCH => anyhow!("process {} not found", pid),
+        e => anyhow!("operation on process {} failed: {}", pid, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PID: u32 = 4242;
+    const STARTED: u64 = 1_700_000_000;
+
+    fn issue(guard: &ActionGuard, now: Instant) -> String {
+        guard.issue_at(PID, STARTED, "node".to_string(), now).token
+    }
+
+    fn error(r: anyhow::Result<()>) -> String {
+        r.unwrap_err().to_string()
+    }
+
+    #[test]
+    fn redeems_once() {
+        let guard = ActionGuard::default();
+        let now = Instant::now();
+        let ticket = guard.issue_at(PID, STARTED, "node".to_string(), now);
+        assert_eq!(ticket.pid, PID);
+        assert_eq!(ticket.name, "node");
+        assert_eq!(ticket.expires_in_ms, 30_000);
+
+        guard
+            .redeem_at(PID, &ticket.token, Some(STARTED), now)
+            .unwrap();
+        assert!(
+            error(guard.redeem_at(PID, &ticket.token, Some(STARTED), now)).contains("already used")
+        );
+        assert!(
+            error(guard.redeem_at(PID, "0123456789abcdef", Some(STARTED), now)).contains("invalid")
+        );
+    }
+
+    #[test]
+    fn tokens_are_distinct() {
+        let guard = ActionGuard::default();
+        let now = Instant::now();
+        let a = issue(&guard, now);
+        let b = issue(&guard, now);
+        assert_ne!(a, b);
+        // 两个都有效，互不影响
+        guard.redeem_at(PID, &b, Some(STARTED), now).unwrap();
+        guard.redeem_at(PID, &a, Some(STARTED), now).unwrap();
+    }
+
+    #[test]
+    fn rejects_other_pid() {
+        let guard = ActionGuard::default();
+        let now = Instant::now();
+        let token = issue(&guard, now);
+        assert!(
+            error(guard.redeem_at(PID + 1, &token, Some(STARTED), now)).contains("another process")
+        );
+        // 用错一次即作废
+        assert!(guard.redeem_at(PID, &token, Some(STARTED), now).is_err());
+    }
+
+    #[test]
+    fn rejects_expired() {
+        let guard = ActionGuard::default();
+        let now = Instant::now();
+        let token = issue(&guard, now);
+        guard
+            .redeem_at(PID, &token, Some(STARTED), now + Duration::from_secs(29))
+            .unwrap();
+
+        let token = issue(&guard, now);
+        assert!(
+            error(guard.redeem_at(PID, &token, Some(STARTED), now + TOKEN_TTL)).contains("expired")
+        );
+    }
+
+    #[test]
+    fn expired_tokens_pruned_on_issue() {
+        let guard = ActionGuard::default();
+        let now = Instant::now();
+        let old = issue(&guard, now);
+        issue(&guard, now + TOKEN_TTL);
+        assert_eq!(guard.pending.lock().unwrap().len(), 1);
+        assert!(error(guard.redeem_at(PID, &old, Some(STARTED), now)).contains("invalid"));
+    }
+
+    #[test]
+    fn rejects_replaced_process() {
+        let guard = ActionGuard::default();
+        let now = Instant::now();
+        // pid 被复用：同一个 pid，启动时间不同
+        let token = issue(&guard, now);
+        assert!(error(guard.redeem_at(PID, &token, Some(STARTED + 5), now)).contains("replaced"));
+        // 进程已退出
+        let token = issue(&guard, now);
+        assert!(error(guard.redeem_at(PID, &token, None, now)).contains("exited"));
+    }
+
+    #[test]
+    fn renice_range() {
+        assert!(renice(PID, 20)
+            .unwrap_err()
+            .to_string()
+            .contains("out of range"));
+        assert!(renice(PID, -21).is_err());
+    }
+}
@@ -0,0 +1,399 @@
+//! 进程列表与详情。
+//!
+//! 进程数据来自后台采样常驻的 `System`（见 `sampler`），CPU% 与磁盘速率都是两次采样之间的值，
+//! 所以命令里不会临时刷新整张进程表；详情命令只对单个进程补充读取环境变量、cwd 等信息。
+//! 发送信号、调整优先级见 [`control`]，套接字表见 [`sockets`]。
+
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, Uid, UpdateKind, Users};
+
+use crate::sampler::Collectors;
+
+pub mod control;
+pub mod sockets;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: Option<u32>,
+    pub name: String,
+    pub cmd: Vec<String>,
+    pub user: Option<String>,
+    /// 单个核心占满为 100，多线程进程可能超过 100
+    pub cpu_usage_percent: f32,
+    pub resident_memory_kb: u64,
+    pub virtual_memory_kb: u64,
+    pub disk_read_bytes_per_sec: u64,
+    pub disk_write_bytes_per_sec: u64,
+    /// 启动时间（unix 秒）
+    pub start_time: u64,
+    /// Run / Sleep / Zombie 等，取自 sysinfo
+    pub state: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProcessDetails {
+    #[serde(flatten)]
+    pub info: ProcessInfo,
+    pub exe: Option<String>,
+    pub cwd: Option<String>,
+    /// `KEY=VALUE` 形式；没有权限读取时为空
+    pub environ: Vec<String>,
+    pub open_files: Option<usize>,
+    pub threads: Option<usize>,
+}
+
+/// 进程树节点，`subtree_*` 为自身加全部子孙进程的合计
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProcessNode {
+    #[serde(flatten)]
+    pub info: ProcessInfo,
+    pub subtree_cpu_usage_percent: f32,
+    pub subtree_resident_memory_kb: u64,
+    pub children: Vec<ProcessNode>,
+}
+
+/// 排序字段：cpu（默认）、memory、disk 按从大到小，pid、name 按从小到大
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortBy {
+    #[default]
+    Cpu,
+    Memory,
+    Disk,
+    Pid,
+    Name,
+}
+
+/// 列出进程；`filter` 按名称 / 命令行做不区分大小写的子串匹配，也可以直接填 pid
+pub fn list(
+    c: &mut Collectors,
+    sort_by: SortBy,
+    filter: Option<&str>,
+    limit: Option<usize>,
+) -> Vec<ProcessInfo> {
+    let filter = filter
+        .map(str::trim)
+        .filter(|f| !f.is_empty())
+        .map(str::to_lowercase);
+    refresh_users_if_needed(c);
+
+    let mut processes: Vec<ProcessInfo> = c
+        .sys
+        .processes()
+        .values()
+        // Linux 下线程也会出现在进程表里
+        .filter(|p| p.thread_kind().is_none())
+        .filter(|p| match &filter {
+            Some(f) => matches(p, f),
+            None => true,
+        })
+        .map(|p| to_info(p, &c.users, c.interval_secs))
+        .collect();
+
+    match sort_by {
+        SortBy::Cpu => {
+            processes.sort_by(|a, b| b.cpu_usage_percent.total_cmp(&a.cpu_usage_percent))
+        }
+        SortBy::Memory => processes.sort_by_key(|p| Reverse(p.resident_memory_kb)),
+        SortBy::Disk => processes
+            .sort_by_key(|p| Reverse(p.disk_read_bytes_per_sec + p.disk_write_bytes_per_sec)),
+        SortBy::Pid => processes.sort_by_key(|p| p.pid),
+        SortBy::Name => processes.sort_by_key(|p| p.name.to_lowercase()),
+    }
+    if let Some(limit) = limit {
+        processes.truncate(limit);
+    }
+    processes
+}
+
+/// 按父子关系组织的进程树；父进程不在表中（已退出或无权限查看）的进程作为根节点
+pub fn tree(c: &mut Collectors) -> Vec<ProcessNode> {
+    build_tree(list(c, SortBy::Pid, None, None))
+}
+
+/// 由进程列表建树，根节点按 pid 排序
+fn build_tree(all: Vec<ProcessInfo>) -> Vec<ProcessNode> {
+    let pids: HashSet<u32> = all.iter().map(|p| p.pid).collect();
+
+    let mut by_parent: HashMap<u32, Vec<ProcessInfo>> = HashMap::new();
+    let mut roots = Vec::new();
+    for p in all {
+        match p.ppid.filter(|ppid| *ppid != p.pid && pids.contains(ppid)) {
+            Some(ppid) => by_parent.entry(ppid).or_default().push(p),
+            None => roots.push(p),
+        }
+    }
+
+    let mut nodes: Vec<ProcessNode> = roots
+        .into_iter()
+        .map(|p| build_node(p, &mut by_parent))
+        .collect();
+    // 两次读取之间 pid 被复用时父子关系可能成环，环上的进程从任何根都走不到，
+    // 取剩下 pid 最小的作为根，保证每个进程都出现一次
+    while let Some(p) = take_lowest(&mut by_parent) {
+        nodes.push(build_node(p, &mut by_parent));
+    }
+    nodes.sort_by_key(|n| n.info.pid);
+    nodes
+}
+
+fn take_lowest(by_parent: &mut HashMap<u32, Vec<ProcessInfo>>) -> Option<ProcessInfo> {
+    let (&ppid, index) = by_parent
+        .iter()
+        .flat_map(|(ppid, children)| {
+            children
+                .iter()
+                .enumerate()
+                .map(move |(i, c)| (ppid, i, c.pid))
+        })
+        .min_by_key(|(_, _, pid)| *pid)
+        .map(|(ppid, i, _)| (ppid, i))?;
+    let children = by_parent.get_mut(&ppid)?;
+    let p = children.remove(index);
+    if children.is_empty() {
+        by_parent.remove(&ppid);
+    }
+    Some(p)
+}
+
+fn build_node(info: ProcessInfo, by_parent: &mut HashMap<u32, Vec<ProcessInfo>>) -> ProcessNode {
+    // 取出后即从表中移除，保证每个进程只出现一次
+    let children: Vec<ProcessNode> = by_parent
+        .remove(&info.pid)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|child| build_node(child, by_parent))
+        .collect();
+    ProcessNode {
+        subtree_cpu_usage_percent: info.cpu_usage_percent
+            + children
+                .iter()
+                .map(|c| c.subtree_cpu_usage_percent)
+                .sum::<f32>(),
+        subtree_resident_memory_kb: info.resident_memory_kb
+            + children
+                .iter()
+                .map(|c| c.subtree_resident_memory_kb)
+                .sum::<u64>(),
+        info,
+        children,
+    }
+}
+
+/// 单个进程的详情，进程不存在时返回 None
+pub fn details(c: &mut Collectors, pid: u32) -> Option<ProcessDetails> {
+    let pid = Pid::from_u32(pid);
+    // 不刷新 CPU，避免打断采样线程计算 CPU% 的区间
+    c.sys.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[pid]),
+        false,
+        ProcessRefreshKind::nothing()
+            .with_memory()
+            .with_user(UpdateKind::OnlyIfNotSet)
+            .with_cmd(UpdateKind::OnlyIfNotSet)
+            .with_exe(UpdateKind::OnlyIfNotSet)
+            .with_cwd(UpdateKind::Always)
+            .with_environ(UpdateKind::Always)
+            .with_tasks(),
+    );
+
+    refresh_users_if_needed(c);
+
+    let p = c.sys.process(pid)?;
+    Some(ProcessDetails {
+        info: to_info(p, &c.users, c.interval_secs),
+        exe: p.exe().map(|e| e.to_string_lossy().into_owned()),
+        cwd: p.cwd().map(|d| d.to_string_lossy().into_owned()),
+        environ: p
+            .environ()
+            .iter()
+            .map(|e| e.to_string_lossy().into_owned())
+            .collect(),
+        open_files: p.open_files(),
+        threads: p.tasks().map(|t| t.len()),
+    })
+}
+
+fn matches(p: &Process, filter: &str) -> bool {
+    if filter
+        .parse::<u32>()
+        .is_ok_and(|pid| pid == p.pid().as_u32())
+    {
+        return true;
+    }
+    p.name().to_string_lossy().to_lowercase().contains(filter)
+        || p.cmd()
+            .iter()
+            .any(|arg| arg.to_string_lossy().to_lowercase().contains(filter))
+}
+
+fn to_info(p: &Process, users: &Users, interval_secs: f64) -> ProcessInfo {
+    let disk = p.disk_usage();
+    let rate = |bytes: u64| (bytes as f64 / interval_secs.max(0.001)) as u64;
+    ProcessInfo {
+        pid: p.pid().as_u32(),
+        ppid: p.parent().map(|pid| pid.as_u32()),
+        name: p.name().to_string_lossy().into_owned(),
+        cmd: p
+            .cmd()
+            .iter()
+            .map(|arg| arg.to_string_lossy().into_owned())
+            .collect(),
+        user: p
+            .user_id()
+            .and_then(|uid| users.get_user_by_id(uid))
+            .map(|u| u.name().to_string()),
+        cpu_usage_percent: p.cpu_usage(),
+        resident_memory_kb: p.memory() / 1024,
+        virtual_memory_kb: p.virtual_memory() / 1024,
+        disk_read_bytes_per_sec: rate(disk.read_bytes),
+        disk_write_bytes_per_sec: rate(disk.written_bytes),
+        start_time: p.start_time(),
+        state: p.status().to_string(),
+    }
+}
+
+/// 进程表里出现未知 uid（例如新建的用户）时刷新一次用户表；
+/// 刷新后仍查不到的 uid（容器里常见，没有 passwd 条目）记下来，之后不再为它们刷新
+fn refresh_users_if_needed(c: &mut Collectors) {
+    let uids = c.sys.processes().values().filter_map(|p| p.user_id());
+    let missing = missing_uids(uids, &c.users, &c.unresolved_uids);
+    if missing.is_empty() {
+        return;
+    }
+    c.users.refresh();
+    let still_missing = missing_uids(&missing, &c.users, &HashSet::new());
+    c.unresolved_uids.extend(still_missing);
+}
+
+/// 用户表里查不到、也不在 `unresolved` 中的 uid
+fn missing_uids<'a>(
+    uids: impl IntoIterator<Item = &'a Uid>,
+    users: &Users,
+    unresolved: &HashSet<Uid>,
+) -> HashSet<Uid> {
+    uids.into_iter()
+        .filter(|uid| !unresolved.contains(*uid) && users.get_user_by_id(uid).is_none())
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(pid: u32, ppid: Option<u32>, cpu: f32, rss_kb: u64) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            ppid,
+            name: format!("p{}", pid),
+            cmd: Vec::new(),
+            user: None,
+            cpu_usage_percent: cpu,
+            resident_memory_kb: rss_kb,
+            virtual_memory_kb: 0,
+            disk_read_bytes_per_sec: 0,
+            disk_write_bytes_per_sec: 0,
+            start_time: 0,
+            state: "Sleep".to_string(),
+        }
+    }
+
+    /// (pid, 子树 CPU%, 子树内存, 子节点) 的缩进文本，便于整棵树比较
+    fn render(nodes: &[ProcessNode], depth: usize, out: &mut Vec<String>) {
+        for n in nodes {
+            out.push(format!(
+                "{}{} {} {}",
+                "  ".repeat(depth),
+                n.info.pid,
+                n.subtree_cpu_usage_percent,
+                n.subtree_resident_memory_kb
+            ));
+            render(&n.children, depth + 1, out);
+        }
+    }
+
+    fn rendered(all: Vec<ProcessInfo>) -> Vec<String> {
+        let mut out = Vec::new();
+        render(&build_tree(all), 0, &mut out);
+        out
+    }
+
+    #[test]
+    fn tree_with_subtree_totals() {
+        let all = vec![
+            info(1, None, 1.0, 100),
+            info(200, Some(1), 2.0, 200),
+            info(201, Some(200), 4.0, 400),
+            info(202, Some(200), 8.0, 800),
+            info(300, Some(1), 16.0, 1600),
+        ];
+        assert_eq!(
+            rendered(all),
+            [
+                "1 31 3100",
+                "  200 14 1400",
+                "    201 4 400",
+                "    202 8 800",
+                "  300 16 1600",
+            ]
+        );
+    }
+
+    #[test]
+    fn orphans_become_roots() {
+        // 900 的父进程不在表中，2 的父进程是 0（内核线程），5 的父进程是自己
+        let all = vec![
+            info(1, Some(0), 0.0, 10),
+            info(2, Some(0), 0.0, 0),
+            info(5, Some(5), 0.0, 5),
+            info(900, Some(899), 1.0, 10),
+            info(901, Some(900), 1.0, 10),
+        ];
+        assert_eq!(
+            rendered(all),
+            ["1 0 10", "2 0 0", "5 0 5", "900 2 20", "  901 1 10"]
+        );
+    }
+
+    #[test]
+    fn cycles_do_not_drop_processes() {
+        // 10 -> 11 -> 12 -> 10 成环，13 挂在环上
+        let all = vec![
+            info(1, None, 0.0, 1),
+            info(10, Some(12), 1.0, 1),
+            info(11, Some(10), 1.0, 1),
+            info(12, Some(11), 1.0, 1),
+            info(13, Some(11), 1.0, 1),
+        ];
+        let lines = rendered(all);
+        assert_eq!(
+            lines,
+            ["1 0 1", "10 4 4", "  11 3 3", "    12 1 1", "    13 1 1"]
+        );
+    }
+
+    #[test]
+    fn unresolved_uids_remembered() {
+        let users = Users::new();
+        let uids: Vec<Uid> = ["1000", "100000"]
+            .iter()
+            .map(|u| u.parse().unwrap())
+            .collect();
+        let mut unresolved = HashSet::new();
+        let missing = missing_uids(&uids, &users, &unresolved);
+        assert_eq!(missing.len(), 2);
+
+        unresolved.extend(missing);
+        assert!(missing_uids(&uids, &users, &unresolved).is_empty());
+        let new_uid: Uid = "1001".parse().unwrap();
+        assert_eq!(
+            missing_uids([&new_uid], &users, &unresolved),
+            HashSet::from([new_uid.clone()])
+        );
+    }
+}
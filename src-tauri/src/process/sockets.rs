@@ -0,0 +1,180 @@
+//! TCP / UDP 套接字表：平台实现列出全部套接字及其所属进程，这里只负责过滤与排序。
+//!
+//! UDP 没有连接状态，与 `ss` 一样把未连接的记为 "UNCONN"、`connect()` 过的记为 "ESTABLISHED"；
+//! 按 "LISTEN" 过滤时未连接的 UDP 套接字一并返回，它们同样在等待任意来源的数据。
+//! 其他用户进程的套接字需要 root 才能对应到 pid，拿不到时 `pid` 为 None。
+
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SocketInfo {
+    /// "tcp" / "udp"
+    pub protocol: String,
+    /// "ipv4" / "ipv6"
+    pub family: String,
+    /// 监听所有地址时为 0.0.0.0 或 ::
+    pub local_address: IpAddr,
+    pub local_port: u16,
+    /// 未连接（监听中、未 connect 的 UDP）时为 None
+    pub remote_address: Option<IpAddr>,
+    pub remote_port: Option<u16>,
+    /// TCP 状态名（LISTEN、ESTABLISHED、TIME_WAIT 等），UDP 为 UNCONN / ESTABLISHED
+    pub state: String,
+    pub pid: Option<u32>,
+    pub process_name: Option<String>,
+}
+
+/// `list_sockets` 的过滤条件，都为空时返回全部
+#[derive(Debug, Default)]
+pub struct SocketFilter {
+    /// "tcp" / "udp"
+    pub protocol: Option<String>,
+    /// 不区分大小写
+    pub state: Option<String>,
+    /// 匹配本地或远端端口
+    pub port: Option<u16>,
+}
+
+impl SocketFilter {
+    fn matches(&self, s: &SocketInfo) -> bool {
+        let protocol = self
+            .protocol
+            .as_deref()
+            .is_none_or(|p| p.eq_ignore_ascii_case(&s.protocol));
+        let state = self.state.as_deref().is_none_or(|st| {
+            st.eq_ignore_ascii_case(&s.state)
+                || (st.eq_ignore_ascii_case("LISTEN") && s.state == "UNCONN")
+        });
+        let port = self
+            .port
+            .is_none_or(|p| s.local_port == p || s.remote_port == Some(p));
+        protocol && state && port
+    }
+}
+
+/// 过滤后按协议、本地端口、pid 排序
+pub fn filter(sockets: Vec<SocketInfo>, f: &SocketFilter) -> Vec<SocketInfo> {
+    let mut sockets: Vec<SocketInfo> = sockets.into_iter().filter(|s| f.matches(s)).collect();
+    sockets.sort_by(|a, b| {
+        (&a.protocol, a.local_port, a.pid, &a.family).cmp(&(
+            &b.protocol,
+            b.local_port,
+            b.pid,
+            &b.family,
+        ))
+    });
+    sockets
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn socket(
+        protocol: &str,
+        local_port: u16,
+        remote_port: Option<u16>,
+        state: &str,
+        pid: Option<u32>,
+    ) -> SocketInfo {
+        SocketInfo {
+            protocol: protocol.to_string(),
+            family: "ipv4".to_string(),
+            local_address: "127.0.0.1".parse().unwrap(),
+            local_port,
+            remote_address: remote_port.map(|_| "127.0.0.1".parse().unwrap()),
+            remote_port,
+            state: state.to_string(),
+            pid,
+            process_name: None,
+        }
+    }
+
+    fn sockets() -> Vec<SocketInfo> {
+        vec![
+            socket("udp", 5353, None, "UNCONN", Some(903)),
+            socket("tcp", 8080, Some(53122), "ESTABLISHED", Some(1523)),
+            socket("tcp", 8080, None, "LISTEN", Some(1523)),
+            socket("tcp", 53122, Some(8080), "ESTABLISHED", Some(3001)),
+            socket("udp", 59601, Some(53), "ESTABLISHED", None),
+            socket("tcp", 22, None, "LISTEN", Some(845)),
+        ]
+    }
+
+    fn ports(sockets: &[SocketInfo]) -> Vec<(&str, u16)> {
+        sockets
+            .iter()
+            .map(|s| (s.protocol.as_str(), s.local_port))
+            .collect()
+    }
+
+    #[test]
+    fn no_filter_sorts() {
+        let all = filter(sockets(), &SocketFilter::default());
+        assert_eq!(
+            ports(&all),
+            [
+                ("tcp", 22),
+                ("tcp", 8080),
+                ("tcp", 8080),
+                ("tcp", 53122),
+                ("udp", 5353),
+                ("udp", 59601),
+            ]
+        );
+    }
+
+    #[test]
+    fn by_protocol_and_state() {
+        let udp = SocketFilter {
+            protocol: Some("UDP".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            ports(&filter(sockets(), &udp)),
+            [("udp", 5353), ("udp", 59601)]
+        );
+
+        // 未连接的 UDP 也算在监听
+        let listen = SocketFilter {
+            state: Some("listen".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            ports(&filter(sockets(), &listen)),
+            [("tcp", 22), ("tcp", 8080), ("udp", 5353)]
+        );
+
+        let established_tcp = SocketFilter {
+            protocol: Some("tcp".to_string()),
+            state: Some("ESTABLISHED".to_string()),
+            port: None,
+        };
+        assert_eq!(
+            ports(&filter(sockets(), &established_tcp)),
+            [("tcp", 8080), ("tcp", 53122)]
+        );
+    }
+
+    #[test]
+    fn by_local_or_remote_port() {
+        let port = SocketFilter {
+            port: Some(8080),
+            ..Default::default()
+        };
+        let matched = filter(sockets(), &port);
+        assert_eq!(
+            ports(&matched),
+            [("tcp", 8080), ("tcp", 8080), ("tcp", 53122)]
+        );
+        assert_eq!(matched[2].remote_port, Some(8080));
+
+        let dns = SocketFilter {
+            port: Some(53),
+            ..Default::default()
+        };
+        assert_eq!(ports(&filter(sockets(), &dns)), [("udp", 59601)]);
+    }
+}
@@ -0,0 +1,395 @@
+//! 后台采样：在 `run()` 的 setup 中启动，常驻一份 `System`/`Disks`/`Networks`，
+//! 按固定间隔刷新并通过 `metrics://tick` 事件推送给前端。
+//!
+//! sysinfo 的 CPU 使用率需要两次间隔至少 `MINIMUM_CPU_UPDATE_INTERVAL` 的刷新才准确，
+//! 所以不能在命令里临时 new 一个 `System` 来算；命令只读取最近一次的快照。
+
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use sysinfo::{
+    DiskKind, Disks, Networks, ProcessRefreshKind, ProcessesToUpdate, System, Uid, UpdateKind,
+    Users, MINIMUM_CPU_UPDATE_INTERVAL,
+};
+use tauri::{AppHandle, Emitter};
+
+use crate::disk::{self, DiskCounters};
+use crate::history::{MetricsHistory, DEFAULT_CAPACITY};
+use crate::network::{self, NetCounters};
+use crate::platform::{Platform, SharedPlatform};
+use crate::store::SharedStore;
+use crate::{DiskInfo, SystemMetrics};
+
+/// 每次采样后推送给前端的事件名
+pub const TICK_EVENT: &str = "metrics://tick";
+
+pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(1000);
+pub const MAX_INTERVAL: Duration = Duration::from_secs(60);
+/// 电池电量的刷新间隔，比采样间隔慢得多
+pub const BATTERY_INTERVAL: Duration = Duration::from_secs(30);
+
+pub type SharedSampler = Arc<Sampler>;
+
+/// 常驻的 sysinfo 对象，只在采样时加锁刷新
+pub struct Collectors {
+    pub sys: System,
+    pub disks: Disks,
+    pub networks: Networks,
+    pub users: Users,
+    /// 刷新用户表后仍解析不到的 uid，不再为它们反复刷新
+    pub unresolved_uids: HashSet<Uid>,
+    /// 最近一次采样与上一次之间的秒数，进程磁盘读写量按它换算成速率
+    pub interval_secs: f64,
+    last_refresh: Instant,
+}
+
+/// 只有采样线程使用的状态，与 `Collectors` 分开加锁
+#[derive(Default)]
+struct SampleState {
+    /// 上一次的块设备累计计数（设备名 -> 计数）
+    disk_counters: HashMap<String, DiskCounters>,
+    /// 上一次的网络接口累计计数（接口名 -> 计数）
+    net_counters: HashMap<String, NetCounters>,
+    /// 应用启动后每个接口第一次读到的计数，用来算本次运行以来的流量
+    net_baseline: HashMap<String, NetCounters>,
+    /// 上一次读取电池的时间与结果
+    battery: Option<(Instant, Option<f32>)>,
+}
+
+pub struct Sampler {
+    collectors: Mutex<Collectors>,
+    state: Mutex<SampleState>,
+    latest: RwLock<Option<SystemMetrics>>,
+    history: MetricsHistory,
+    interval_ms: AtomicU64,
+}
+
+impl Sampler {
+    pub fn new(interval: Duration) -> Self {
+        let mut sys = System::new_all();
+        // 第一次刷新作为 CPU 使用率的基准，真正的数据从下一次采样开始
+        sys.refresh_cpu_usage();
+
+        let sampler = Self {
+            collectors: Mutex::new(Collectors {
+                sys,
+                disks: Disks::new_with_refreshed_list(),
+                networks: Networks::new_with_refreshed_list(),
+                users: Users::new_with_refreshed_list(),
+                unresolved_uids: HashSet::new(),
+                interval_secs: interval.as_secs_f64(),
+                last_refresh: Instant::now(),
+            }),
+            state: Mutex::new(SampleState::default()),
+            latest: RwLock::new(None),
+            history: MetricsHistory::new(DEFAULT_CAPACITY),
+            interval_ms: AtomicU64::new(0),
+        };
+        sampler.set_interval(interval);
+        sampler
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.interval_ms.load(Ordering::Relaxed))
+    }
+
+    /// 设置采样间隔，限制在 [MINIMUM_CPU_UPDATE_INTERVAL, MAX_INTERVAL] 内，返回实际生效的值
+    pub fn set_interval(&self, interval: Duration) -> Duration {
+        let interval = interval.clamp(MINIMUM_CPU_UPDATE_INTERVAL, MAX_INTERVAL);
+        self.interval_ms
+            .store(interval.as_millis() as u64, Ordering::Relaxed);
+        interval
+    }
+
+    /// 最近一次采样结果，采样线程还没跑完第一轮时为 None
+    pub fn latest(&self) -> Option<SystemMetrics> {
+        self.latest.read().unwrap().clone()
+    }
+
+    pub fn history(&self) -> &MetricsHistory {
+        &self.history
+    }
+
+    /// 借用常驻的 sysinfo 对象（进程列表等），持锁期间采样的 sysinfo 刷新会等待
+    pub fn collectors(&self) -> MutexGuard<'_, Collectors> {
+        self.collectors.lock().unwrap()
+    }
+
+    /// 刷新一次并更新快照（阻塞调用，平台温度读取可能需要数百毫秒）
+    ///
+    /// `collectors` 只在刷新 sysinfo 时持有；之后的平台调用（macOS 上要执行 powermetrics、
+    /// pmset 等命令）不持锁，进程相关的命令不会被它们拖住
+    pub fn sample(&self, platform: &dyn Platform) -> SystemMetrics {
+        let mut c = self.collectors.lock().unwrap();
+        c.sys.refresh_cpu_usage();
+        c.sys.refresh_memory();
+        c.disks.refresh(true);
+        c.networks.refresh(true);
+        // 进程 CPU% 同样依赖两次刷新之间的间隔，跟随采样一起刷新；线程不作为单独的进程列出
+        c.sys.refresh_processes_specifics(
+            ProcessesToUpdate::All,
+            true,
+            ProcessRefreshKind::nothing()
+                .with_cpu()
+                .with_memory()
+                .with_disk_usage()
+                .with_user(UpdateKind::OnlyIfNotSet)
+                .with_cmd(UpdateKind::OnlyIfNotSet)
+                .with_exe(UpdateKind::OnlyIfNotSet),
+        );
+        let now = Instant::now();
+        let elapsed = elapsed_secs(c.last_refresh, now);
+        c.last_refresh = now;
+        c.interval_secs = elapsed;
+
+        // 所属块设备与 inode 数分别依赖平台计数和 statvfs，释放锁之后再填
+        let disks = c
+            .disks
+            .iter()
+            .map(|d| DiskInfo {
+                name: d.name().to_string_lossy().into_owned(),
+                mount_point: d.mount_point().to_string_lossy().into_owned(),
+                device: None,
+                total: d.total_space(),
+                available: d.available_space(),
+                file_system: d.file_system().to_string_lossy().into_owned(),
+                kind: match d.kind() {
+                    DiskKind::SSD => Some("SSD".to_string()),
+                    DiskKind::HDD => Some("HDD".to_string()),
+                    DiskKind::Unknown(_) => None,
+                },
+                is_removable: d.is_removable(),
+                is_read_only: d.is_read_only(),
+                inodes_total: None,
+                inodes_free: None,
+            })
+            .collect::<Vec<_>>();
+
+        // 同一块设备可能挂载多次，按设备名去重后再累加读写量
+        let mut seen = std::collections::HashSet::new();
+        let (read, written) = c
+            .disks
+            .iter()
+            .filter(|d| seen.insert(d.name().to_os_string()))
+            .fold((0u64, 0u64), |(r, w), d| {
+                let usage = d.usage();
+                (r + usage.read_bytes, w + usage.written_bytes)
+            });
+
+        let (rx, tx) = c.networks.iter().fold((0u64, 0u64), |(rx, tx), (_, data)| {
+            (rx + data.received(), tx + data.transmitted())
+        });
+
+        let mut metrics = SystemMetrics {
+            timestamp_ms: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_millis() as u64,
+            cpu_usage_percent: c.sys.global_cpu_usage(),
+            per_core_usage: c.sys.cpus().iter().map(|cpu| cpu.cpu_usage()).collect(),
+            total_memory_kb: c.sys.total_memory() / 1024,
+            used_memory_kb: c.sys.used_memory() / 1024,
+            total_swap_kb: c.sys.total_swap() / 1024,
+            used_swap_kb: c.sys.used_swap() / 1024,
+            disk_read_bytes_per_sec: per_sec(read, elapsed),
+            disk_write_bytes_per_sec: per_sec(written, elapsed),
+            network_rx_bytes_per_sec: per_sec(rx, elapsed),
+            network_tx_bytes_per_sec: per_sec(tx, elapsed),
+            disks,
+            disk_io: Vec::new(),
+            net_io: Vec::new(),
+            temps: None,
+            power: None,
+            battery_percent: None,
+        };
+        drop(c);
+
+        let mut state = self.state.lock().unwrap();
+
+        // 块设备计数拿不到时（容器、未支持的平台）只是没有分设备的数据
+        let counters = platform.disk_counters().unwrap_or_default();
+        metrics.disk_io = disk::rates(&state.disk_counters, &counters, elapsed);
+        let device_of: HashMap<&str, &str> = counters
+            .iter()
+            .flat_map(|d| {
+                d.mount_points
+                    .iter()
+                    .map(|m| (m.as_str(), d.device.as_str()))
+            })
+            .collect();
+        for d in &mut metrics.disks {
+            d.device = device_of.get(d.mount_point.as_str()).map(|s| s.to_string());
+            let inodes = disk::inodes(Path::new(&d.mount_point));
+            d.inodes_total = inodes.map(|(total, _)| total);
+            d.inodes_free = inodes.map(|(_, free)| free);
+        }
+        state.disk_counters = counters
+            .into_iter()
+            .map(|d| (d.device.clone(), d))
+            .collect();
+
+        let counters = platform.net_counters().unwrap_or_default();
+        let SampleState {
+            net_counters,
+            net_baseline,
+            ..
+        } = &mut *state;
+        metrics.net_io = network::rates(net_counters, net_baseline, &counters, elapsed);
+        state.net_counters = counters
+            .into_iter()
+            .map(|n| (n.interface.clone(), n))
+            .collect();
+
+        metrics.battery_percent = state.battery_percent(platform, now);
+        drop(state);
+
+        metrics.temps = platform.temps();
+        metrics.power = platform.power();
+
+        self.history.record(&metrics);
+        *self.latest.write().unwrap() = Some(metrics.clone());
+        metrics
+    }
+}
+
+impl SampleState {
+    /// 电量变化很慢，而 macOS 上每次读取都要执行 pmset 和 ioreg，所以按 `BATTERY_INTERVAL` 缓存
+    fn battery_percent(&mut self, platform: &dyn Platform, now: Instant) -> Option<f32> {
+        if let Some((at, percent)) = self.battery {
+            if now.saturating_duration_since(at) < BATTERY_INTERVAL {
+                return percent;
+            }
+        }
+        let percent = battery_percent(platform);
+        self.battery = Some((now, percent));
+        percent
+    }
+}
+
+/// 两次采样之间的秒数，至少 1ms，避免两次刷新挨得太近时除以 0
+fn elapsed_secs(last: Instant, now: Instant) -> f64 {
+    now.saturating_duration_since(last).as_secs_f64().max(0.001)
+}
+
+/// 区间内的字节数换算成每秒速率
+fn per_sec(bytes: u64, elapsed_secs: f64) -> u64 {
+    (bytes as f64 / elapsed_secs) as u64
+}
+
+/// 所有电池电量的平均值，没有电池时为 None
+fn battery_percent(platform: &dyn Platform) -> Option<f32> {
+    let info = platform.battery().ok()?;
+    let values: Vec<f32> = info.batteries.iter().filter_map(|b| b.percentage).collect();
+    (!values.is_empty()).then(|| values.iter().sum::<f32>() / values.len() as f32)
+}
+
+/// 启动后台采样任务：每个间隔采样一次，写入磁盘存储并发送 `metrics://tick`
+pub fn spawn(
+    handle: AppHandle,
+    sampler: SharedSampler,
+    platform: SharedPlatform,
+    store: SharedStore,
+) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(sampler.interval()).await;
+
+            let s = sampler.clone();
+            let p = platform.clone();
+            let st = store.clone();
+            let metrics = match tauri::async_runtime::spawn_blocking(move || {
+                let m = s.sample(p.as_ref());
+                if let Err(e) = st.insert(&m) {
+                    log::warn!("failed to persist metrics sample: {:?}", e);
+                }
+                m
+            })
+            .await
+            {
+                Ok(m) => m,
+                Err(e) => {
+                    log::error!("metrics sampling task failed: {:?}", e);
+                    continue;
+                }
+            };
+
+            if let Err(e) = handle.emit(TICK_EVENT, metrics) {
+                log::warn!("failed to emit {}: {}", TICK_EVENT, e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::platform::FixturePlatform;
+
+    fn write_battery(dir: &Path, percentages: &[Option<f32>]) {
+        let batteries: Vec<serde_json::Value> = percentages
+            .iter()
+            .map(|p| serde_json::json!({ "state": "Discharging", "percentage": p }))
+            .collect();
+        let info = serde_json::json!({ "batteries": batteries, "timestamp_unix": 0 });
+        fs::write(dir.join("battery.json"), info.to_string()).unwrap();
+    }
+
+    #[test]
+    fn rates_use_elapsed_seconds() {
+        let last = Instant::now();
+        let elapsed = elapsed_secs(last, last + Duration::from_millis(500));
+        assert_eq!(elapsed, 0.5);
+        assert_eq!(per_sec(1000, elapsed), 2000);
+        assert_eq!(per_sec(0, elapsed), 0);
+
+        // 两次刷新挨在一起（或时钟倒退）时按 1ms 算，不会除以 0
+        assert_eq!(elapsed_secs(last, last), 0.001);
+        assert_eq!(elapsed_secs(last + Duration::from_secs(1), last), 0.001);
+        assert_eq!(per_sec(5, elapsed_secs(last, last)), 5000);
+    }
+
+    #[test]
+    fn battery_averaged() {
+        let dir = tempfile::tempdir().unwrap();
+        let p = FixturePlatform::new(dir.path());
+        assert_eq!(battery_percent(&p), None);
+
+        write_battery(dir.path(), &[]);
+        assert_eq!(battery_percent(&p), None);
+
+        write_battery(dir.path(), &[Some(80.0), None, Some(60.0)]);
+        assert_eq!(battery_percent(&p), Some(70.0));
+    }
+
+    #[test]
+    fn battery_cached_for_interval() {
+        let dir = tempfile::tempdir().unwrap();
+        let p = FixturePlatform::new(dir.path());
+        let mut state = SampleState::default();
+        let t0 = Instant::now();
+
+        write_battery(dir.path(), &[Some(80.0)]);
+        assert_eq!(state.battery_percent(&p, t0), Some(80.0));
+
+        // 间隔内不重新读取，文件变了也还是上一次的结果
+        write_battery(dir.path(), &[Some(50.0)]);
+        let just_before = t0 + BATTERY_INTERVAL - Duration::from_millis(1);
+        assert_eq!(state.battery_percent(&p, just_before), Some(80.0));
+
+        let t1 = t0 + BATTERY_INTERVAL;
+        assert_eq!(state.battery_percent(&p, t1), Some(50.0));
+
+        // 读取失败也缓存，间隔内不反复重试
+        fs::remove_file(dir.path().join("battery.json")).unwrap();
+        let t2 = t1 + BATTERY_INTERVAL;
+        assert_eq!(state.battery_percent(&p, t2), None);
+        write_battery(dir.path(), &[Some(40.0)]);
+        assert_eq!(state.battery_percent(&p, t2 + Duration::from_secs(1)), None);
+        assert_eq!(state.battery_percent(&p, t2 + BATTERY_INTERVAL), Some(40.0));
+    }
+}
@@ -0,0 +1,555 @@
+//! 磁盘健康：NVMe SMART / Health 日志页、ATA SMART 属性与 `smartctl --json` 的解析和判定。
+//!
+//! NVMe 日志页（Log Identifier 02h）固定 512 字节，多字节字段为小端，计数器为 128 位；
+//! "Data Units" 以 1000 个 512 字节为单位。ATA SMART READ DATA 同为 512 字节，
+//! 从偏移 2 开始是 30 个 12 字节的属性项；阈值来自 SMART READ THRESHOLDS，布局相同。
+//!
+//! 判定结果统一为 "good" / "warning" / "critical" / "unknown"：
+//! 控制器报告的 critical warning、属性值跌破阈值、寿命用尽为 critical；
+//! 出现介质错误、重映射或待映射扇区、寿命超过 90% 为 warning。
+
+use anyhow::Context;
+use serde_json::Value;
+
+use crate::{DiskHealth, SmartAttribute};
+
+/// NVMe 日志页大小
+pub const NVME_LOG_SIZE: usize = 512;
+/// ATA SMART 数据页大小
+pub const ATA_SMART_SIZE: usize = 512;
+
+/// "Data Units" 的单位：1000 个 512 字节扇区
+const NVME_DATA_UNIT: u64 = 512 * 1000;
+
+/// critical warning 各位的含义
+const NVME_CRITICAL_WARNINGS: &[&str] = &[
+    "available spare below threshold",
+    "temperature outside threshold",
+    "reliability degraded",
+    "media placed in read-only mode",
+    "volatile memory backup failed",
+    "persistent memory region read-only",
+];
+
+/// 原始值非 0 即说明介质已有坏块的 ATA 属性
+const ATA_DEFECT_ATTRIBUTES: &[u8] = &[5, 196, 197, 198];
+
+impl DiskHealth {
+    pub fn new(device: &str) -> Self {
+        Self {
+            device: device.to_string(),
+            verdict: "unknown".to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+/// 解析 NVMe SMART / Health 日志页，结果写入 `health` 并给出判定
+pub fn apply_nvme_log(health: &mut DiskHealth, page: &[u8]) -> anyhow::Result<()> {
+    if page.len() < NVME_LOG_SIZE {
+        anyhow::bail!("nvme health log too short: {} bytes", page.len());
+    }
+    let u16_at = |o: usize| u16::from_le_bytes([page[o], page[o + 1]]);
+    // 128 位计数器在实际设备上不会超过 u64，超出时饱和
+    let u128_at = |o: usize| {
+        let v = u128::from_le_bytes(page[o..o + 16].try_into().unwrap());
+        u64::try_from(v).unwrap_or(u64::MAX)
+    };
+
+    let critical_warning = page[0];
+    health.protocol = Some("nvme".to_string());
+    // 复合温度以开尔文表示，0 表示未报告
+    health.temperature_c = Some(u16_at(1)).filter(|k| *k > 0).map(|k| k as f32 - 273.0);
+    health.available_spare = Some(page[3] as u32);
+    health.available_spare_threshold = Some(page[4] as u32);
+    health.percentage_used = Some(page[5] as u32);
+    health.data_read_bytes = Some(u128_at(32).saturating_mul(NVME_DATA_UNIT));
+    health.data_written_bytes = Some(u128_at(48).saturating_mul(NVME_DATA_UNIT));
+    health.power_cycles = Some(u128_at(112));
+    health.power_on_hours = Some(u128_at(128));
+    health.unsafe_shutdowns = Some(u128_at(144));
+    health.media_errors = Some(u128_at(160));
+    health.error_log_entries = Some(u128_at(176));
+    judge(health, Some(critical_warning), None);
+    Ok(())
+}
+
+/// 解析 ATA SMART READ DATA（及可选的阈值页），结果写入 `health` 并给出判定
+pub fn apply_ata_smart(
+    health: &mut DiskHealth,
+    values: &[u8],
+    thresholds: Option<&[u8]>,
+) -> anyhow::Result<()> {
+    if values.len() < ATA_SMART_SIZE {
+        anyhow::bail!("ata smart data too short: {} bytes", values.len());
+    }
+    let threshold_of = |id: u8| {
+        let page = thresholds.filter(|t| t.len() >= ATA_SMART_SIZE)?;
+        page[2..362]
+            .chunks_exact(12)
+            .find(|entry| entry[0] == id)
+            .map(|entry| entry[1])
+    };
+
+    let attributes: Vec<SmartAttribute> = values[2..362]
+        .chunks_exact(12)
+        .filter(|entry| entry[0] != 0)
+        .map(|entry| {
+            let id = entry[0];
+            let mut raw = [0u8; 8];
+            raw[..6].copy_from_slice(&entry[5..11]);
+            let threshold = threshold_of(id);
+            SmartAttribute {
+                id,
+                name: ata_attribute_name(id).to_string(),
+                value: entry[3],
+                worst: entry[4],
+                threshold,
+                raw: u64::from_le_bytes(raw),
+                // 阈值为 0 的属性只用于展示，永远不会“失败”
+                failing: threshold.is_some_and(|t| t > 0 && entry[3] <= t),
+            }
+        })
+        .collect();
+
+    health.protocol = Some("ata".to_string());
+    apply_ata_attributes(health, attributes);
+    judge(health, None, None);
+    Ok(())
+}
+
+/// 从 ATA 属性表中提取通用字段
+fn apply_ata_attributes(health: &mut DiskHealth, attributes: Vec<SmartAttribute>) {
+    let raw = |id: u8| attributes.iter().find(|a| a.id == id).map(|a| a.raw);
+    let value = |id: u8| attributes.iter().find(|a| a.id == id).map(|a| a.value);
+
+    // 194 的原始值低 16 位为当前温度，高位是厂商自定义的最低 / 最高温度
+    health.temperature_c = raw(194).or_else(|| raw(190)).map(|r| (r & 0xffff) as f32);
+    // 9 号属性部分厂商以分钟或半小时计，这里按最常见的小时处理
+    health.power_on_hours = raw(9).map(|r| r & 0xffff_ffff);
+    health.power_cycles = raw(12);
+    health.unsafe_shutdowns = raw(192).or_else(|| raw(174));
+    health.media_errors = raw(187).or_else(|| raw(198));
+    // 241 Total_LBAs_Written 以 512 字节扇区计
+    health.data_written_bytes = raw(241).map(|r| r.saturating_mul(512));
+    health.data_read_bytes = raw(242).map(|r| r.saturating_mul(512));
+    // SSD 剩余寿命：231 SSD_Life_Left、233 Media_Wearout_Indicator、177 Wear_Leveling_Count，值为剩余百分比
+    health.percentage_used = value(231)
+        .or_else(|| value(233))
+        .or_else(|| value(177))
+        .map(|left| 100u32.saturating_sub(left.min(100) as u32));
+    health.attributes = attributes;
+}
+
+/// 解析 `smartctl --json -a <设备>` 的输出
+pub fn parse_smartctl(health: &mut DiskHealth, json: &str) -> anyhow::Result<()> {
+    let v: Value = serde_json::from_str(json).context("failed to parse smartctl JSON")?;
+    let str_of = |key: &str| v.get(key).and_then(|s| s.as_str()).map(str::to_string);
+    let u64_at = |ptr: &str| v.pointer(ptr).and_then(|n| n.as_u64());
+
+    // smartctl 的退出码是位掩码，打不开设备时 JSON 里没有任何设备数据
+    if v.get("device").is_none() {
+        let message = v
+            .pointer("/smartctl/messages/0/string")
+            .and_then(|s| s.as_str())
+            .unwrap_or("no device data");
+        anyhow::bail!("smartctl: {}", message);
+    }
+
+    health.model = str_of("model_name").or(health.model.take());
+    health.serial_number = str_of("serial_number").or(health.serial_number.take());
+    let passed = v.pointer("/smart_status/passed").and_then(|p| p.as_bool());
+
+    if let Some(log) = v.get("nvme_smart_health_information_log") {
+        let field = |key: &str| log.get(key).and_then(|n| n.as_u64());
+        health.protocol = Some("nvme".to_string());
+        health.temperature_c = field("temperature").map(|t| t as f32);
+        health.available_spare = field("available_spare").map(|n| n as u32);
+        health.available_spare_threshold = field("available_spare_threshold").map(|n| n as u32);
+        health.percentage_used = field("percentage_used").map(|n| n as u32);
+        health.data_read_bytes = field("data_units_read").map(|n| n.saturating_mul(NVME_DATA_UNIT));
+        health.data_written_bytes =
+            field("data_units_written").map(|n| n.saturating_mul(NVME_DATA_UNIT));
+        health.power_cycles = field("power_cycles");
+        health.power_on_hours = field("power_on_hours");
+        health.unsafe_shutdowns = field("unsafe_shutdowns");
+        health.media_errors = field("media_errors");
+        health.error_log_entries = field("num_err_log_entries");
+        let critical_warning = field("critical_warning").map(|n| n as u8);
+        judge(health, critical_warning, passed);
+    } else if let Some(table) = v
+        .pointer("/ata_smart_attributes/table")
+        .and_then(|t| t.as_array())
+    {
+        let attributes = table
+            .iter()
+            .filter_map(|a| {
+                let num = |key: &str| a.get(key).and_then(|n| n.as_u64());
+                let id = num("id")? as u8;
+                let value = num("value")? as u8;
+                let threshold = num("thresh").map(|t| t as u8);
+                Some(SmartAttribute {
+                    id,
+                    name: a
+                        .get("name")
+                        .and_then(|n| n.as_str())
+                        .unwrap_or(ata_attribute_name(id))
+                        .to_string(),
+                    value,
+                    worst: num("worst").unwrap_or(value as u64) as u8,
+                    threshold,
+                    raw: a
+                        .pointer("/raw/value")
+                        .and_then(|r| r.as_u64())
+                        .unwrap_or(0),
+                    failing: threshold.is_some_and(|t| t > 0 && value <= t),
+                })
+            })
+            .collect();
+        health.protocol = Some("ata".to_string());
+        apply_ata_attributes(health, attributes);
+        judge(health, None, passed);
+    } else {
+        judge(health, None, passed);
+    }
+
+    // 顶层的通用字段比各协议的原始值更可靠（smartctl 已处理单位差异）
+    if let Some(t) = u64_at("/temperature/current") {
+        health.temperature_c = Some(t as f32);
+    }
+    if let Some(h) = u64_at("/power_on_time/hours") {
+        health.power_on_hours = Some(h);
+    }
+    if let Some(n) = u64_at("/power_cycle_count") {
+        health.power_cycles = Some(n);
+    }
+    health.source = Some("smartctl".to_string());
+    Ok(())
+}
+
+/// 根据已填好的字段给出判定与原因
+fn judge(health: &mut DiskHealth, critical_warning: Option<u8>, passed: Option<bool>) {
+    let mut critical = Vec::new();
+    let mut warning = Vec::new();
+
+    if let Some(bits) = critical_warning {
+        for (bit, reason) in NVME_CRITICAL_WARNINGS.iter().enumerate() {
+            if bits & (1 << bit) != 0 {
+                critical.push(reason.to_string());
+            }
+        }
+    }
+    if passed == Some(false) {
+        critical.push("SMART overall health self-assessment failed".to_string());
+    }
+    for a in health.attributes.iter().filter(|a| a.failing) {
+        critical.push(format!("attribute {} {} below threshold", a.id, a.name));
+    }
+    match health.percentage_used {
+        Some(used) if used >= 100 => critical.push(format!("{}% of rated endurance used", used)),
+        Some(used) if used >= 90 => warning.push(format!("{}% of rated endurance used", used)),
+        _ => {}
+    }
+    if let (Some(spare), Some(threshold)) =
+        (health.available_spare, health.available_spare_threshold)
+    {
+        if spare < threshold && critical_warning.is_none() {
+            critical.push("available spare below threshold".to_string());
+        }
+    }
+    if health.protocol.as_deref() == Some("nvme") {
+        if let Some(errors) = health.media_errors.filter(|n| *n > 0) {
+            warning.push(format!("{} media and data integrity errors", errors));
+        }
+    }
+    for a in &health.attributes {
+        if ATA_DEFECT_ATTRIBUTES.contains(&a.id) && a.raw > 0 && !a.failing {
+            warning.push(format!("{} {} = {}", a.id, a.name, a.raw));
+        }
+    }
+
+    let has_data = critical_warning.is_some()
+        || passed.is_some()
+        || !health.attributes.is_empty()
+        || health.percentage_used.is_some();
+    health.verdict = if !critical.is_empty() {
+        "critical"
+    } else if !warning.is_empty() {
+        "warning"
+    } else if has_data {
+        "good"
+    } else {
+        "unknown"
+    }
+    .to_string();
+    critical.extend(warning);
+    health.reasons = critical;
+}
+
+/// 常见 ATA 属性名（与 smartctl 的命名一致），厂商自定义的显示为 Unknown_Attribute
+pub fn ata_attribute_name(id: u8) -> &'static str {
+    match id {
+        1 => "Raw_Read_Error_Rate",
+        2 => "Throughput_Performance",
+        3 => "Spin_Up_Time",
+        4 => "Start_Stop_Count",
+        5 => "Reallocated_Sector_Ct",
+        7 => "Seek_Error_Rate",
+        8 => "Seek_Time_Performance",
+        9 => "Power_On_Hours",
+        10 => "Spin_Retry_Count",
+        11 => "Calibration_Retry_Count",
+        12 => "Power_Cycle_Count",
+        170 => "Available_Reservd_Space",
+        171 => "Program_Fail_Count",
+        172 => "Erase_Fail_Count",
+        173 => "Ave_Block-Erase_Count",
+        174 => "Unexpect_Power_Loss_Ct",
+        177 => "Wear_Leveling_Count",
+        179 => "Used_Rsvd_Blk_Cnt_Tot",
+        181 => "Program_Fail_Cnt_Total",
+        182 => "Erase_Fail_Count_Total",
+        183 => "Runtime_Bad_Block",
+        184 => "End-to-End_Error",
+        187 => "Reported_Uncorrect",
+        188 => "Command_Timeout",
+        189 => "High_Fly_Writes",
+        190 => "Airflow_Temperature_Cel",
+        191 => "G-Sense_Error_Rate",
+        192 => "Power-Off_Retract_Count",
+        193 => "Load_Cycle_Count",
+        194 => "Temperature_Celsius",
+        195 => "Hardware_ECC_Recovered",
+        196 => "Reallocated_Event_Count",
+        197 => "Current_Pending_Sector",
+        198 => "Offline_Uncorrectable",
+        199 => "UDMA_CRC_Error_Count",
+        200 => "Multi_Zone_Error_Rate",
+        231 => "SSD_Life_Left",
+        233 => "Media_Wearout_Indicator",
+        235 => "POR_Recovery_Count",
+        240 => "Head_Flying_Hours",
+        241 => "Total_LBAs_Written",
+        242 => "Total_LBAs_Read",
+        _ => "Unknown_Attribute",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NVME_LOG: &[u8] = include_bytes!("../fixtures/smart/nvme-health-log.bin");
+    const ATA_VALUES: &[u8] = include_bytes!("../fixtures/smart/ata-smart-values.bin");
+    const ATA_THRESHOLDS: &[u8] = include_bytes!("../fixtures/smart/ata-smart-thresholds.bin");
+    const SMARTCTL_NVME: &str = include_str!("../fixtures/smart/smartctl-nvme.json");
+    const SMARTCTL_ATA: &str = include_str!("../fixtures/smart/smartctl-ata.json");
+    const SMARTCTL_DENIED: &str = include_str!("../fixtures/smart/smartctl-permission-denied.json");
+
+    /// ATA 属性表中 `id` 对应属性项的偏移
+    fn ata_entry(page: &[u8], id: u8) -> usize {
+        (2..362).step_by(12).find(|o| page[*o] == id).unwrap()
+    }
+
+    #[test]
+    fn nvme_health_log() {
+        let mut health = DiskHealth::new("nvme0n1");
+        apply_nvme_log(&mut health, NVME_LOG).unwrap();
+        assert_eq!(health.protocol.as_deref(), Some("nvme"));
+        assert_eq!(health.verdict, "good");
+        assert!(health.reasons.is_empty());
+        // 复合温度 310 K
+        assert_eq!(health.temperature_c, Some(37.0));
+        assert_eq!(health.available_spare, Some(100));
+        assert_eq!(health.available_spare_threshold, Some(10));
+        assert_eq!(health.percentage_used, Some(3));
+        assert_eq!(health.data_read_bytes, Some(12345678 * 512 * 1000));
+        assert_eq!(health.data_written_bytes, Some(9876543 * 512 * 1000));
+        assert_eq!(health.power_cycles, Some(1523));
+        assert_eq!(health.power_on_hours, Some(4321));
+        assert_eq!(health.unsafe_shutdowns, Some(87));
+        assert_eq!(health.media_errors, Some(0));
+        assert_eq!(health.error_log_entries, Some(12));
+        assert!(health.attributes.is_empty());
+    }
+
+    #[test]
+    fn nvme_critical_warning() {
+        // bit 0 备用块不足、bit 2 可靠性下降
+        let mut page = NVME_LOG.to_vec();
+        page[0] = 0b0000_0101;
+        let mut health = DiskHealth::new("nvme0n1");
+        apply_nvme_log(&mut health, &page).unwrap();
+        assert_eq!(health.verdict, "critical");
+        assert_eq!(
+            health.reasons,
+            ["available spare below threshold", "reliability degraded"]
+        );
+
+        // 温度未报告、寿命用尽、有介质错误
+        let mut page = NVME_LOG.to_vec();
+        page[1..3].copy_from_slice(&[0, 0]);
+        page[5] = 104;
+        page[160] = 3;
+        let mut health = DiskHealth::new("nvme0n1");
+        apply_nvme_log(&mut health, &page).unwrap();
+        assert_eq!(health.temperature_c, None);
+        assert_eq!(health.verdict, "critical");
+        assert_eq!(
+            health.reasons,
+            [
+                "104% of rated endurance used",
+                "3 media and data integrity errors"
+            ]
+        );
+
+        // 寿命超过 90% 只是警告
+        let mut page = NVME_LOG.to_vec();
+        page[5] = 92;
+        let mut health = DiskHealth::new("nvme0n1");
+        apply_nvme_log(&mut health, &page).unwrap();
+        assert_eq!(health.verdict, "warning");
+
+        assert!(apply_nvme_log(&mut DiskHealth::new("nvme0n1"), &NVME_LOG[..64]).is_err());
+    }
+
+    #[test]
+    fn ata_values_and_thresholds() {
+        let mut health = DiskHealth::new("sda");
+        apply_ata_smart(&mut health, ATA_VALUES, Some(ATA_THRESHOLDS)).unwrap();
+        assert_eq!(health.protocol.as_deref(), Some("ata"));
+
+        let ids: Vec<u8> = health.attributes.iter().map(|a| a.id).collect();
+        assert_eq!(
+            ids,
+            [5, 9, 12, 177, 179, 181, 182, 183, 187, 190, 194, 195, 197, 199, 235, 241]
+        );
+        let attr = |id: u8| health.attributes.iter().find(|a| a.id == id).unwrap();
+        let realloc = attr(5);
+        assert_eq!(realloc.name, "Reallocated_Sector_Ct");
+        assert_eq!((realloc.value, realloc.worst), (100, 100));
+        assert_eq!(realloc.threshold, Some(10));
+        assert!(!realloc.failing);
+        assert_eq!(attr(190).worst, 52);
+        // 194 的原始值高位是最低 / 最高温度
+        assert_eq!(attr(194).raw, 223339610145);
+        assert!(health.attributes.iter().all(|a| !a.failing));
+
+        assert_eq!(health.temperature_c, Some(33.0));
+        assert_eq!(health.power_on_hours, Some(12034));
+        assert_eq!(health.power_cycles, Some(845));
+        assert_eq!(health.media_errors, Some(0));
+        assert_eq!(health.data_written_bytes, Some(21474836480 * 512));
+        // 177 Wear_Leveling_Count 剩余 97%
+        assert_eq!(health.percentage_used, Some(3));
+        // 有待映射扇区但属性值仍高于阈值
+        assert_eq!(health.verdict, "warning");
+        assert_eq!(health.reasons, ["197 Current_Pending_Sector = 2"]);
+    }
+
+    #[test]
+    fn ata_failing_threshold() {
+        let mut values = ATA_VALUES.to_vec();
+        let o = ata_entry(&values, 5);
+        values[o + 3] = 8;
+        values[o + 5] = 200;
+
+        let mut health = DiskHealth::new("sda");
+        apply_ata_smart(&mut health, &values, Some(ATA_THRESHOLDS)).unwrap();
+        let realloc = health.attributes.iter().find(|a| a.id == 5).unwrap();
+        assert!(realloc.failing);
+        assert_eq!(health.verdict, "critical");
+        assert_eq!(
+            health.reasons,
+            [
+                "attribute 5 Reallocated_Sector_Ct below threshold",
+                "197 Current_Pending_Sector = 2"
+            ]
+        );
+
+        // 没有阈值页时无法判断属性是否失败
+        let mut health = DiskHealth::new("sda");
+        apply_ata_smart(&mut health, &values, None).unwrap();
+        assert!(health
+            .attributes
+            .iter()
+            .all(|a| a.threshold.is_none() && !a.failing));
+        assert_eq!(health.verdict, "warning");
+
+        assert!(apply_ata_smart(&mut DiskHealth::new("sda"), &values[..100], None).is_err());
+    }
+
+    #[test]
+    fn smartctl_nvme() {
+        let mut health = DiskHealth::new("nvme0n1");
+        parse_smartctl(&mut health, SMARTCTL_NVME).unwrap();
+        assert_eq!(health.source.as_deref(), Some("smartctl"));
+        assert_eq!(health.model.as_deref(), Some("SAMSUNG MZVL2512HCJQ-00B00"));
+        assert_eq!(health.serial_number.as_deref(), Some("S675NX0T123456"));
+        assert_eq!(health.protocol.as_deref(), Some("nvme"));
+        assert_eq!(health.verdict, "good");
+        assert_eq!(health.temperature_c, Some(37.0));
+        assert_eq!(health.percentage_used, Some(3));
+        assert_eq!(health.data_read_bytes, Some(12345678 * 512 * 1000));
+        assert_eq!(health.power_on_hours, Some(4321));
+        assert_eq!(health.error_log_entries, Some(12));
+    }
+
+    #[test]
+    fn smartctl_ata_failing() {
+        let mut health = DiskHealth::new("sdb");
+        parse_smartctl(&mut health, SMARTCTL_ATA).unwrap();
+        assert_eq!(health.model.as_deref(), Some("WDC WD10EZEX-08WN4A0"));
+        assert_eq!(health.protocol.as_deref(), Some("ata"));
+        assert_eq!(health.attributes.len(), 10);
+
+        let realloc = health.attributes.iter().find(|a| a.id == 5).unwrap();
+        assert_eq!((realloc.value, realloc.threshold), (120, Some(140)));
+        assert_eq!(realloc.raw, 1496);
+        assert!(realloc.failing);
+        // 值等于阈值 0 的属性不算失败
+        assert!(
+            !health
+                .attributes
+                .iter()
+                .find(|a| a.id == 4)
+                .unwrap()
+                .failing
+        );
+
+        assert_eq!(health.verdict, "critical");
+        assert_eq!(
+            health.reasons,
+            [
+                "SMART overall health self-assessment failed",
+                "attribute 5 Reallocated_Sector_Ct below threshold",
+                "197 Current_Pending_Sector = 17",
+                "198 Offline_Uncorrectable = 9",
+            ]
+        );
+        assert_eq!(health.media_errors, Some(9));
+        assert_eq!(health.temperature_c, Some(39.0));
+        assert_eq!(health.power_on_hours, Some(33901));
+        assert_eq!(health.power_cycles, Some(2790));
+    }
+
+    #[test]
+    fn smartctl_permission_denied() {
+        let mut health = DiskHealth::new("nvme0n1");
+        let err = parse_smartctl(&mut health, SMARTCTL_DENIED).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "smartctl: Smartctl open device: /dev/nvme0n1 failed: Permission denied"
+        );
+        assert_eq!(health.verdict, "unknown");
+        assert_eq!(health.source, None);
+
+        assert!(parse_smartctl(&mut health, "not json").is_err());
+    }
+
+    #[test]
+    fn attribute_names() {
+        assert_eq!(ata_attribute_name(9), "Power_On_Hours");
+        assert_eq!(ata_attribute_name(250), "Unknown_Attribute");
+    }
+}
@@ -0,0 +1,633 @@
+//! SMBIOS 表解析（纯 Rust，不依赖平台）。
+//!
+//! 输入是 Linux `/sys/firmware/dmi/tables/DMI` 那样的原始结构表（不含入口点）。
+//! 每个结构由 4 字节头（type、length、handle）、`length` 字节的格式化区
+//! 以及紧随其后、以两个 NUL 结尾的字符串集组成；格式化区里的字符串字段是从 1 开始的序号，0 表示没有。
+//! 这里只解码主板页用到的几种结构：
+//! - type 0 BIOS
+//! - type 1 System
+//! - type 2 Baseboard
+//! - type 3 Chassis（机箱 / 系统外壳）
+//! - type 16 Physical Memory Array、type 17 Memory Device（内存页）
+
+use anyhow::anyhow;
+
+use crate::{
+    BaseboardInfo, BiosInfo, BoardInfo, ChassisInfo, MemoryLayout, MemoryModule, ProductInfo,
+};
+
+/// 表结束标记
+const END_OF_TABLE: u8 = 127;
+
+/// 主板厂商没有填写时常见的占位值
+const PLACEHOLDERS: &[&str] = &[
+    "To Be Filled By O.E.M.",
+    "To be filled by O.E.M.",
+    "Default string",
+    "System Product Name",
+    "System Serial Number",
+    "Not Applicable",
+    "Not Specified",
+    "Not Available",
+    "No Asset Information",
+    "None",
+    "0123456789",
+    "03000200-0400-0500-0006-000700080009",
+    "00000000-0000-0000-0000-000000000000",
+];
+
+pub fn is_placeholder(s: &str) -> bool {
+    PLACEHOLDERS.contains(&s)
+}
+
+/// 一个 SMBIOS 结构，借用原始表的数据
+pub struct Structure<'a> {
+    pub kind: u8,
+    /// 格式化区，包含 4 字节头
+    pub data: &'a [u8],
+    pub strings: Vec<&'a [u8]>,
+}
+
+impl Structure<'_> {
+    pub fn byte(&self, offset: usize) -> Option<u8> {
+        self.data.get(offset).copied()
+    }
+
+    pub fn word(&self, offset: usize) -> Option<u16> {
+        let b = self.data.get(offset..offset + 2)?;
+        Some(u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    pub fn dword(&self, offset: usize) -> Option<u32> {
+        let b = self.data.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub fn qword(&self, offset: usize) -> Option<u64> {
+        let b = self.data.get(offset..offset + 8)?;
+        Some(u64::from_le_bytes(b.try_into().ok()?))
+    }
+
+    /// 格式化区 `offset` 处的字符串序号对应的字符串，去掉首尾空白，空串和占位值视为没有
+    pub fn string(&self, offset: usize) -> Option<String> {
+        let index = self.byte(offset)? as usize;
+        let raw = self.strings.get(index.checked_sub(1)?)?;
+        let s = String::from_utf8_lossy(raw).trim().to_string();
+        (!s.is_empty() && !is_placeholder(&s)).then_some(s)
+    }
+}
+
+/// 把原始表切分成结构；表被截断时返回已经解析出的部分
+pub fn parse(table: &[u8]) -> anyhow::Result<Vec<Structure<'_>>> {
+    let mut structures = Vec::new();
+    let mut pos = 0;
+    while pos + 4 <= table.len() {
+        let kind = table[pos];
+        let len = table[pos + 1] as usize;
+        if len < 4 || pos + len > table.len() {
+            if structures.is_empty() {
+                return Err(anyhow!("invalid SMBIOS structure at offset {}", pos));
+            }
+            log::warn!("truncated SMBIOS table at offset {}", pos);
+            break;
+        }
+        let data = &table[pos..pos + len];
+
+        // 字符串集以两个连续的 NUL 结尾；没有字符串时格式化区后直接是两个 NUL
+        let rest = &table[pos + len..];
+        let Some(end) = rest.windows(2).position(|w| w == [0, 0]) else {
+            log::warn!("unterminated SMBIOS string set at offset {}", pos);
+            break;
+        };
+        let strings = rest[..end]
+            .split(|b| *b == 0)
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        structures.push(Structure {
+            kind,
+            data,
+            strings,
+        });
+        if kind == END_OF_TABLE {
+            break;
+        }
+        pos += len + end + 2;
+    }
+    Ok(structures)
+}
+
+/// 解码主板页需要的结构；同类结构有多个时取第一个
+pub fn decode(table: &[u8]) -> anyhow::Result<BoardInfo> {
+    let structures = parse(table)?;
+    let first = |kind: u8| structures.iter().find(|s| s.kind == kind);
+
+    Ok(BoardInfo {
+        bios: first(0).map(decode_bios),
+        system: first(1).map(decode_system),
+        baseboard: first(2).map(decode_baseboard),
+        chassis: first(3).map(decode_chassis),
+    })
+}
+
+fn decode_bios(s: &Structure) -> BiosInfo {
+    // 0xFF 表示不支持该字段
+    let revision = |major: usize, minor: usize| match (s.byte(major), s.byte(minor)) {
+        (Some(a), Some(b)) if a != 0xff && b != 0xff => Some(format!("{}.{}", a, b)),
+        _ => None,
+    };
+    // ROM 大小：64K * (n + 1)；n 为 0xFF 时使用 3.1 新增的扩展字段（bit 14-15 为单位 MB/GB）
+    let rom_size_kb = match s.byte(0x09) {
+        Some(0xff) => s.word(0x18).map(|w| {
+            let size = (w & 0x3fff) as u64;
+            match w >> 14 {
+                0 => size * 1024,
+                _ => size * 1024 * 1024,
+            }
+        }),
+        Some(n) => Some(64 * (n as u64 + 1)),
+        None => None,
+    };
+
+    BiosInfo {
+        vendor: s.string(0x04),
+        version: s.string(0x05),
+        release_date: s.string(0x08),
+        rom_size_kb,
+        bios_revision: revision(0x14, 0x15),
+        ec_revision: revision(0x16, 0x17),
+    }
+}
+
+fn decode_system(s: &Structure) -> ProductInfo {
+    ProductInfo {
+        manufacturer: s.string(0x04),
+        product_name: s.string(0x05),
+        version: s.string(0x06),
+        serial_number: s.string(0x07),
+        uuid: s
+            .data
+            .get(0x08..0x18)
+            .and_then(format_uuid)
+            .filter(|u| !is_placeholder(u)),
+        sku: s.string(0x19),
+        family: s.string(0x1a),
+    }
+}
+
+fn decode_baseboard(s: &Structure) -> BaseboardInfo {
+    BaseboardInfo {
+        manufacturer: s.string(0x04),
+        product: s.string(0x05),
+        version: s.string(0x06),
+        serial_number: s.string(0x07),
+        asset_tag: s.string(0x08),
+    }
+}
+
+fn decode_chassis(s: &Structure) -> ChassisInfo {
+    let kind = s.byte(0x05).unwrap_or(0);
+    ChassisInfo {
+        manufacturer: s.string(0x04),
+        chassis_type: chassis_type_name(kind & 0x7f).map(str::to_string),
+        lock_present: kind & 0x80 != 0,
+        version: s.string(0x06),
+        serial_number: s.string(0x07),
+        asset_tag: s.string(0x08),
+    }
+}
+
+/// 解码内存阵列与各插槽；只统计用途为系统内存的阵列（type 16 的 use 字段为 0x03）
+pub fn decode_memory(table: &[u8]) -> anyhow::Result<MemoryLayout> {
+    let structures = parse(table)?;
+    let arrays: Vec<&Structure> = structures
+        .iter()
+        .filter(|s| s.kind == 16 && s.byte(0x05) == Some(0x03))
+        .collect();
+    let modules: Vec<MemoryModule> = structures
+        .iter()
+        .filter(|s| s.kind == 17)
+        .map(decode_memory_device)
+        .collect();
+
+    let total_slots = arrays
+        .iter()
+        .filter_map(|a| a.word(0x0d))
+        .map(u32::from)
+        .reduce(|a, b| a + b)
+        .or_else(|| (!modules.is_empty()).then_some(modules.len() as u32));
+    let max_capacity = arrays
+        .iter()
+        .filter_map(|a| match a.dword(0x07)? {
+            // 0x80000000 表示使用 2.7 新增的扩展字段（字节）
+            0x8000_0000 => a.qword(0x0f),
+            kb => Some(kb as u64 * 1024),
+        })
+        .reduce(|a, b| a + b);
+
+    Ok(crate::memory::layout(modules, total_slots, max_capacity))
+}
+
+fn decode_memory_device(s: &Structure) -> MemoryModule {
+    // size：0 为空插槽，0xFFFF 为未知，bit 15 置位时单位为 KB，0x7FFF 表示使用扩展字段（MB）
+    let size_bytes = match s.word(0x0c) {
+        None | Some(0) | Some(0xffff) => None,
+        Some(0x7fff) => s
+            .dword(0x1c)
+            .map(|mb| (mb & 0x7fff_ffff) as u64 * 1024 * 1024),
+        Some(w) if w & 0x8000 != 0 => Some((w & 0x7fff) as u64 * 1024),
+        Some(w) => Some(w as u64 * 1024 * 1024),
+    };
+    // 速率：0 为未知，0xFFFF 表示使用 3.3 新增的 32 位扩展字段
+    let speed = |offset: usize, extended: usize| match s.word(offset)? {
+        0 => None,
+        0xffff => s.dword(extended).filter(|v| *v > 0),
+        v => Some(v as u32),
+    };
+
+    // 空插槽的厂商、料号等字段通常是 "Unknown"、"NO DIMM" 之类的占位文本
+    let installed = |offset: usize| s.string(offset).filter(|_| size_bytes.is_some());
+
+    MemoryModule {
+        slot: s.string(0x10),
+        bank: s.string(0x11),
+        channel: None,
+        size_bytes,
+        memory_type: s.byte(0x12).and_then(memory_type_name).map(str::to_string),
+        form_factor: s.byte(0x0e).and_then(form_factor_name).map(str::to_string),
+        speed_mts: speed(0x15, 0x54),
+        configured_speed_mts: speed(0x20, 0x58),
+        manufacturer: installed(0x17),
+        part_number: installed(0x1a),
+        serial_number: installed(0x18),
+        rank: s.byte(0x1b).map(|b| (b & 0x0f) as u32).filter(|r| *r > 0),
+    }
+}
+
+/// 内存类型编号（SMBIOS 规范 7.18.2）
+fn memory_type_name(kind: u8) -> Option<&'static str> {
+    Some(match kind {
+        0x03 => "DRAM",
+        0x0f => "SDRAM",
+        0x12 => "DDR",
+        0x13 => "DDR2",
+        0x14 => "DDR2 FB-DIMM",
+        0x18 => "DDR3",
+        0x19 => "FBD2",
+        0x1a => "DDR4",
+        0x1b => "LPDDR",
+        0x1c => "LPDDR2",
+        0x1d => "LPDDR3",
+        0x1e => "LPDDR4",
+        0x1f => "Logical non-volatile device",
+        0x20 => "HBM",
+        0x21 => "HBM2",
+        0x22 => "DDR5",
+        0x23 => "LPDDR5",
+        0x24 => "HBM3",
+        _ => return None,
+    })
+}
+
+/// 内存外形编号（SMBIOS 规范 7.18.1）
+fn form_factor_name(kind: u8) -> Option<&'static str> {
+    Some(match kind {
+        0x03 => "SIMM",
+        0x04 => "SIP",
+        0x05 => "Chip",
+        0x06 => "DIP",
+        0x07 => "ZIP",
+        0x08 => "Proprietary Card",
+        0x09 => "DIMM",
+        0x0a => "TSOP",
+        0x0b => "Row Of Chips",
+        0x0c => "RIMM",
+        0x0d => "SODIMM",
+        0x0e => "SRIMM",
+        0x0f => "FB-DIMM",
+        0x10 => "Die",
+        0x11 => "CAMM",
+        _ => return None,
+    })
+}
+
+/// SMBIOS 2.6 起 UUID 前三段按小端存储；全 0 或全 FF 表示没有设置
+fn format_uuid(b: &[u8]) -> Option<String> {
+    if b.iter().all(|x| *x == 0) || b.iter().all(|x| *x == 0xff) {
+        return None;
+    }
+    Some(format!(
+        "{:02X}{:02X}{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}-{:02X}{:02X}{:02X}{:02X}{:02X}{:02X}",
+        b[3], b[2], b[1], b[0], b[5], b[4], b[7], b[6],
+        b[8], b[9], b[10], b[11], b[12], b[13], b[14], b[15]
+    ))
+}
+
+/// 机箱类型编号（SMBIOS 规范 7.4.1），也用于 /sys/class/dmi/id/chassis_type
+pub fn chassis_type_name(kind: u8) -> Option<&'static str> {
+    const NAMES: [&str; 36] = [
+        "Other",
+        "Unknown",
+        "Desktop",
+        "Low Profile Desktop",
+        "Pizza Box",
+        "Mini Tower",
+        "Tower",
+        "Portable",
+        "Laptop",
+        "Notebook",
+        "Hand Held",
+        "Docking Station",
+        "All in One",
+        "Sub Notebook",
+        "Space-saving",
+        "Lunch Box",
+        "Main Server Chassis",
+        "Expansion Chassis",
+        "SubChassis",
+        "Bus Expansion Chassis",
+        "Peripheral Chassis",
+        "RAID Chassis",
+        "Rack Mount Chassis",
+        "Sealed-case PC",
+        "Multi-system Chassis",
+        "Compact PCI",
+        "Advanced TCA",
+        "Blade",
+        "Blade Enclosure",
+        "Tablet",
+        "Convertible",
+        "Detachable",
+        "IoT Gateway",
+        "Embedded PC",
+        "Mini PC",
+        "Stick PC",
+    ];
+    NAMES.get((kind as usize).checked_sub(1)?).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTEL_LAPTOP: &[u8] =
+        include_bytes!("../fixtures/sysfs/intel-laptop/firmware/dmi/tables/DMI");
+    const RYZEN_DESKTOP: &[u8] =
+        include_bytes!("../fixtures/sysfs/ryzen-desktop/firmware/dmi/tables/DMI");
+
+    const GIB: u64 = 1 << 30;
+
+    /// 拼出一个结构：头、格式化区（不含头）和字符串集
+    fn structure(kind: u8, body: &[u8], strings: &[&str]) -> Vec<u8> {
+        let mut out = vec![kind, 4 + body.len() as u8, 0, 0];
+        out.extend_from_slice(body);
+        for s in strings {
+            out.extend_from_slice(s.as_bytes());
+            out.push(0);
+        }
+        if strings.is_empty() {
+            out.push(0);
+        }
+        out.push(0);
+        out
+    }
+
+    #[test]
+    fn intel_laptop_board() {
+        let info = decode(INTEL_LAPTOP).unwrap();
+
+        let bios = info.bios.unwrap();
+        assert_eq!(bios.vendor.as_deref(), Some("LENOVO"));
+        assert_eq!(bios.version.as_deref(), Some("N3AET72W (1.37 )"));
+        assert_eq!(bios.release_date.as_deref(), Some("07/19/2023"));
+        assert_eq!(bios.rom_size_kb, Some(32 * 1024));
+        assert_eq!(bios.bios_revision.as_deref(), Some("1.37"));
+        assert_eq!(bios.ec_revision.as_deref(), Some("1.22"));
+
+        let system = info.system.unwrap();
+        assert_eq!(system.manufacturer.as_deref(), Some("LENOVO"));
+        assert_eq!(system.product_name.as_deref(), Some("21CB0067CD"));
+        assert_eq!(system.version.as_deref(), Some("ThinkPad X1 Carbon Gen 10"));
+        assert_eq!(system.serial_number.as_deref(), Some("PF3ABCDE"));
+        assert_eq!(
+            system.uuid.as_deref(),
+            Some("4C4C4544-0031-3510-8052-B3C04F4A3732")
+        );
+        assert_eq!(system.family.as_deref(), Some("ThinkPad X1 Carbon Gen 10"));
+
+        let board = info.baseboard.unwrap();
+        assert_eq!(board.manufacturer.as_deref(), Some("LENOVO"));
+        assert_eq!(board.product.as_deref(), Some("21CB0067CD"));
+        assert_eq!(board.version.as_deref(), Some("SDK0T76530 WIN"));
+        assert_eq!(board.serial_number.as_deref(), Some("L1HF2AB00CD"));
+        assert_eq!(board.asset_tag, None);
+
+        let chassis = info.chassis.unwrap();
+        assert_eq!(chassis.manufacturer.as_deref(), Some("LENOVO"));
+        assert_eq!(chassis.chassis_type.as_deref(), Some("Notebook"));
+        assert!(!chassis.lock_present);
+        assert_eq!(chassis.serial_number.as_deref(), Some("PF3ABCDE"));
+    }
+
+    #[test]
+    fn intel_laptop_memory() {
+        let layout = decode_memory(INTEL_LAPTOP).unwrap();
+        assert_eq!(layout.total_slots, Some(8));
+        assert_eq!(layout.populated_slots, 8);
+        assert_eq!(layout.channels_populated, Some(8));
+        assert_eq!(layout.max_capacity_bytes, Some(64 * GIB));
+        assert_eq!(layout.installed_bytes, 16 * GIB);
+
+        // 板载 LPDDR5：两个控制器各四个通道，每个通道 2 GB
+        assert_eq!(layout.modules.len(), 8);
+        for (i, m) in layout.modules.iter().enumerate() {
+            let (controller, channel) = (i / 4, ["A", "B", "C", "D"][i % 4]);
+            assert_eq!(
+                m.slot.as_deref(),
+                Some(format!("Controller{}-Channel{}", controller, channel).as_str())
+            );
+            assert_eq!(m.bank.as_deref(), Some("BANK 0"));
+            assert_eq!(
+                m.channel.as_deref(),
+                Some(format!("{}-{}", controller, channel).as_str())
+            );
+            assert_eq!(m.size_bytes, Some(2 * GIB));
+            assert_eq!(m.memory_type.as_deref(), Some("LPDDR5"));
+            assert_eq!(m.form_factor.as_deref(), Some("Row Of Chips"));
+            assert_eq!(m.speed_mts, Some(5200));
+            assert_eq!(m.configured_speed_mts, Some(5200));
+            assert_eq!(m.manufacturer.as_deref(), Some("Samsung"));
+            assert_eq!(m.part_number.as_deref(), Some("K3LKBKB0BM-MGCP"));
+            assert_eq!(m.serial_number.as_deref(), Some("00000000"));
+            assert_eq!(m.rank, Some(2));
+        }
+    }
+
+    #[test]
+    fn ryzen_desktop_board() {
+        let info = decode(RYZEN_DESKTOP).unwrap();
+
+        let bios = info.bios.unwrap();
+        assert_eq!(
+            bios.vendor.as_deref(),
+            Some("American Megatrends International, LLC.")
+        );
+        assert_eq!(bios.version.as_deref(), Some("P2.10"));
+        assert_eq!(bios.release_date.as_deref(), Some("10/27/2022"));
+        // ROM 大小字段为 0xFF，使用扩展字段里的 16 MB
+        assert_eq!(bios.rom_size_kb, Some(16 * 1024));
+        assert_eq!(bios.bios_revision.as_deref(), Some("5.17"));
+        assert_eq!(bios.ec_revision, None);
+
+        // 整机信息全是 "To Be Filled By O.E.M." 和默认 UUID
+        let system = info.system.unwrap();
+        assert_eq!(system.manufacturer, None);
+        assert_eq!(system.product_name, None);
+        assert_eq!(system.serial_number, None);
+        assert_eq!(system.uuid, None);
+        assert_eq!(system.sku, None);
+        assert_eq!(system.family, None);
+
+        let board = info.baseboard.unwrap();
+        assert_eq!(board.manufacturer.as_deref(), Some("ASRock"));
+        assert_eq!(board.product.as_deref(), Some("B550M Pro4"));
+        assert_eq!(board.version, None);
+        assert_eq!(board.serial_number.as_deref(), Some("M80-D3012300456"));
+
+        let chassis = info.chassis.unwrap();
+        assert_eq!(chassis.manufacturer, None);
+        assert_eq!(chassis.chassis_type.as_deref(), Some("Desktop"));
+        assert_eq!(chassis.asset_tag, None);
+    }
+
+    #[test]
+    fn ryzen_desktop_memory() {
+        let layout = decode_memory(RYZEN_DESKTOP).unwrap();
+        assert_eq!(layout.total_slots, Some(4));
+        assert_eq!(layout.populated_slots, 2);
+        assert_eq!(layout.channels_populated, Some(2));
+        assert_eq!(layout.max_capacity_bytes, Some(128 * GIB));
+        assert_eq!(layout.installed_bytes, 32 * GIB);
+
+        let slots: Vec<(Option<&str>, Option<&str>)> = layout
+            .modules
+            .iter()
+            .map(|m| (m.slot.as_deref(), m.channel.as_deref()))
+            .collect();
+        assert_eq!(
+            slots,
+            [
+                (Some("DIMM 0"), Some("A")),
+                (Some("DIMM 1"), Some("A")),
+                (Some("DIMM 0"), Some("B")),
+                (Some("DIMM 1"), Some("B")),
+            ]
+        );
+
+        for (m, serial) in [
+            (&layout.modules[0], "00000000"),
+            (&layout.modules[2], "00000002"),
+        ] {
+            assert_eq!(m.size_bytes, Some(16 * GIB));
+            assert_eq!(m.memory_type.as_deref(), Some("DDR4"));
+            assert_eq!(m.form_factor.as_deref(), Some("DIMM"));
+            assert_eq!(m.speed_mts, Some(3200));
+            assert_eq!(m.configured_speed_mts, Some(3200));
+            assert_eq!(m.manufacturer.as_deref(), Some("Corsair"));
+            assert_eq!(m.part_number.as_deref(), Some("CMK32GX4M2E3200C16"));
+            assert_eq!(m.serial_number.as_deref(), Some(serial));
+            assert_eq!(m.rank, Some(2));
+        }
+        // 空插槽只保留位置信息
+        for m in [&layout.modules[1], &layout.modules[3]] {
+            assert_eq!(m.size_bytes, None);
+            assert_eq!(m.memory_type, None);
+            assert_eq!(m.manufacturer, None);
+            assert_eq!(m.part_number, None);
+            assert_eq!(m.rank, None);
+        }
+    }
+
+    #[test]
+    fn truncated_structure() {
+        // 第一个结构就越界：整张表无效
+        assert!(parse(&[0, 0x1a, 0, 0, 1, 2]).is_err());
+        assert!(parse(&[0, 2, 0, 0, 0, 0]).is_err());
+
+        // 后面的结构被截断：保留已经解析出的部分
+        let mut table = structure(2, &[1, 2], &["ASRock", "B550M Pro4"]);
+        table.extend_from_slice(&[3, 0x16, 0, 0, 1]);
+        let structures = parse(&table).unwrap();
+        assert_eq!(structures.len(), 1);
+        assert_eq!(structures[0].kind, 2);
+
+        let board = decode(&table).unwrap().baseboard.unwrap();
+        assert_eq!(board.manufacturer.as_deref(), Some("ASRock"));
+        assert_eq!(board.product.as_deref(), Some("B550M Pro4"));
+        // 格式化区太短，越界的字段视为没有
+        assert_eq!(board.version, None);
+    }
+
+    #[test]
+    fn missing_string_terminator() {
+        let mut table = structure(0, &[1, 0, 0, 0], &["LENOVO"]);
+        table.extend_from_slice(&[2, 5, 0, 0, 1, b'X', b'1', 0]);
+        let structures = parse(&table).unwrap();
+        assert_eq!(structures.len(), 1);
+        assert_eq!(structures[0].strings, [b"LENOVO".as_slice()]);
+
+        let info = decode(&table).unwrap();
+        assert_eq!(info.bios.unwrap().vendor.as_deref(), Some("LENOVO"));
+        assert!(info.baseboard.is_none());
+    }
+
+    #[test]
+    fn stops_at_end_of_table() {
+        let mut table = structure(2, &[1], &["ASRock"]);
+        table.extend(structure(END_OF_TABLE, &[], &[]));
+        table.extend(structure(2, &[1], &["Other"]));
+        let kinds: Vec<u8> = parse(&table).unwrap().iter().map(|s| s.kind).collect();
+        assert_eq!(kinds, [2, END_OF_TABLE]);
+    }
+
+    #[test]
+    fn placeholder_strings() {
+        assert!(is_placeholder("To Be Filled By O.E.M."));
+        assert!(is_placeholder("Default string"));
+        assert!(is_placeholder("03000200-0400-0500-0006-000700080009"));
+        assert!(!is_placeholder("ASRock"));
+        assert!(!is_placeholder("to be filled"));
+
+        let table = structure(
+            2,
+            &[1, 2, 3, 4, 5],
+            &[
+                "  ASUSTeK COMPUTER INC. ",
+                "Default string",
+                " ",
+                "None",
+                "Rev 1.xx",
+            ],
+        );
+        let board = decode(&table).unwrap().baseboard.unwrap();
+        assert_eq!(board.manufacturer.as_deref(), Some("ASUSTeK COMPUTER INC."));
+        assert_eq!(board.product, None);
+        assert_eq!(board.version, None);
+        assert_eq!(board.serial_number, None);
+        assert_eq!(board.asset_tag.as_deref(), Some("Rev 1.xx"));
+    }
+
+    #[test]
+    fn chassis_types() {
+        assert_eq!(chassis_type_name(3), Some("Desktop"));
+        assert_eq!(chassis_type_name(10), Some("Notebook"));
+        assert_eq!(chassis_type_name(36), Some("Stick PC"));
+        assert_eq!(chassis_type_name(0), None);
+        assert_eq!(chassis_type_name(37), None);
+
+        let table = structure(3, &[0, 0x83], &[]);
+        let chassis = decode(&table).unwrap().chassis.unwrap();
+        assert_eq!(chassis.chassis_type.as_deref(), Some("Desktop"));
+        assert!(chassis.lock_present);
+    }
+}
@@ -0,0 +1,452 @@
+//! 指标的磁盘存储（SQLite，位于应用数据目录）。
+//!
+//! 分三级保存：
+//! - `samples_raw`：原始采样，保留 1 天
+//! - `samples_1m`：1 分钟汇总（min/avg/max/count），保留 30 天
+//! - `samples_1h`：1 小时汇总，保留 1 年
+//!
+//! 后台压缩任务定期把已结束的分钟/小时汇总到上一级并清理过期数据；
+//! 查询时按时间段从不同精度拼接，调用方不需要关心数据在哪一级。
+
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::history::{flatten, merge_buckets, Bucket};
+use crate::SystemMetrics;
+
+pub const DB_FILE: &str = "metrics.db";
+
+const MINUTE_MS: u64 = 60 * 1000;
+const HOUR_MS: u64 = 60 * MINUTE_MS;
+const DAY_MS: u64 = 24 * HOUR_MS;
+
+pub const RAW_RETENTION_MS: u64 = DAY_MS;
+pub const MINUTE_RETENTION_MS: u64 = 30 * DAY_MS;
+pub const HOUR_RETENTION_MS: u64 = 365 * DAY_MS;
+
+/// 压缩任务的执行间隔
+pub const COMPACTION_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+pub type SharedStore = Arc<MetricsStore>;
+
+pub struct MetricsStore {
+    conn: Mutex<Connection>,
+}
+
+impl MetricsStore {
+    pub fn open(path: &Path) -> anyhow::Result<Self> {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .with_context(|| format!("failed to create {}", dir.display()))?;
+        }
+        let conn =
+            Connection::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        Self::init(conn)
+    }
+
+    /// 数据目录不可用时的退路：只在本次运行内保存
+    pub fn open_in_memory() -> anyhow::Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> anyhow::Result<Self> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS samples_raw (
+                 metric TEXT NOT NULL,
+                 ts INTEGER NOT NULL,
+                 value REAL NOT NULL
+             );
+             CREATE INDEX IF NOT EXISTS samples_raw_metric_ts ON samples_raw (metric, ts);
+             CREATE TABLE IF NOT EXISTS samples_1m (
+                 metric TEXT NOT NULL,
+                 ts INTEGER NOT NULL,
+                 min REAL NOT NULL,
+                 avg REAL NOT NULL,
+                 max REAL NOT NULL,
+                 count INTEGER NOT NULL,
+                 PRIMARY KEY (metric, ts)
+             );
+             CREATE TABLE IF NOT EXISTS samples_1h (
+                 metric TEXT NOT NULL,
+                 ts INTEGER NOT NULL,
+                 min REAL NOT NULL,
+                 avg REAL NOT NULL,
+                 max REAL NOT NULL,
+                 count INTEGER NOT NULL,
+                 PRIMARY KEY (metric, ts)
+             );
+             CREATE TABLE IF NOT EXISTS meta (
+                 key TEXT PRIMARY KEY,
+                 value INTEGER NOT NULL
+             );",
+        )
+        .context("failed to create metrics tables")?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// 写入一次采样的全部指标
+    pub fn insert(&self, m: &SystemMetrics) -> anyhow::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+        {
+            let mut stmt = tx.prepare_cached(
+                "INSERT INTO samples_raw (metric, ts, value) VALUES (?1, ?2, ?3)",
+            )?;
+            for (metric, value) in flatten(m) {
+                stmt.execute(params![metric, m.timestamp_ms as i64, value])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 汇总已结束的分钟/小时并删除超出保留期的数据
+    pub fn compact(&self, now_ms: u64) -> anyhow::Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.transaction()?;
+
+        // raw -> 1m：只汇总已经结束的分钟
+        let minute_end = now_ms - now_ms % MINUTE_MS;
+        let minute_from = read_watermark(&tx, "rollup_1m_until")?;
+        tx.execute(
+            "INSERT OR REPLACE INTO samples_1m (metric, ts, min, avg, max, count)
+             SELECT metric, (ts / ?1) * ?1, MIN(value), AVG(value), MAX(value), COUNT(*)
+             FROM samples_raw WHERE ts >= ?2 AND ts < ?3
+             GROUP BY metric, (ts / ?1) * ?1",
+            params![MINUTE_MS as i64, minute_from as i64, minute_end as i64],
+        )?;
+        write_watermark(&tx, "rollup_1m_until", minute_end)?;
+
+        // 1m -> 1h：平均值按样本数加权
+        let hour_end = now_ms - now_ms % HOUR_MS;
+        let hour_from = read_watermark(&tx, "rollup_1h_until")?;
+        tx.execute(
+            "INSERT OR REPLACE INTO samples_1h (metric, ts, min, avg, max, count)
+             SELECT metric, (ts / ?1) * ?1, MIN(min), SUM(avg * count) / SUM(count), MAX(max),
+                    SUM(count)
+             FROM samples_1m WHERE ts >= ?2 AND ts < ?3
+             GROUP BY metric, (ts / ?1) * ?1",
+            params![HOUR_MS as i64, hour_from as i64, hour_end as i64],
+        )?;
+        write_watermark(&tx, "rollup_1h_until", hour_end)?;
+
+        // 清理过期数据
+        tx.execute(
+            "DELETE FROM samples_raw WHERE ts < ?1",
+            params![now_ms.saturating_sub(RAW_RETENTION_MS) as i64],
+        )?;
+        tx.execute(
+            "DELETE FROM samples_1m WHERE ts < ?1",
+            params![now_ms.saturating_sub(MINUTE_RETENTION_MS) as i64],
+        )?;
+        tx.execute(
+            "DELETE FROM samples_1h WHERE ts < ?1",
+            params![now_ms.saturating_sub(HOUR_RETENTION_MS) as i64],
+        )?;
+
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// 查询 [since_ms, until_ms) 的数据，最近 1 天取原始数据、30 天内取分钟汇总、
+    /// 更早取小时汇总，再统一合并到 `resolution_ms` 宽的桶
+    pub fn query(
+        &self,
+        metric: &str,
+        since_ms: u64,
+        until_ms: u64,
+        resolution_ms: u64,
+        now_ms: u64,
+    ) -> anyhow::Result<Vec<Bucket>> {
+        // 各级的分界对齐到上一级的桶边界，避免同一时间段被重复统计
+        let raw_start = align_up(now_ms.saturating_sub(RAW_RETENTION_MS), MINUTE_MS);
+        let minute_start = align_up(now_ms.saturating_sub(MINUTE_RETENTION_MS), HOUR_MS);
+
+        let conn = self.conn.lock().unwrap();
+        let mut buckets = query_rollup(
+            &conn,
+            "samples_1h",
+            metric,
+            since_ms,
+            until_ms.min(minute_start),
+        )?;
+        buckets.extend(query_rollup(
+            &conn,
+            "samples_1m",
+            metric,
+            since_ms.max(minute_start),
+            until_ms.min(raw_start),
+        )?);
+
+        let mut stmt = conn.prepare_cached(
+            "SELECT ts, value FROM samples_raw
+             WHERE metric = ?1 AND ts >= ?2 AND ts < ?3 ORDER BY ts",
+        )?;
+        let raw = stmt.query_map(
+            params![metric, since_ms.max(raw_start) as i64, until_ms as i64],
+            |row| {
+                let value: f64 = row.get(1)?;
+                Ok(Bucket {
+                    timestamp_ms: row.get::<_, i64>(0)? as u64,
+                    min: value,
+                    avg: value,
+                    max: value,
+                    count: 1,
+                })
+            },
+        )?;
+        for b in raw {
+            buckets.push(b?);
+        }
+
+        Ok(merge_buckets(buckets, resolution_ms))
+    }
+}
+
+fn align_up(ts: u64, step: u64) -> u64 {
+    ts.div_ceil(step) * step
+}
+
+fn read_watermark(conn: &Connection, key: &str) -> anyhow::Result<u64> {
+    let v: Option<i64> = conn
+        .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+            row.get(0)
+        })
+        .optional()?;
+    Ok(v.unwrap_or(0) as u64)
+}
+
+fn write_watermark(conn: &Connection, key: &str, value: u64) -> anyhow::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)",
+        params![key, value as i64],
+    )?;
+    Ok(())
+}
+
+fn query_rollup(
+    conn: &Connection,
+    table: &str,
+    metric: &str,
+    since_ms: u64,
+    until_ms: u64,
+) -> anyhow::Result<Vec<Bucket>> {
+    if since_ms >= until_ms {
+        return Ok(Vec::new());
+    }
+    let mut stmt = conn.prepare_cached(&format!(
+        "SELECT ts, min, avg, max, count FROM {}
+         WHERE metric = ?1 AND ts >= ?2 AND ts < ?3 ORDER BY ts",
+        table
+    ))?;
+    let rows = stmt.query_map(params![metric, since_ms as i64, until_ms as i64], |row| {
+        Ok(Bucket {
+            timestamp_ms: row.get::<_, i64>(0)? as u64,
+            min: row.get(1)?,
+            avg: row.get(2)?,
+            max: row.get(3)?,
+            count: row.get::<_, i64>(4)? as usize,
+        })
+    })?;
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
+pub fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_millis() as u64
+}
+
+/// 启动定期压缩任务
+pub fn spawn_compaction(store: SharedStore) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let s = store.clone();
+            match tauri::async_runtime::spawn_blocking(move || s.compact(now_ms())).await {
+                Ok(Err(e)) => log::error!("metrics store compaction failed: {:?}", e),
+                Err(e) => log::error!("metrics store compaction task failed: {:?}", e),
+                Ok(Ok(())) => {}
+            }
+            tokio::time::sleep(COMPACTION_INTERVAL).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::sample_metrics;
+
+    /// 对齐到天的起点，方便推算各级分界
+    const BASE: u64 = 20_000 * DAY_MS;
+    const SIX_HOURS: u64 = 6 * HOUR_MS;
+
+    fn count(store: &MetricsStore, table: &str) -> i64 {
+        store
+            .conn
+            .lock()
+            .unwrap()
+            .query_row(
+                &format!("SELECT COUNT(*) FROM {} WHERE metric = 'cpu'", table),
+                [],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+
+    /// 每 6 小时一次采样（落在该小时第 90 秒），共 32 天，cpu 值为序号
+    fn store_with_month() -> (MetricsStore, u64) {
+        let store = MetricsStore::open_in_memory().unwrap();
+        for i in 0..=128 {
+            store
+                .insert(&sample_metrics(BASE + i * SIX_HOURS + 90_000, i as f32))
+                .unwrap();
+        }
+        (store, BASE + 32 * DAY_MS + 2 * HOUR_MS)
+    }
+
+    #[test]
+    fn query_stitches_tiers() {
+        let (store, now) = store_with_month();
+        store.compact(now).unwrap();
+
+        let buckets = store.query("cpu", 0, u64::MAX / 2, 0, now).unwrap();
+        let values: Vec<f64> = buckets.iter().map(|b| b.avg).collect();
+        let expected: Vec<f64> = (0..=128).map(|i| i as f64).collect();
+        // 每个采样恰好出现一次
+        assert_eq!(values, expected);
+        assert!(buckets.iter().all(|b| b.count == 1));
+
+        // 30 天前的分界（BASE + 2d + 2h）之前来自小时汇总，
+        // 1 天前的分界（BASE + 31d + 2h）之后来自原始数据，中间是分钟汇总
+        let tier = |b: &Bucket| match b.timestamp_ms % HOUR_MS {
+            0 => "1h",
+            60_000 => "1m",
+            90_000 => "raw",
+            _ => "?",
+        };
+        let tiers: Vec<&str> = buckets.iter().map(tier).collect();
+        assert_eq!(tiers.iter().filter(|&&t| t == "1h").count(), 9);
+        assert_eq!(tiers.iter().filter(|&&t| t == "1m").count(), 116);
+        assert_eq!(tiers.iter().filter(|&&t| t == "raw").count(), 4);
+        assert_eq!(tiers[8], "1h");
+        assert_eq!(tiers[9], "1m");
+        assert_eq!(tiers[124], "1m");
+        assert_eq!(tiers[125], "raw");
+
+        // 按天合并后样本总数不变
+        let daily = store.query("cpu", 0, u64::MAX / 2, DAY_MS, now).unwrap();
+        assert_eq!(daily.len(), 33);
+        assert_eq!(daily.iter().map(|b| b.count).sum::<usize>(), 129);
+        assert_eq!(daily[0].timestamp_ms, BASE);
+        assert_eq!((daily[0].min, daily[0].avg, daily[0].max), (0.0, 1.5, 3.0));
+
+        // 时间窗口落在单一级别内
+        let window = store
+            .query("cpu", BASE + 10 * DAY_MS, BASE + 11 * DAY_MS, 0, now)
+            .unwrap();
+        let values: Vec<f64> = window.iter().map(|b| b.avg).collect();
+        assert_eq!(values, [40.0, 41.0, 42.0, 43.0]);
+        assert!(store
+            .query("gpu", 0, u64::MAX / 2, 0, now)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn compaction_is_idempotent() {
+        let (store, now) = store_with_month();
+        store.compact(now).unwrap();
+        let first = store.query("cpu", 0, u64::MAX / 2, 0, now).unwrap();
+        let rows = (count(&store, "samples_1m"), count(&store, "samples_1h"));
+
+        store.compact(now).unwrap();
+        let second = store.query("cpu", 0, u64::MAX / 2, 0, now).unwrap();
+        assert_eq!(
+            (count(&store, "samples_1m"), count(&store, "samples_1h")),
+            rows
+        );
+        let summary = |buckets: &[Bucket]| -> Vec<(u64, f64, usize)> {
+            buckets
+                .iter()
+                .map(|b| (b.timestamp_ms, b.avg, b.count))
+                .collect()
+        };
+        assert_eq!(summary(&first), summary(&second));
+
+        let conn = store.conn.lock().unwrap();
+        assert_eq!(
+            read_watermark(&conn, "rollup_1m_until").unwrap(),
+            now - now % MINUTE_MS
+        );
+        assert_eq!(
+            read_watermark(&conn, "rollup_1h_until").unwrap(),
+            now - now % HOUR_MS
+        );
+    }
+
+    #[test]
+    fn open_minute_not_rolled_up() {
+        let store = MetricsStore::open_in_memory().unwrap();
+        let minute = BASE + 10 * MINUTE_MS;
+        store.insert(&sample_metrics(minute + 1_000, 1.0)).unwrap();
+        store.compact(minute + 30_000).unwrap();
+        assert_eq!(count(&store, "samples_1m"), 0);
+
+        // 分钟结束后再压缩，后来写入的样本也要算进去
+        store.insert(&sample_metrics(minute + 40_000, 3.0)).unwrap();
+        store.compact(minute + MINUTE_MS).unwrap();
+        let conn = store.conn.lock().unwrap();
+        let (avg, n): (f64, i64) = conn
+            .query_row(
+                "SELECT avg, count FROM samples_1m WHERE metric = 'cpu' AND ts = ?1",
+                [minute as i64],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((avg, n), (2.0, 2));
+    }
+
+    #[test]
+    fn retention_drops_expired_rows() {
+        let (store, now) = store_with_month();
+        assert_eq!(count(&store, "samples_raw"), 129);
+        store.compact(now).unwrap();
+
+        let conn = store.conn.lock().unwrap();
+        let oldest = |table: &str| -> i64 {
+            conn.query_row(&format!("SELECT MIN(ts) FROM {}", table), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+        };
+        assert!(oldest("samples_raw") as u64 >= now - RAW_RETENTION_MS);
+        assert!(oldest("samples_1m") as u64 >= now - MINUTE_RETENTION_MS);
+        assert_eq!(oldest("samples_1h") as u64, BASE);
+        drop(conn);
+        assert_eq!(count(&store, "samples_raw"), 4);
+        assert_eq!(count(&store, "samples_1m"), 120);
+        assert_eq!(count(&store, "samples_1h"), 129);
+
+        // 一年以后小时汇总也被清掉
+        store.compact(now + HOUR_RETENTION_MS + DAY_MS).unwrap();
+        assert_eq!(count(&store, "samples_raw"), 0);
+        assert_eq!(count(&store, "samples_1m"), 0);
+        assert_eq!(count(&store, "samples_1h"), 0);
+    }
+
+    #[test]
+    fn align_up_boundaries() {
+        assert_eq!(align_up(0, MINUTE_MS), 0);
+        assert_eq!(align_up(MINUTE_MS, MINUTE_MS), MINUTE_MS);
+        assert_eq!(align_up(MINUTE_MS + 1, MINUTE_MS), 2 * MINUTE_MS);
+    }
+}